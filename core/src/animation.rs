@@ -0,0 +1,505 @@
+use std::{convert::TryInto, io::Cursor, time::Duration};
+
+use image::{
+	codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
+	AnimationDecoder, ImageResult,
+};
+
+pub use image::RgbaImage;
+
+/// Bytes at the start of an image enough to tell whether it is animated, in all but odd files.
+pub const HEADER_BYTES: usize = 64 * 1024;
+
+/// MIME types of images which can hold animations.
+const ANIMATABLE_TYPES: [&str; 4] = ["image/apng", "image/gif", "image/png", "image/webp"];
+
+/// Browsers show GIF frames with a delay of 10ms or less for this long instead.
+const GIF_MIN_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Container {
+	Gif,
+	Png,
+	WebP,
+}
+
+/// Frame timing of an animated GIF, APNG or WebP image.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Animation {
+	container: Container,
+	/// Display time of every frame, in order.
+	pub frames: Vec<Duration>,
+	/// Number of times the animation plays, or `None` if it loops forever.
+	pub plays: Option<u32>,
+}
+
+impl Animation {
+	/// Reads the frame timing of an image, if it is animated.
+	pub fn parse(bytes: &[u8]) -> Option<Self> {
+		let animation = if bytes.starts_with(b"GIF8") {
+			parse_gif(bytes)
+		} else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+			parse_png(bytes)
+		} else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+			parse_webp(bytes)
+		} else {
+			None
+		}?;
+		if animation.frames.len() > 1 {
+			Some(animation)
+		} else {
+			None
+		}
+	}
+
+	/// Length of a single loop.
+	pub fn duration(&self) -> Duration {
+		self.frames.iter().sum()
+	}
+
+	/// Time it takes to play the given number of loops, or fewer if the animation stops before.
+	pub fn play_time(&self, loops: u32) -> Duration {
+		let loops = self.plays.map_or(loops, |plays| loops.min(plays));
+		self.duration() * loops
+	}
+
+	/// Index of the frame showing after the animation played for the given time.
+	pub fn frame_at(&self, elapsed: Duration) -> usize {
+		let duration = self.duration();
+		if duration.is_zero() || self.plays.is_some_and(|plays| elapsed >= duration * plays) {
+			return self.frames.len() - 1;
+		}
+		let mut elapsed = Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64);
+		for (i, frame) in self.frames.iter().enumerate() {
+			if elapsed < *frame {
+				return i;
+			}
+			elapsed -= *frame;
+		}
+		self.frames.len() - 1
+	}
+
+	/// Time from the start of a loop until the given frame shows.
+	pub fn frame_start(&self, frame: usize) -> Duration {
+		self.frames.iter().take(frame).sum()
+	}
+
+	/// Decodes every frame of the animation, composited onto the full canvas.
+	pub fn decode(&self, bytes: &[u8]) -> ImageResult<Vec<RgbaImage>> {
+		let frames = match self.container {
+			Container::Gif => GifDecoder::new(Cursor::new(bytes))?
+				.into_frames()
+				.collect_frames()?,
+			Container::Png => PngDecoder::new(Cursor::new(bytes))?
+				.apng()?
+				.into_frames()
+				.collect_frames()?,
+			Container::WebP => WebPDecoder::new(Cursor::new(bytes))?
+				.into_frames()
+				.collect_frames()?,
+		};
+		Ok(
+			frames
+				.into_iter()
+				.map(|frame| frame.into_buffer())
+				.collect(),
+		)
+	}
+}
+
+/// Whether images of a MIME type can hold animations.
+pub fn animatable(mime: &str) -> bool {
+	ANIMATABLE_TYPES.contains(&mime)
+}
+
+/// Whether an image starting with the given bytes may be animated, so that still images need not
+/// be read whole. Headers ending before this is known count as animated.
+pub fn may_be_animated(header: &[u8]) -> bool {
+	if header.starts_with(b"GIF8") {
+		// Parsing only gets through GIFs which end within the header.
+		parse_gif(header).is_none_or(|animation| animation.frames.len() > 1)
+	} else if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+		png_animated(header).unwrap_or(true)
+	} else if header.starts_with(b"RIFF") && header.get(8..12) == Some(b"WEBP") {
+		// Only extended WebP images can be animated, which they flag in their first chunk.
+		header.get(12..16) == Some(b"VP8X") && header.get(20).is_none_or(|flags| flags & 0x02 != 0)
+	} else {
+		false
+	}
+}
+
+fn u16_le(bytes: &[u8], at: usize) -> Option<u16> {
+	Some(u16::from_le_bytes(
+		bytes.get(at..at.checked_add(2)?)?.try_into().ok()?,
+	))
+}
+
+fn u16_be(bytes: &[u8], at: usize) -> Option<u16> {
+	Some(u16::from_be_bytes(
+		bytes.get(at..at.checked_add(2)?)?.try_into().ok()?,
+	))
+}
+
+fn u24_le(bytes: &[u8], at: usize) -> Option<u32> {
+	let b = bytes.get(at..at.checked_add(3)?)?;
+	Some(u32::from(b[0]) | u32::from(b[1]) << 8 | u32::from(b[2]) << 16)
+}
+
+fn u32_le(bytes: &[u8], at: usize) -> Option<u32> {
+	Some(u32::from_le_bytes(
+		bytes.get(at..at.checked_add(4)?)?.try_into().ok()?,
+	))
+}
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+	Some(u32::from_be_bytes(
+		bytes.get(at..at.checked_add(4)?)?.try_into().ok()?,
+	))
+}
+
+/// Skips a run of GIF data sub-blocks, returning the position after the terminator.
+fn skip_sub_blocks(bytes: &[u8], mut at: usize) -> Option<usize> {
+	loop {
+		let size = *bytes.get(at)? as usize;
+		at += 1 + size;
+		if size == 0 {
+			return Some(at);
+		}
+	}
+}
+
+fn color_table_size(packed: u8) -> usize {
+	if packed & 0x80 != 0 {
+		3 << ((packed & 0x07) + 1)
+	} else {
+		0
+	}
+}
+
+fn parse_gif(bytes: &[u8]) -> Option<Animation> {
+	let mut at = 13 + color_table_size(*bytes.get(10)?);
+	let mut frames = Vec::new();
+	// Without a NETSCAPE2.0 extension GIFs play once.
+	let mut plays = Some(1);
+	let mut delay = Duration::ZERO;
+	loop {
+		match *bytes.get(at)? {
+			0x21 => {
+				let label = *bytes.get(at + 1)?;
+				let data = at + 2;
+				match label {
+					0xf9 => {
+						let centiseconds = u16_le(bytes, data + 2)?;
+						delay = Duration::from_millis(u64::from(centiseconds) * 10);
+					}
+					0xff if bytes.get(data..data + 12) == Some(b"\x0bNETSCAPE2.0") => {
+						let loops = u16_le(bytes, data + 14)?;
+						plays = if loops == 0 {
+							None
+						} else {
+							Some(u32::from(loops) + 1)
+						};
+					}
+					_ => {}
+				}
+				at = skip_sub_blocks(bytes, data)?;
+			}
+			0x2c => {
+				let packed = *bytes.get(at + 9)?;
+				at = skip_sub_blocks(bytes, at + 11 + color_table_size(packed))?;
+				frames.push(if delay <= Duration::from_millis(10) {
+					GIF_MIN_DELAY
+				} else {
+					delay
+				});
+				delay = Duration::ZERO;
+			}
+			_ => break,
+		}
+	}
+	Some(Animation {
+		container: Container::Gif,
+		frames,
+		plays,
+	})
+}
+
+/// Whether a PNG is animated, which it tells before its image data, or `None` if the bytes end
+/// first.
+fn png_animated(bytes: &[u8]) -> Option<bool> {
+	let mut at = 8;
+	loop {
+		let length = u32_be(bytes, at)?;
+		match bytes.get(at + 4..at + 8)? {
+			b"acTL" => return Some(true),
+			b"IDAT" => return Some(false),
+			_ => {}
+		}
+		at = (at + 8).checked_add(length as usize)?.checked_add(4)?;
+	}
+}
+
+fn parse_png(bytes: &[u8]) -> Option<Animation> {
+	let mut at = 8;
+	let mut frames = Vec::new();
+	let mut plays = None;
+	let mut animated = false;
+	while let Some(length) = u32_be(bytes, at) {
+		let data = at + 8;
+		match bytes.get(at + 4..at + 8)? {
+			b"acTL" => {
+				animated = true;
+				let count = u32_be(bytes, data + 4)?;
+				plays = if count == 0 { None } else { Some(count) };
+			}
+			b"fcTL" => {
+				let numerator = u16_be(bytes, data + 20)?;
+				let denominator = match u16_be(bytes, data + 22)? {
+					0 => 100,
+					d => d,
+				};
+				frames.push(Duration::from_secs_f64(
+					f64::from(numerator) / f64::from(denominator),
+				));
+			}
+			b"IEND" => break,
+			_ => {}
+		}
+		// Lengths come from the file, so a bogus one must neither wrap around nor run past its end.
+		at = data
+			.checked_add(length as usize)?
+			.checked_add(4)
+			.filter(|&end| end <= bytes.len())?;
+	}
+	if !animated {
+		return None;
+	}
+	Some(Animation {
+		container: Container::Png,
+		frames,
+		plays,
+	})
+}
+
+fn parse_webp(bytes: &[u8]) -> Option<Animation> {
+	let mut at: usize = 12;
+	let mut frames = Vec::new();
+	let mut plays = None;
+	while let Some(length) = u32_le(bytes, at.checked_add(4)?) {
+		let data = at + 8;
+		match bytes.get(at..at + 4)? {
+			b"ANIM" => {
+				let count = u16_le(bytes, data + 4)?;
+				plays = if count == 0 {
+					None
+				} else {
+					Some(u32::from(count))
+				};
+			}
+			b"ANMF" => frames.push(Duration::from_millis(u64::from(u24_le(bytes, data + 12)?))),
+			_ => {}
+		}
+		// Chunks are padded to an even size.
+		at = data
+			.checked_add(length as usize)?
+			.checked_add(length as usize & 1)
+			.filter(|&end| end <= bytes.len())?;
+	}
+	Some(Animation {
+		container: Container::WebP,
+		frames,
+		plays,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use image::{
+		codecs::gif::{GifEncoder, Repeat},
+		Delay, Frame, Rgba,
+	};
+
+	use super::*;
+
+	fn gif(delays: &[u32], repeat: Repeat) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		{
+			let mut encoder = GifEncoder::new(&mut bytes);
+			encoder.set_repeat(repeat).unwrap();
+			for (i, delay) in delays.iter().enumerate() {
+				let buffer = RgbaImage::from_pixel(2, 2, Rgba([i as u8 * 40, 0, 0, 255]));
+				encoder
+					.encode_frame(Frame::from_parts(
+						buffer,
+						0,
+						0,
+						Delay::from_numer_denom_ms(*delay, 1),
+					))
+					.unwrap();
+			}
+		}
+		bytes
+	}
+
+	/// Chunk with a bogus CRC, which the parser does not check.
+	fn png_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+		let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+		chunk.extend_from_slice(kind);
+		chunk.extend_from_slice(data);
+		chunk.extend_from_slice(&[0; 4]);
+		chunk
+	}
+
+	fn fctl(numerator: u16, denominator: u16) -> Vec<u8> {
+		let mut data = vec![0; 20];
+		data.extend_from_slice(&numerator.to_be_bytes());
+		data.extend_from_slice(&denominator.to_be_bytes());
+		data.extend_from_slice(&[0, 0]);
+		png_chunk(b"fcTL", &data)
+	}
+
+	fn webp_chunk(kind: &[u8], data: &[u8]) -> Vec<u8> {
+		let mut chunk = kind.to_vec();
+		chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+		chunk.extend_from_slice(data);
+		if data.len() % 2 == 1 {
+			chunk.push(0);
+		}
+		chunk
+	}
+
+	fn anmf(duration: u32) -> Vec<u8> {
+		let mut data = vec![0; 12];
+		data.extend_from_slice(&duration.to_le_bytes()[..3]);
+		data.push(0);
+		webp_chunk(b"ANMF", &data)
+	}
+
+	fn millis(frames: &[u64]) -> Vec<Duration> {
+		frames.iter().map(|ms| Duration::from_millis(*ms)).collect()
+	}
+
+	#[test]
+	fn parses_gif_timing() {
+		let bytes = gif(&[50, 200, 10], Repeat::Finite(2));
+		let animation = Animation::parse(&bytes).unwrap();
+		assert_eq!(animation.frames, millis(&[50, 200, 100]));
+		assert_eq!(animation.plays, Some(3));
+		assert_eq!(
+			Animation::parse(&gif(&[100, 100], Repeat::Infinite))
+				.unwrap()
+				.plays,
+			None
+		);
+	}
+
+	#[test]
+	fn decodes_gif_frames() {
+		let bytes = gif(&[100, 100, 100], Repeat::Infinite);
+		let animation = Animation::parse(&bytes).unwrap();
+		let frames = animation.decode(&bytes).unwrap();
+		assert_eq!(frames.len(), 3);
+		assert_eq!(frames[2].get_pixel(0, 0).0[0], 80);
+	}
+
+	#[test]
+	fn ignores_still_images() {
+		assert_eq!(Animation::parse(&gif(&[100], Repeat::Infinite)), None);
+		assert_eq!(Animation::parse(b"not an image"), None);
+	}
+
+	#[test]
+	fn parses_apng_timing() {
+		let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+		bytes.extend(png_chunk(b"IHDR", &[0; 13]));
+		bytes.extend(png_chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 4]));
+		bytes.extend(fctl(1, 4));
+		bytes.extend(fctl(30, 0));
+		bytes.extend(png_chunk(b"IEND", &[]));
+		let animation = Animation::parse(&bytes).unwrap();
+		assert_eq!(animation.frames, millis(&[250, 300]));
+		assert_eq!(animation.plays, Some(4));
+	}
+
+	#[test]
+	fn parses_webp_timing() {
+		let mut chunks = webp_chunk(b"VP8X", &[0; 10]);
+		chunks.extend(webp_chunk(b"ANIM", &[0, 0, 0, 0, 0, 0]));
+		chunks.extend(anmf(40));
+		chunks.extend(anmf(70_000));
+		let mut bytes = b"RIFF".to_vec();
+		bytes.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+		bytes.extend_from_slice(b"WEBP");
+		bytes.extend(chunks);
+		let animation = Animation::parse(&bytes).unwrap();
+		assert_eq!(animation.frames, millis(&[40, 70_000]));
+		assert_eq!(animation.plays, None);
+	}
+
+	#[test]
+	fn tells_stills_from_headers() {
+		let still = gif(&[100], Repeat::Infinite);
+		let animated = gif(&[100, 100], Repeat::Infinite);
+		assert!(!may_be_animated(&still));
+		assert!(may_be_animated(&animated));
+		assert!(may_be_animated(&still[..still.len() / 2]));
+
+		let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+		png.extend(png_chunk(b"IHDR", &[0; 13]));
+		assert!(may_be_animated(&png));
+		let mut apng = png.clone();
+		apng.extend(png_chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 0]));
+		assert!(may_be_animated(&apng));
+		png.extend(png_chunk(b"IDAT", &[0; 4]));
+		assert!(!may_be_animated(&png));
+
+		let webp = |flags: u8| {
+			let mut bytes = b"RIFF\0\0\0\0WEBP".to_vec();
+			bytes.extend(webp_chunk(b"VP8X", &[flags, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+			bytes
+		};
+		assert!(may_be_animated(&webp(0x02)));
+		assert!(!may_be_animated(&webp(0)));
+		assert!(!may_be_animated(b"not an image"));
+	}
+
+	#[test]
+	fn rejects_oversized_chunks() {
+		let mut bogus = png_chunk(b"tEXt", &[0; 4]);
+		bogus[..4].copy_from_slice(&u32::MAX.to_be_bytes());
+		let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+		bytes.extend(png_chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 0]));
+		bytes.extend(fctl(1, 4));
+		bytes.extend(fctl(1, 4));
+		bytes.extend(bogus);
+		assert_eq!(Animation::parse(&bytes), None);
+
+		let mut bogus = webp_chunk(b"ANMF", &[0; 4]);
+		bogus[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+		let mut chunks = webp_chunk(b"VP8X", &[0; 10]);
+		chunks.extend(anmf(40));
+		chunks.extend(anmf(40));
+		chunks.extend(bogus);
+		let mut bytes = b"RIFF".to_vec();
+		bytes.extend_from_slice(&(chunks.len() as u32 + 4).to_le_bytes());
+		bytes.extend_from_slice(b"WEBP");
+		bytes.extend(chunks);
+		assert_eq!(Animation::parse(&bytes), None);
+	}
+
+	#[test]
+	fn locates_frames_in_time() {
+		let animation = Animation {
+			container: Container::Gif,
+			frames: millis(&[100, 200, 300]),
+			plays: Some(2),
+		};
+		assert_eq!(animation.duration(), Duration::from_millis(600));
+		assert_eq!(animation.play_time(5), Duration::from_millis(1200));
+		assert_eq!(animation.frame_at(Duration::from_millis(50)), 0);
+		assert_eq!(animation.frame_at(Duration::from_millis(250)), 1);
+		assert_eq!(animation.frame_at(Duration::from_millis(650)), 0);
+		assert_eq!(animation.frame_at(Duration::from_secs(10)), 2);
+		assert_eq!(animation.frame_start(2), Duration::from_millis(300));
+	}
+}
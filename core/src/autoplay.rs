@@ -0,0 +1,304 @@
+use std::time::Duration;
+
+use crate::settings::Rule;
+
+/// Autoplay state of the displayed item.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum State {
+	/// The item is being loaded, so whether and how long it plays is not known yet.
+	#[default]
+	Loading,
+	/// The item plays until it ends by itself or its max display time is up.
+	Playing,
+	/// Playback was paused by the user, after the item had ended if `ended`.
+	Paused { ended: bool },
+	/// The item ended and stays up, since autoplay is off or was canceled.
+	Ended,
+	/// The item ended and the next one follows once the delay is up.
+	Countdown,
+	/// The delay is up and the next item was asked for.
+	Advancing,
+}
+
+/// Something which happened to the displayed item.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Event {
+	/// Another item started loading.
+	Load,
+	/// The item is ready. Still items have nothing to play, so they end as soon as they are shown.
+	Loaded {
+		still: bool,
+	},
+	/// The item ended by itself, or could not be loaded.
+	End,
+	/// The timer of the current state is up.
+	Elapsed,
+	Pause,
+	Resume,
+	/// The user seeked within the item, so it plays on.
+	Seeked,
+	/// The user wants the item to stay up.
+	Cancel,
+	/// The rule of the item changed, possibly making it still.
+	Rule {
+		still: bool,
+	},
+}
+
+/// Timer of an autoplay state, which can be frozen and resumed where it left off.
+///
+/// Timestamps are in milliseconds, as handed out by the browser.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timer {
+	pub length: Duration,
+	/// Time the timer ran for until `since`, or until it was frozen.
+	ran: Duration,
+	/// Timestamp since when the timer runs, unless it is frozen.
+	since: Option<f64>,
+}
+
+impl Timer {
+	pub fn start(length: Duration, now: f64) -> Self {
+		Self {
+			length,
+			ran: Duration::ZERO,
+			since: Some(now),
+		}
+	}
+
+	pub fn elapsed(&self, now: f64) -> Duration {
+		let running = self
+			.since
+			.map_or(0.0, |since| ((now - since) / 1000.0).max(0.0));
+		(self.ran + Duration::from_secs_f64(running)).min(self.length)
+	}
+
+	pub fn remaining(&self, now: f64) -> Duration {
+		self.length - self.elapsed(now)
+	}
+
+	/// Share of the timer which is left, from 1 down to 0.
+	pub fn progress(&self, now: f64) -> f64 {
+		if self.length.is_zero() {
+			0.0
+		} else {
+			self.remaining(now).as_secs_f64() / self.length.as_secs_f64()
+		}
+	}
+
+	pub fn is_frozen(&self) -> bool {
+		self.since.is_none()
+	}
+
+	pub fn freeze(&mut self, now: f64) {
+		self.ran = self.elapsed(now);
+		self.since = None;
+	}
+
+	pub fn resume(&mut self, now: f64) {
+		if self.since.is_none() {
+			self.since = Some(now);
+		}
+	}
+}
+
+impl State {
+	fn ended(rule: &Rule) -> Self {
+		if rule.autoplay {
+			State::Countdown
+		} else {
+			State::Ended
+		}
+	}
+
+	/// State following an event, or `None` if the event does not apply in this state.
+	///
+	/// Timers derive from the state, so they are restarted whenever an event applies.
+	pub fn next(self, event: Event, rule: &Rule) -> Option<Self> {
+		match (self, event) {
+			(_, Event::Load) => Some(State::Loading),
+			(State::Loading, Event::Loaded { still: true }) => Some(Self::ended(rule)),
+			(State::Loading, Event::Loaded { still: false }) => Some(State::Playing),
+			(State::Loading, Event::End) | (State::Playing, Event::End) => Some(Self::ended(rule)),
+			(State::Playing, Event::Elapsed) => Some(Self::ended(rule)),
+			(State::Countdown, Event::Elapsed) => Some(State::Advancing),
+			(State::Playing, Event::Pause) => Some(State::Paused { ended: false }),
+			(State::Ended, Event::Pause) | (State::Countdown, Event::Pause) => {
+				Some(State::Paused { ended: true })
+			}
+			(State::Paused { ended: false }, Event::Resume) => Some(State::Playing),
+			(State::Paused { ended: true }, Event::Resume) => Some(Self::ended(rule)),
+			(State::Playing, Event::Seeked)
+			| (State::Ended, Event::Seeked)
+			| (State::Countdown, Event::Seeked) => Some(State::Playing),
+			(State::Countdown, Event::Cancel) => Some(State::Ended),
+			(State::Playing, Event::Rule { still: true }) => Some(Self::ended(rule)),
+			(State::Playing, Event::Rule { still: false }) => Some(State::Playing),
+			(State::Countdown, Event::Rule { .. }) => Some(Self::ended(rule)),
+			_ => None,
+		}
+	}
+
+	/// Time after which this state elapses, if it does at all. `animation_remaining` is the time
+	/// left until an animated item played the loops of its rule.
+	pub fn timer(&self, rule: &Rule, animation_remaining: Option<Duration>) -> Option<Duration> {
+		match self {
+			State::Playing if rule.autoplay => match (rule.max_display_time, animation_remaining) {
+				(Some(max), Some(remaining)) => Some(max.min(remaining)),
+				(max, remaining) => max.or(remaining),
+			},
+			State::Countdown => Some(rule.delay),
+			_ => None,
+		}
+	}
+
+	pub fn is_paused(&self) -> bool {
+		matches!(self, State::Paused { .. })
+	}
+
+	/// Timer running in the state following an event. Pausing freezes the running timer and
+	/// resuming picks it up again, so held items continue where they left off.
+	pub fn next_timer(
+		&self,
+		event: Event,
+		timer: Option<Timer>,
+		rule: &Rule,
+		animation_remaining: Option<Duration>,
+		now: f64,
+	) -> Option<Timer> {
+		match (event, timer) {
+			(Event::Pause, Some(mut timer)) => {
+				timer.freeze(now);
+				Some(timer)
+			}
+			(Event::Resume, Some(mut timer)) if timer.is_frozen() => {
+				timer.resume(now);
+				Some(timer)
+			}
+			_ => self
+				.timer(rule, animation_remaining)
+				.map(|length| Timer::start(length, now)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		media::Kind,
+		settings::{Rule, RuleChange},
+	};
+
+	fn run(rule: &Rule, events: &[Event]) -> State {
+		events.iter().fold(State::default(), |state, event| {
+			state.next(*event, rule).unwrap_or(state)
+		})
+	}
+
+	#[test]
+	fn counts_down_once_ended() {
+		let rule = Rule::new(Kind::Image, None);
+		let state = run(&rule, &[Event::Loaded { still: true }]);
+		assert_eq!(state, State::Countdown);
+		assert_eq!(state.timer(&rule, None), Some(Duration::from_secs(2)));
+		assert_eq!(state.next(Event::Elapsed, &rule), Some(State::Advancing));
+		assert_eq!(state.next(Event::Cancel, &rule), Some(State::Ended));
+		assert_eq!(State::Ended.timer(&rule, None), None);
+	}
+
+	#[test]
+	fn stays_without_autoplay() {
+		let mut rule = Rule::new(Kind::Video, None);
+		rule.apply(RuleChange::ToggleAutoplay);
+		rule.apply(RuleChange::MaxDisplayTime(Some(Duration::from_secs(5))));
+		let state = run(&rule, &[Event::Loaded { still: false }]);
+		assert_eq!(state, State::Playing);
+		assert_eq!(state.timer(&rule, None), None);
+		assert_eq!(
+			run(&rule, &[Event::Loaded { still: false }, Event::End]),
+			State::Ended
+		);
+	}
+
+	#[test]
+	fn ends_at_max_display_time_or_animation_end() {
+		let mut rule = Rule::new(Kind::Image, None);
+		let three = Some(Duration::from_secs(3));
+		assert_eq!(State::Playing.timer(&rule, three), three);
+		rule.apply(RuleChange::MaxDisplayTime(Some(Duration::from_secs(1))));
+		assert_eq!(
+			State::Playing.timer(&rule, three),
+			Some(Duration::from_secs(1))
+		);
+		assert_eq!(
+			State::Playing.timer(&rule, None),
+			Some(Duration::from_secs(1))
+		);
+		assert_eq!(
+			State::Playing.next(Event::Elapsed, &rule),
+			Some(State::Countdown)
+		);
+	}
+
+	#[test]
+	fn pausing_holds_timers() {
+		let rule = Rule::new(Kind::Image, None);
+		let paused = run(&rule, &[Event::Loaded { still: false }, Event::Pause]);
+		assert_eq!(paused, State::Paused { ended: false });
+		assert_eq!(paused.timer(&rule, Some(Duration::from_secs(1))), None);
+		assert_eq!(paused.next(Event::Resume, &rule), Some(State::Playing));
+		let paused = run(&rule, &[Event::Loaded { still: true }, Event::Pause]);
+		assert_eq!(paused.next(Event::Resume, &rule), Some(State::Countdown));
+		assert_eq!(paused.next(Event::Pause, &rule), None);
+	}
+
+	#[test]
+	fn ignores_stale_events() {
+		let rule = Rule::new(Kind::Video, None);
+		assert_eq!(State::Loading.next(Event::Elapsed, &rule), None);
+		assert_eq!(State::Advancing.next(Event::End, &rule), None);
+		assert_eq!(State::Advancing.next(Event::Seeked, &rule), None);
+		assert_eq!(State::Ended.next(Event::Rule { still: false }, &rule), None);
+		assert_eq!(
+			State::Advancing.next(Event::Load, &rule),
+			Some(State::Loading)
+		);
+	}
+
+	#[test]
+	fn freezes_timers_while_paused() {
+		let rule = Rule::new(Kind::Image, None);
+		let timer = State::Countdown.next_timer(Event::Elapsed, None, &rule, None, 0.0);
+		assert_eq!(timer, Some(Timer::start(Duration::from_secs(2), 0.0)));
+		let paused = State::Paused { ended: true };
+		let timer = paused.next_timer(Event::Pause, timer, &rule, None, 500.0);
+		let frozen = timer.unwrap();
+		assert!(frozen.is_frozen());
+		assert_eq!(frozen.remaining(10_000.0), Duration::from_millis(1500));
+		let timer = State::Countdown.next_timer(Event::Resume, timer, &rule, None, 10_000.0);
+		assert_eq!(
+			timer.unwrap().remaining(11_000.0),
+			Duration::from_millis(500)
+		);
+		assert_eq!(timer.unwrap().progress(11_000.0), 0.25);
+		let timer = State::Countdown.next_timer(Event::Rule { still: true }, timer, &rule, None, 0.0);
+		assert_eq!(timer.unwrap().remaining(0.0), Duration::from_secs(2));
+	}
+
+	#[test]
+	fn timers_stop_at_zero() {
+		let timer = Timer::start(Duration::from_secs(1), 1000.0);
+		assert_eq!(timer.remaining(0.0), Duration::from_secs(1));
+		assert_eq!(timer.remaining(5000.0), Duration::ZERO);
+		assert_eq!(Timer::start(Duration::ZERO, 0.0).progress(0.0), 0.0);
+	}
+
+	#[test]
+	fn seeking_plays_on() {
+		let rule = Rule::new(Kind::Video, None);
+		let state = run(&rule, &[Event::Loaded { still: false }, Event::End]);
+		assert_eq!(state, State::Countdown);
+		assert_eq!(state.next(Event::Seeked, &rule), Some(State::Playing));
+	}
+}
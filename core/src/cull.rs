@@ -0,0 +1,78 @@
+//! Culling: sorting items into those kept and those rejected, then moving rejects aside or
+//! deleting them.
+
+/// Subfolder rejected files are moved into, next to the files which were kept.
+pub const REJECTS_FOLDER: &str = "rejected";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+	Keep,
+	Reject,
+}
+
+/// A file moved aside, which can be moved back.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Move {
+	pub from: String,
+	pub to: String,
+}
+
+/// Splits a path into its top folder, which access has to be granted to for the file to be changed,
+/// and the rest of the path within it. Files added on their own have no folder.
+pub fn split_top(path: &str) -> Option<(&str, &str)> {
+	path
+		.split_once('/')
+		.filter(|(top, rest)| !top.is_empty() && !rest.is_empty())
+}
+
+/// Path a rejected file is moved to, in a subfolder of its folder.
+pub fn rejected_path(path: &str) -> String {
+	match path.rsplit_once('/') {
+		Some((folder, name)) => format!("{}/{}/{}", folder, REJECTS_FOLDER, name),
+		None => format!("{}/{}", REJECTS_FOLDER, path),
+	}
+}
+
+/// Variant of a path told apart by a number, as in `photo (2).jpg`, for files not to overwrite
+/// one another. The first variant is the path itself.
+pub fn numbered(path: &str, number: u32) -> String {
+	if number <= 1 {
+		return String::from(path);
+	}
+	let name_start = path.rfind('/').map_or(0, |i| i + 1);
+	// Hidden files have no extension, only a leading dot.
+	match path[name_start..].rfind('.').filter(|&i| i > 0) {
+		Some(dot) => {
+			let (stem, extension) = path.split_at(name_start + dot);
+			format!("{} ({}){}", stem, number, extension)
+		}
+		None => format!("{} ({})", path, number),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn splits_top_folders() {
+		assert_eq!(split_top("Shoot/a/b.jpg"), Some(("Shoot", "a/b.jpg")));
+		assert_eq!(split_top("b.jpg"), None);
+		assert_eq!(split_top("/b.jpg"), None);
+	}
+
+	#[test]
+	fn places_rejects_next_to_their_folder() {
+		assert_eq!(rejected_path("a/b.jpg"), "a/rejected/b.jpg");
+		assert_eq!(rejected_path("b.jpg"), "rejected/b.jpg");
+	}
+
+	#[test]
+	fn numbers_paths() {
+		assert_eq!(numbered("a/b.jpg", 1), "a/b.jpg");
+		assert_eq!(numbered("a/b.jpg", 2), "a/b (2).jpg");
+		assert_eq!(numbered("a.d/b", 3), "a.d/b (3)");
+		assert_eq!(numbered(".hidden", 2), ".hidden (2)");
+		assert_eq!(numbered("b.tar.gz", 2), "b.tar (2).gz");
+	}
+}
@@ -0,0 +1,124 @@
+//! Finding copies of files, exact ones by their contents and near ones by how their pictures look.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Width of the grayscale picture perceptual hashes are made from, one pixel wider than high for
+/// each row to yield eight comparisons.
+pub const FINGERPRINT_WIDTH: u32 = 9;
+pub const FINGERPRINT_HEIGHT: u32 = 8;
+
+/// Number of differing bits up to which two perceptual hashes are taken for the same picture.
+pub const MAX_DISTANCE: u32 = 10;
+
+/// Difference hash of a picture scaled down to 9 by 8 RGBA pixels, each bit telling whether a
+/// pixel is brighter than its right neighbor. Pictures which look alike differ in few bits, even
+/// after being scaled, recompressed or slightly retouched.
+pub fn dhash(rgba: &[u8]) -> u64 {
+	let luma: Vec<u32> = rgba
+		.chunks_exact(4)
+		.map(|p| (p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114) / 1000)
+		.collect();
+	let width = FINGERPRINT_WIDTH as usize;
+	let mut hash = 0;
+	for row in luma.chunks_exact(width).take(FINGERPRINT_HEIGHT as usize) {
+		for pair in row.windows(2) {
+			hash = (hash << 1) | (pair[0] > pair[1]) as u64;
+		}
+	}
+	hash
+}
+
+pub fn distance(a: u64, b: u64) -> u32 {
+	(a ^ b).count_ones()
+}
+
+/// Positions of the values sharing a key, for every key shared by several of them, in order of
+/// first appearance. Values without a key are left out.
+pub fn groups_by<K: Eq + Hash>(keys: &[Option<K>]) -> Vec<Vec<usize>> {
+	let mut groups: Vec<Vec<usize>> = Vec::new();
+	let mut positions: HashMap<&K, usize> = HashMap::new();
+	for (i, key) in keys.iter().enumerate() {
+		if let Some(key) = key {
+			let group = *positions.entry(key).or_insert_with(|| {
+				groups.push(Vec::new());
+				groups.len() - 1
+			});
+			groups[group].push(i);
+		}
+	}
+	groups.retain(|group| group.len() > 1);
+	groups
+}
+
+fn root(parents: &mut [usize], mut i: usize) -> usize {
+	while parents[i] != i {
+		parents[i] = parents[parents[i]];
+		i = parents[i];
+	}
+	i
+}
+
+/// Positions of the perceptual hashes which are close to one another, directly or through others,
+/// for every group of several of them. Values without a hash are left out.
+pub fn similar_groups(hashes: &[Option<u64>], max_distance: u32) -> Vec<Vec<usize>> {
+	let mut parents: Vec<usize> = (0..hashes.len()).collect();
+	let hashed: Vec<(usize, u64)> = hashes
+		.iter()
+		.enumerate()
+		.filter_map(|(i, hash)| Some((i, (*hash)?)))
+		.collect();
+	for (n, &(i, a)) in hashed.iter().enumerate() {
+		for &(j, b) in &hashed[n + 1..] {
+			if distance(a, b) <= max_distance {
+				let (i, j) = (root(&mut parents, i), root(&mut parents, j));
+				parents[i.max(j)] = i.min(j);
+			}
+		}
+	}
+	let roots: Vec<Option<usize>> = (0..hashes.len())
+		.map(|i| hashes[i].map(|_| root(&mut parents, i)))
+		.collect();
+	groups_by(&roots)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fingerprints_pictures() {
+		let pixels = |brightness: &dyn Fn(usize) -> u8| -> Vec<u8> {
+			(0..(FINGERPRINT_WIDTH * FINGERPRINT_HEIGHT) as usize)
+				.flat_map(|i| {
+					let b = brightness(i % FINGERPRINT_WIDTH as usize);
+					vec![b, b, b, 255]
+				})
+				.collect()
+		};
+		let darkening = dhash(&pixels(&|x| 255 - x as u8 * 20));
+		assert_eq!(darkening, u64::MAX);
+		assert_eq!(dhash(&pixels(&|x| x as u8 * 20)), 0);
+		// Lowering the contrast leaves the hash as it is.
+		assert_eq!(dhash(&pixels(&|x| 255 - x as u8 * 10)), darkening);
+		assert_eq!(distance(darkening, 0), 64);
+	}
+
+	#[test]
+	fn groups_duplicates() {
+		assert_eq!(
+			groups_by(&[Some("a"), Some("b"), None, Some("a"), Some("c"), Some("b")]),
+			vec![vec![0, 3], vec![1, 5]]
+		);
+		let hashes = [
+			Some(0b0000),
+			Some(u64::MAX),
+			Some(0b0011),
+			None,
+			// Close to the third, though not to the first.
+			Some(0b1111),
+			Some(u64::MAX >> 1),
+		];
+		assert_eq!(similar_groups(&hashes, 2), vec![vec![0, 2, 4], vec![1, 5]]);
+		assert_eq!(similar_groups(&hashes, 0), Vec::<Vec<usize>>::new());
+	}
+}
@@ -0,0 +1,284 @@
+//! Capture metadata stored in the EXIF tags of photos.
+
+use std::fmt::Write;
+
+/// Number of bytes read from the start of a file to find its EXIF tags, which JPEG files keep near
+/// the start.
+pub const HEADER_BYTES: i32 = 128 * 1024;
+
+const TAG_MODEL: u16 = 0x0110;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+/// Type of values made of ASCII characters.
+const ASCII: u16 = 2;
+
+/// Calendar date and time of day, in the local time of wherever it was taken.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct DateTime {
+	pub year: i32,
+	pub month: u32,
+	pub day: u32,
+	pub hour: u32,
+	pub minute: u32,
+	pub second: u32,
+}
+
+impl DateTime {
+	/// Reads dates written as `YYYY:MM:DD HH:MM:SS`, as EXIF does.
+	pub fn parse_exif(text: &str) -> Option<Self> {
+		let (date, time) = text.trim_end_matches('\0').trim().split_once(' ')?;
+		let mut date = date.splitn(3, ':').map(str::parse::<u32>);
+		let mut time = time.splitn(3, ':').map(str::parse::<u32>);
+		let date_time = Self {
+			year: date.next()?.ok()? as i32,
+			month: date.next()?.ok()?,
+			day: date.next()?.ok()?,
+			hour: time.next()?.ok()?,
+			minute: time.next()?.ok()?,
+			second: time.next()?.ok()?,
+		};
+		// Cameras without a clock set write zeros or blanks.
+		if date_time.month == 0 || date_time.day == 0 {
+			return None;
+		}
+		Some(date_time)
+	}
+
+	/// Writes the date following a pattern, in which `%Y`, `%y`, `%m`, `%d`, `%H`, `%M` and `%S`
+	/// stand for its fields and `%%` for a percent sign.
+	pub fn format(&self, pattern: &str) -> String {
+		let mut formatted = String::new();
+		let mut chars = pattern.chars();
+		while let Some(c) = chars.next() {
+			if c != '%' {
+				formatted.push(c);
+				continue;
+			}
+			// Writing to a string cannot fail.
+			let _ = match chars.next() {
+				Some('Y') => write!(formatted, "{:04}", self.year),
+				Some('y') => write!(formatted, "{:02}", self.year.rem_euclid(100)),
+				Some('m') => write!(formatted, "{:02}", self.month),
+				Some('d') => write!(formatted, "{:02}", self.day),
+				Some('H') => write!(formatted, "{:02}", self.hour),
+				Some('M') => write!(formatted, "{:02}", self.minute),
+				Some('S') => write!(formatted, "{:02}", self.second),
+				Some('%') => write!(formatted, "%"),
+				Some(other) => write!(formatted, "%{}", other),
+				None => write!(formatted, "%"),
+			};
+		}
+		formatted
+	}
+}
+
+/// Metadata found in the EXIF tags of a file.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Exif {
+	/// When the photo was taken, or else last changed.
+	pub date: Option<DateTime>,
+	pub camera: Option<String>,
+}
+
+/// TIFF structure holding the tags, which is either little or big endian.
+struct Tiff<'a> {
+	bytes: &'a [u8],
+	little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+	fn new(bytes: &'a [u8]) -> Option<Self> {
+		let little_endian = match bytes.get(..4)? {
+			b"II*\0" => true,
+			b"MM\0*" => false,
+			_ => return None,
+		};
+		Some(Self {
+			bytes,
+			little_endian,
+		})
+	}
+
+	fn u16(&self, at: usize) -> Option<u16> {
+		let b = self.bytes.get(at..at.checked_add(2)?)?;
+		Some(if self.little_endian {
+			u16::from_le_bytes([b[0], b[1]])
+		} else {
+			u16::from_be_bytes([b[0], b[1]])
+		})
+	}
+
+	fn u32(&self, at: usize) -> Option<u32> {
+		let b = self.bytes.get(at..at.checked_add(4)?)?;
+		Some(if self.little_endian {
+			u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+		} else {
+			u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+		})
+	}
+
+	/// Positions of the 12 byte entries of the directory starting at an offset, as far as they are
+	/// within the bytes. Offsets come from the file, and may overflow 32 bit positions.
+	fn entries(&self, offset: usize) -> impl Iterator<Item = usize> {
+		let count = self.u16(offset).unwrap_or_default() as usize;
+		let len = self.bytes.len();
+		(0..count).map_while(move |i| {
+			let entry = offset.checked_add(2 + i * 12)?;
+			(entry.checked_add(12)? <= len).then_some(entry)
+		})
+	}
+
+	/// Entry of a directory holding a tag.
+	fn find(&self, offset: usize, tag: u16) -> Option<usize> {
+		self
+			.entries(offset)
+			.find(|&entry| self.u16(entry) == Some(tag))
+	}
+
+	fn ascii(&self, entry: usize) -> Option<String> {
+		if self.u16(entry + 2)? != ASCII {
+			return None;
+		}
+		let count = self.u32(entry + 4)? as usize;
+		// Values of up to four bytes are kept in the entry itself.
+		let at = if count <= 4 {
+			entry + 8
+		} else {
+			self.u32(entry + 8)? as usize
+		};
+		let text = String::from_utf8_lossy(self.bytes.get(at..at.checked_add(count)?)?);
+		let text = text.trim_end_matches('\0').trim();
+		if text.is_empty() {
+			None
+		} else {
+			Some(text.to_string())
+		}
+	}
+
+	fn exif(&self) -> Exif {
+		let ifd0 = match self.u32(4) {
+			Some(offset) => offset as usize,
+			None => return Exif::default(),
+		};
+		let text = |offset: usize, tag: u16| self.find(offset, tag).and_then(|e| self.ascii(e));
+		let original = self
+			.find(ifd0, TAG_EXIF_IFD)
+			.and_then(|entry| self.u32(entry + 8))
+			.and_then(|offset| text(offset as usize, TAG_DATE_TIME_ORIGINAL));
+		Exif {
+			date: original
+				.or_else(|| text(ifd0, TAG_DATE_TIME))
+				.and_then(|date| DateTime::parse_exif(&date)),
+			camera: text(ifd0, TAG_MODEL),
+		}
+	}
+}
+
+/// TIFF structure within the `APP1` segment of a JPEG file.
+fn jpeg_tiff(bytes: &[u8]) -> Option<&[u8]> {
+	if bytes.get(..2)? != [0xff, 0xd8] {
+		return None;
+	}
+	let mut at = 2;
+	loop {
+		let marker = bytes.get(at..at + 2)?;
+		// Image data starts at the start of scan marker, past any metadata.
+		if marker[0] != 0xff || marker[1] == 0xda {
+			return None;
+		}
+		let length = u16::from_be_bytes([*bytes.get(at + 2)?, *bytes.get(at + 3)?]) as usize;
+		let segment = bytes.get(at + 4..(at + 2 + length).min(bytes.len()))?;
+		if marker[1] == 0xe1 && segment.starts_with(b"Exif\0\0") {
+			return Some(&segment[6..]);
+		}
+		at += 2 + length;
+	}
+}
+
+/// Reads the EXIF tags at the start of a JPEG or TIFF file, of which raw camera files are made.
+pub fn parse(bytes: &[u8]) -> Exif {
+	jpeg_tiff(bytes)
+		.or(Some(bytes))
+		.and_then(Tiff::new)
+		.map(|tiff| tiff.exif())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> DateTime {
+		DateTime {
+			year,
+			month,
+			day,
+			hour,
+			minute,
+			second,
+		}
+	}
+
+	/// Little endian TIFF structure holding a camera model, and a capture date in its EXIF directory.
+	fn tiff() -> Vec<u8> {
+		let model = b"Camera\0";
+		let taken = b"2021:06:05 14:03:09\0";
+		let mut bytes = b"II*\0".to_vec();
+		bytes.extend(&8u32.to_le_bytes());
+		// IFD0 at 8: model and EXIF directory pointer, then the next directory offset.
+		let ifd0_end = 8 + 2 + 2 * 12 + 4;
+		let exif_ifd = ifd0_end + model.len();
+		let exif_end = exif_ifd + 2 + 12 + 4;
+		bytes.extend(&2u16.to_le_bytes());
+		bytes.extend(&TAG_MODEL.to_le_bytes());
+		bytes.extend(&ASCII.to_le_bytes());
+		bytes.extend(&(model.len() as u32).to_le_bytes());
+		bytes.extend(&(ifd0_end as u32).to_le_bytes());
+		bytes.extend(&TAG_EXIF_IFD.to_le_bytes());
+		bytes.extend(&4u16.to_le_bytes());
+		bytes.extend(&1u32.to_le_bytes());
+		bytes.extend(&(exif_ifd as u32).to_le_bytes());
+		bytes.extend(&0u32.to_le_bytes());
+		bytes.extend(model);
+		bytes.extend(&1u16.to_le_bytes());
+		bytes.extend(&TAG_DATE_TIME_ORIGINAL.to_le_bytes());
+		bytes.extend(&ASCII.to_le_bytes());
+		bytes.extend(&(taken.len() as u32).to_le_bytes());
+		bytes.extend(&(exif_end as u32).to_le_bytes());
+		bytes.extend(&0u32.to_le_bytes());
+		bytes.extend(taken);
+		bytes
+	}
+
+	#[test]
+	fn reads_jpeg_tags() {
+		let bytes = tiff();
+		let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe0, 0, 4, 0, 0, 0xff, 0xe1];
+		jpeg.extend(&((bytes.len() + 8) as u16).to_be_bytes());
+		jpeg.extend(b"Exif\0\0");
+		jpeg.extend(&bytes);
+		jpeg.extend(&[0xff, 0xda]);
+		let exif = parse(&jpeg);
+		assert_eq!(exif.date, Some(date(2021, 6, 5, 14, 3, 9)));
+		assert_eq!(exif.camera.as_deref(), Some("Camera"));
+		assert_eq!(parse(&bytes), exif);
+		assert_eq!(parse(b"not a photo"), Exif::default());
+		assert_eq!(parse(&jpeg[..20]), Exif::default());
+	}
+
+	#[test]
+	fn parses_and_formats_dates() {
+		assert_eq!(
+			DateTime::parse_exif("2021:06:05 14:03:09"),
+			Some(date(2021, 6, 5, 14, 3, 9))
+		);
+		assert_eq!(DateTime::parse_exif("0000:00:00 00:00:00"), None);
+		assert_eq!(DateTime::parse_exif("    :  :     :  :  "), None);
+		let taken = date(2021, 6, 5, 14, 3, 9);
+		assert_eq!(taken.format("%Y%m%d"), "20210605");
+		assert_eq!(taken.format("%y-%m-%d %H.%M.%S"), "21-06-05 14.03.09");
+		assert_eq!(taken.format("100%% %q%"), "100% %q%");
+	}
+}
@@ -0,0 +1,17 @@
+//! Browser independent logic of OmnivYou, built and tested on the host.
+
+pub mod animation;
+pub mod autoplay;
+pub mod cull;
+pub mod duplicates;
+pub mod exif;
+pub mod library;
+pub mod media;
+pub mod playlist;
+pub mod profile;
+pub mod query;
+pub mod queue;
+pub mod rename;
+pub mod settings;
+pub mod thumbnail;
+pub mod url;
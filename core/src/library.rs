@@ -0,0 +1,300 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_RATING: u8 = 5;
+
+/// User assigned favorite flag and star rating of an item.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Mark {
+	pub favorite: bool,
+	pub rating: u8,
+}
+
+impl Mark {
+	pub fn is_empty(&self) -> bool {
+		!self.favorite && self.rating == 0
+	}
+}
+
+/// How a set of selected tags is matched against the tags of an item.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TagMatch {
+	/// Intersection: the item must carry every selected tag.
+	All,
+	/// Union: the item must carry at least one selected tag.
+	Any,
+}
+
+impl TagMatch {
+	pub fn matches(&self, selected: &[String], tags: &[String]) -> bool {
+		match self {
+			TagMatch::All => selected.iter().all(|t| tags.contains(t)),
+			TagMatch::Any => selected.iter().any(|t| tags.contains(t)),
+		}
+	}
+}
+
+/// Whether a file is left out of the library, like dotfiles are.
+pub fn is_hidden(name: &str) -> bool {
+	name.starts_with('.')
+}
+
+/// Folder containing the given path, the root folder being the empty string.
+pub fn folder_of(path: &str) -> &str {
+	path.rfind('/').map_or("", |i| &path[..i])
+}
+
+/// Subfolders and indices of the files directly within a folder, given every path of the library.
+pub fn folder_entries<'a>(
+	folder: &str,
+	paths: impl IntoIterator<Item = &'a str>,
+) -> (Vec<String>, Vec<usize>) {
+	let mut folders = Vec::new();
+	let mut files = Vec::new();
+	for (i, path) in paths.into_iter().enumerate() {
+		let rest = if folder.is_empty() {
+			path
+		} else {
+			match path
+				.strip_prefix(folder)
+				.and_then(|rest| rest.strip_prefix('/'))
+			{
+				Some(rest) => rest,
+				None => continue,
+			}
+		};
+		match rest.find('/') {
+			Some(end) => folders.push(String::from(&path[..path.len() - rest.len() + end])),
+			None => files.push(i),
+		}
+	}
+	folders.sort_by(|a, b| natural_cmp(a, b));
+	folders.dedup();
+	(folders, files)
+}
+
+/// Name shown for a source of files: the folder holding all of them, or else the file name or the
+/// number of files.
+pub fn source_name(paths: &[&str]) -> String {
+	fn top(path: &str) -> Option<&str> {
+		path.split_once('/').map(|(folder, _)| folder)
+	}
+	match paths {
+		[] => String::from("No files"),
+		[path] if top(path).is_none() => String::from(*path),
+		[first, rest @ ..] => match top(first) {
+			Some(folder) if rest.iter().all(|path| top(path) == Some(folder)) => String::from(folder),
+			_ => format!("{} files", paths.len()),
+		},
+	}
+}
+
+/// Builds the set of path and size keys of present items that `without_duplicates` checks against.
+pub fn duplicate_keys<T>(present: &[T], key: impl Fn(&T) -> (&str, u64)) -> HashSet<(String, u64)> {
+	present
+		.iter()
+		.map(|item| {
+			let (path, size) = key(item);
+			(String::from(path), size)
+		})
+		.collect()
+}
+
+/// Leaves out added items whose path and size are already in `seen`, or duplicate earlier added
+/// ones, and records the keys of the kept ones.
+pub fn without_duplicates<T>(
+	seen: &mut HashSet<(String, u64)>,
+	added: Vec<T>,
+	key: impl Fn(&T) -> (&str, u64),
+) -> Vec<T> {
+	added
+		.into_iter()
+		.filter(|item| {
+			let (path, size) = key(item);
+			seen.insert((String::from(path), size))
+		})
+		.collect()
+}
+
+/// Merges sorted added items into sorted present ones, keeping present items ahead of equal added
+/// ones, instead of sorting everything again.
+pub fn merge_sorted<T>(
+	sorted: &mut Vec<T>,
+	added: Vec<T>,
+	mut compare: impl FnMut(&T, &T) -> Ordering,
+) {
+	if added.is_empty() {
+		return;
+	}
+	let mut merged = Vec::with_capacity(sorted.len() + added.len());
+	let mut present = sorted.drain(..).peekable();
+	for item in added {
+		merged.extend(std::iter::from_fn(|| {
+			present.next_if(|other| compare(other, &item) != Ordering::Greater)
+		}));
+		merged.push(item);
+	}
+	merged.extend(present);
+	*sorted = merged;
+}
+
+/// Compares paths the way file browsers do, with runs of digits compared by value so that
+/// `img2` sorts before `img10`, and case only breaking ties.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+	let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+	loop {
+		match (a_chars.peek().copied(), b_chars.peek().copied()) {
+			(None, None) => return a.cmp(b),
+			(None, Some(_)) => return Ordering::Less,
+			(Some(_), None) => return Ordering::Greater,
+			(Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+				let x: String = std::iter::from_fn(|| a_chars.next_if(char::is_ascii_digit)).collect();
+				let y: String = std::iter::from_fn(|| b_chars.next_if(char::is_ascii_digit)).collect();
+				let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+				match x.len().cmp(&y.len()).then_with(|| x.cmp(y)) {
+					Ordering::Equal => {}
+					ordering => return ordering,
+				}
+			}
+			(Some(x), Some(y)) => {
+				match x.to_lowercase().cmp(y.to_lowercase()) {
+					Ordering::Equal => {}
+					ordering => return ordering,
+				}
+				a_chars.next();
+				b_chars.next();
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tags(tags: &[&str]) -> Vec<String> {
+		tags.iter().map(|t| String::from(*t)).collect()
+	}
+
+	#[test]
+	fn matches_tags() {
+		let item = tags(&["beach", "family"]);
+		assert!(TagMatch::All.matches(&tags(&["beach", "family"]), &item));
+		assert!(!TagMatch::All.matches(&tags(&["beach", "snow"]), &item));
+		assert!(TagMatch::Any.matches(&tags(&["beach", "snow"]), &item));
+		assert!(!TagMatch::Any.matches(&tags(&["snow"]), &item));
+	}
+
+	#[test]
+	fn sorts_paths_naturally() {
+		let mut paths = vec![
+			"b/img10.png",
+			"a/IMG2.png",
+			"b/img2.png",
+			"b/img002.png",
+			"a/img1.png",
+			"b/img.png",
+		];
+		paths.sort_by(|a, b| natural_cmp(a, b));
+		assert_eq!(
+			paths,
+			vec![
+				"a/img1.png",
+				"a/IMG2.png",
+				"b/img.png",
+				"b/img002.png",
+				"b/img2.png",
+				"b/img10.png",
+			]
+		);
+	}
+
+	#[test]
+	fn lists_folders() {
+		let paths = [
+			"Trip/b.jpg",
+			"Trip/day 2/c.jpg",
+			"Trip/day 1/a.jpg",
+			"Tripod/d.jpg",
+			"e.jpg",
+		];
+		assert_eq!(
+			folder_entries("", paths.iter().copied()),
+			(vec![String::from("Trip"), String::from("Tripod")], vec![4])
+		);
+		assert_eq!(
+			folder_entries("Trip", paths.iter().copied()),
+			(
+				vec![String::from("Trip/day 1"), String::from("Trip/day 2")],
+				vec![0]
+			)
+		);
+		assert_eq!(folder_of("Trip/day 1/a.jpg"), "Trip/day 1");
+		assert_eq!(folder_of("e.jpg"), "");
+	}
+
+	#[test]
+	fn names_sources() {
+		assert_eq!(source_name(&["Trip/a.jpg", "Trip/day 1/b.jpg"]), "Trip");
+		assert_eq!(source_name(&["a.jpg"]), "a.jpg");
+		assert_eq!(source_name(&["Trip/a.jpg", "b.jpg"]), "2 files");
+		assert_eq!(source_name(&["Trip/a.jpg", "Tripod/b.jpg"]), "2 files");
+	}
+
+	#[test]
+	fn drops_duplicates_by_path_and_size() {
+		let present = [("Trip/a.jpg", 10)];
+		let added = vec![
+			("Trip/a.jpg", 10),
+			("Trip/a.jpg", 20),
+			("Trip/b.jpg", 10),
+			("Trip/b.jpg", 10),
+		];
+		let mut seen = duplicate_keys(&present, |&(path, size)| (path, size));
+		assert_eq!(
+			without_duplicates(&mut seen, added, |&(path, size)| (path, size)),
+			vec![("Trip/a.jpg", 20), ("Trip/b.jpg", 10)]
+		);
+		assert_eq!(
+			without_duplicates(
+				&mut seen,
+				vec![("Trip/b.jpg", 10), ("Trip/c.jpg", 10)],
+				|&(path, size)| (path, size)
+			),
+			vec![("Trip/c.jpg", 10)]
+		);
+	}
+
+	#[test]
+	fn merges_sorted_items() {
+		let mut sorted = vec![(1, 'a'), (3, 'a'), (5, 'a')];
+		merge_sorted(
+			&mut sorted,
+			vec![(0, 'b'), (3, 'b'), (4, 'b'), (7, 'b')],
+			|a, b| a.0.cmp(&b.0),
+		);
+		assert_eq!(
+			sorted,
+			vec![
+				(0, 'b'),
+				(1, 'a'),
+				(3, 'a'),
+				(3, 'b'),
+				(4, 'b'),
+				(5, 'a'),
+				(7, 'b')
+			]
+		);
+	}
+
+	#[test]
+	fn marks_are_empty_by_default() {
+		assert!(Mark::default().is_empty());
+		assert!(!Mark {
+			favorite: false,
+			rating: 1
+		}
+		.is_empty());
+	}
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+/// Media categories which can be displayed.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Kind {
+	Audio,
+	Image,
+	Video,
+}
+
+impl Kind {
+	pub const ALL: [Kind; 3] = [Kind::Video, Kind::Audio, Kind::Image];
+
+	pub fn from_mime(mime: &str) -> Option<Self> {
+		if mime.starts_with("audio/") {
+			Some(Kind::Audio)
+		} else if mime.starts_with("image/") {
+			Some(Kind::Image)
+		} else if mime.starts_with("video/") {
+			Some(Kind::Video)
+		} else {
+			None
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Kind::Audio => "Audio",
+			Kind::Image => "Photos",
+			Kind::Video => "Video",
+		}
+	}
+}
+
+/// Media type of a file, holding its MIME type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Type {
+	Audio(String),
+	Image(String),
+	Video(String),
+	Invalid(String),
+}
+
+impl Type {
+	pub fn kind(&self) -> Option<Kind> {
+		match self {
+			Type::Audio(_) => Some(Kind::Audio),
+			Type::Image(_) => Some(Kind::Image),
+			Type::Video(_) => Some(Kind::Video),
+			Type::Invalid(_) => None,
+		}
+	}
+}
+
+impl From<String> for Type {
+	fn from(s: String) -> Self {
+		match Kind::from_mime(&s) {
+			Some(Kind::Audio) => Type::Audio(s),
+			Some(Kind::Image) => Type::Image(s),
+			Some(Kind::Video) => Type::Video(s),
+			None => Type::Invalid(s),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn detects_type_from_mime() {
+		assert_eq!(
+			Type::from(String::from("audio/flac")),
+			Type::Audio(String::from("audio/flac"))
+		);
+		assert_eq!(
+			Type::from(String::from("image/gif")),
+			Type::Image(String::from("image/gif"))
+		);
+		assert_eq!(
+			Type::from(String::from("video/webm")),
+			Type::Video(String::from("video/webm"))
+		);
+		assert_eq!(Type::from(String::new()), Type::Invalid(String::new()));
+		assert_eq!(Type::from(String::from("application/pdf")).kind(), None);
+	}
+
+	#[test]
+	fn maps_types_to_kinds() {
+		for kind in Kind::ALL.iter() {
+			let mime = format!("{}/x", format!("{:?}", kind).to_lowercase());
+			assert_eq!(Type::from(mime).kind(), Some(*kind));
+		}
+	}
+}
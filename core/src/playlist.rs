@@ -0,0 +1,262 @@
+use crate::url;
+
+/// Playlist file formats supported for import and export.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+	M3u,
+	Pls,
+	Xspf,
+}
+
+impl Format {
+	pub const ALL: [Format; 3] = [Format::M3u, Format::Pls, Format::Xspf];
+
+	/// Guesses the format of a playlist from its file name.
+	pub fn from_name(name: &str) -> Option<Self> {
+		let extension = name.rsplit('.').next()?.to_ascii_lowercase();
+		match extension.as_str() {
+			"m3u" | "m3u8" => Some(Format::M3u),
+			"pls" => Some(Format::Pls),
+			"xspf" => Some(Format::Xspf),
+			_ => None,
+		}
+	}
+
+	pub fn extension(&self) -> &'static str {
+		match self {
+			Format::M3u => "m3u8",
+			Format::Pls => "pls",
+			Format::Xspf => "xspf",
+		}
+	}
+
+	pub fn mime(&self) -> &'static str {
+		match self {
+			Format::M3u => "audio/x-mpegurl",
+			Format::Pls => "audio/x-scpls",
+			Format::Xspf => "application/xspf+xml",
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Format::M3u => "M3U8",
+			Format::Pls => "PLS",
+			Format::Xspf => "XSPF",
+		}
+	}
+
+	/// Extracts the entry locations of a playlist, in order.
+	pub fn parse(&self, contents: &str) -> Vec<String> {
+		match self {
+			Format::M3u => parse_m3u(contents),
+			Format::Pls => parse_pls(contents),
+			Format::Xspf => parse_xspf(contents),
+		}
+	}
+
+	/// Writes a playlist of the given paths, relative to the selected folder.
+	pub fn write(&self, paths: &[&str]) -> String {
+		match self {
+			Format::M3u => write_m3u(paths),
+			Format::Pls => write_pls(paths),
+			Format::Xspf => write_xspf(paths),
+		}
+	}
+}
+
+fn title(path: &str) -> &str {
+	let name = path.rsplit('/').next().unwrap_or(path);
+	match name.rfind('.') {
+		Some(i) if i > 0 => &name[..i],
+		_ => name,
+	}
+}
+
+fn parse_m3u(contents: &str) -> Vec<String> {
+	contents
+		.lines()
+		.map(|l| l.trim_start_matches('\u{feff}').trim())
+		.filter(|l| !l.is_empty() && !l.starts_with('#'))
+		.map(String::from)
+		.collect()
+}
+
+fn write_m3u(paths: &[&str]) -> String {
+	let mut out = String::from("#EXTM3U\n");
+	for path in paths {
+		out.push_str(&format!("#EXTINF:-1,{}\n{}\n", title(path), path));
+	}
+	out
+}
+
+fn parse_pls(contents: &str) -> Vec<String> {
+	let mut entries: Vec<(u32, String)> = contents
+		.lines()
+		.filter_map(|l| {
+			let (key, value) = l.trim().split_once('=')?;
+			let number = key.trim().strip_prefix("File")?.parse().ok()?;
+			Some((number, value.trim().to_string()))
+		})
+		.collect();
+	entries.sort_by_key(|(n, _)| *n);
+	entries.into_iter().map(|(_, v)| v).collect()
+}
+
+fn write_pls(paths: &[&str]) -> String {
+	let mut out = String::from("[playlist]\n");
+	for (i, path) in paths.iter().enumerate() {
+		out.push_str(&format!(
+			"File{n}={}\nTitle{n}={}\nLength{n}=-1\n",
+			path,
+			title(path),
+			n = i + 1
+		));
+	}
+	out.push_str(&format!("NumberOfEntries={}\nVersion=2\n", paths.len()));
+	out
+}
+
+fn parse_xspf(contents: &str) -> Vec<String> {
+	let mut entries = Vec::new();
+	let mut rest = contents;
+	while let Some(start) = rest.find("<location>") {
+		rest = &rest[start + "<location>".len()..];
+		match rest.find("</location>") {
+			Some(end) => {
+				// XSPF locations are always URIs, relative ones included.
+				entries.push(url::decode(&unescape_xml(rest[..end].trim())));
+				rest = &rest[end..];
+			}
+			None => break,
+		}
+	}
+	entries
+}
+
+fn write_xspf(paths: &[&str]) -> String {
+	let mut out = String::from(
+		"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n\t<trackList>\n",
+	);
+	for path in paths {
+		out.push_str(&format!(
+			"\t\t<track>\n\t\t\t<location>{}</location>\n\t\t\t<title>{}</title>\n\t\t</track>\n",
+			escape_xml(&url::encode(path)),
+			escape_xml(title(path))
+		));
+	}
+	out.push_str("\t</trackList>\n</playlist>\n");
+	out
+}
+
+fn escape_xml(s: &str) -> String {
+	s.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+fn unescape_xml(s: &str) -> String {
+	s.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&apos;", "'")
+		.replace("&amp;", "&")
+}
+
+/// Normalizes a playlist location into `/` separated path segments.
+fn segments(location: &str) -> Vec<String> {
+	let location = location.trim();
+	let is_uri = location.starts_with("file://");
+	let location = location
+		.strip_prefix("file://")
+		.unwrap_or(location)
+		.replace('\\', "/");
+	let location = if is_uri {
+		url::decode(&location)
+	} else {
+		location
+	};
+	location
+		.split('/')
+		.filter(|s| !s.is_empty() && *s != ".")
+		.map(String::from)
+		.collect()
+}
+
+/// Finds which of the given paths a playlist location refers to.
+///
+/// Locations may be relative to anywhere within or above the selected folder, or absolute, so the
+/// path sharing the longest trailing run of segments with the location wins. At least the file
+/// name has to match.
+pub fn resolve<'a>(location: &str, paths: impl IntoIterator<Item = &'a str>) -> Option<usize> {
+	let location = segments(location);
+	let mut best: Option<(usize, usize)> = None;
+	for (i, path) in paths.into_iter().enumerate() {
+		let shared = path
+			.split('/')
+			.rev()
+			.zip(location.iter().rev())
+			.take_while(|(a, b)| a == b)
+			.count();
+		if shared > 0 && best.is_none_or(|(_, s)| shared > s) {
+			best = Some((i, shared));
+		}
+	}
+	best.map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PATHS: [&str; 3] = ["Trip/day 1/a&b.jpg", "Trip/day 2/clip.mp4", "Trip/song.mp3"];
+
+	#[test]
+	fn round_trips_every_format() {
+		for format in Format::ALL.iter() {
+			assert_eq!(
+				format.parse(&format.write(&PATHS)),
+				PATHS,
+				"{}",
+				format.name()
+			);
+			assert_eq!(
+				Format::from_name(&format!("list.{}", format.extension())),
+				Some(*format)
+			);
+		}
+		assert_eq!(Format::from_name("list.M3U"), Some(Format::M3u));
+		assert_eq!(Format::from_name("list.txt"), None);
+	}
+
+	#[test]
+	fn parses_foreign_playlists() {
+		assert_eq!(
+			Format::M3u.parse("\u{feff}#EXTM3U\r\n#EXTINF:1,A\r\na.mp3\r\n\r\nb.mp3\r\n"),
+			vec!["a.mp3", "b.mp3"]
+		);
+		assert_eq!(
+			Format::Pls.parse("[playlist]\nFile2=b.mp3\nTitle1=A\nFile1 = a.mp3\n"),
+			vec!["a.mp3", "b.mp3"]
+		);
+		assert_eq!(
+			Format::Xspf
+				.parse("<trackList><track><location>file:///x/a%20b.mp3</location></track></trackList>"),
+			vec!["file:///x/a b.mp3"]
+		);
+	}
+
+	#[test]
+	fn resolves_locations() {
+		let paths = ["Music/a.mp3", "Music/album/a.mp3", "Music/b.mp3"];
+		assert_eq!(resolve("album/a.mp3", paths.iter().copied()), Some(1));
+		assert_eq!(resolve("./a.mp3", paths.iter().copied()), Some(0));
+		assert_eq!(resolve("C:\\Music\\b.mp3", paths.iter().copied()), Some(2));
+		assert_eq!(
+			resolve("file:///home/me/Music/album/a%2Emp3", paths.iter().copied()),
+			Some(1)
+		);
+		assert_eq!(resolve("c.mp3", paths.iter().copied()), None);
+	}
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// Name profiles are exported under.
+pub const FILE_NAME: &str = "omnivyou-profiles.json";
+
+pub const MIME: &str = "application/json";
+
+/// Settings saved under a name, to switch between use cases at once.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Profile {
+	pub name: String,
+	pub settings: Settings,
+}
+
+/// Contents of an exported profiles file.
+#[derive(Deserialize, Serialize)]
+struct ProfileFile {
+	profiles: Vec<Profile>,
+}
+
+/// Writes profiles to a file which can be imported on other machines.
+pub fn export(profiles: &[Profile]) -> String {
+	serde_json::to_string_pretty(&ProfileFile {
+		profiles: profiles.to_vec(),
+	})
+	.expect("profiles serialize to JSON")
+}
+
+/// Reads the profiles of an exported file. Settings missing from the file keep their defaults, so
+/// files from older versions still import, and values out of range are brought within limits.
+pub fn import(contents: &str) -> Result<Vec<Profile>, String> {
+	let mut profiles = serde_json::from_str::<ProfileFile>(contents)
+		.map(|file| file.profiles)
+		.map_err(|err| err.to_string())?;
+	for profile in &mut profiles {
+		profile.settings.normalize();
+	}
+	Ok(profiles)
+}
+
+/// Adds profiles to a list sorted by name, replacing those with the same name.
+pub fn merge(profiles: &mut Vec<Profile>, added: impl IntoIterator<Item = Profile>) {
+	for profile in added {
+		match profiles.iter_mut().find(|p| p.name == profile.name) {
+			Some(existing) => *existing = profile,
+			None => profiles.push(profile),
+		}
+	}
+	profiles.sort_by(|a, b| a.name.cmp(&b.name));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{library::Mark, media::Kind};
+
+	fn profile(name: &str, rating: u8) -> Profile {
+		Profile {
+			name: String::from(name),
+			settings: Settings {
+				filter_rating: rating,
+				..Settings::default()
+			},
+		}
+	}
+
+	#[test]
+	fn round_trips() {
+		let mut signage = profile("Signage", 0);
+		let video = signage.settings.kind_rule_index(Kind::Video).unwrap();
+		signage.settings.rules[video].looping = true;
+		let profiles = vec![profile("Review", 3), signage];
+		assert_eq!(import(&export(&profiles)), Ok(profiles));
+	}
+
+	#[test]
+	fn imports_partial_settings() {
+		let profiles = import(r#"{"profiles":[{"name":"Music","settings":{"soundtrack_volume":80}}]}"#);
+		assert_eq!(
+			profiles,
+			Ok(vec![Profile {
+				name: String::from("Music"),
+				settings: Settings {
+					soundtrack_volume: 80,
+					..Settings::default()
+				},
+			}])
+		);
+		assert!(import("[]").is_err());
+	}
+
+	#[test]
+	fn repairs_imported_settings() {
+		let profiles = import(
+			r#"{"profiles":[{"name":"x","settings":{"rules":[],"filter_rating":9,"page_size":0,"soundtrack_volume":200}}]}"#,
+		)
+		.unwrap();
+		let settings = &profiles[0].settings;
+		assert_eq!(settings.kind_rule(Kind::Video), None);
+		assert!(!settings.passes("video/mp4", "a.mp4", Mark::default()));
+		assert_eq!(settings.filter_rating, 5);
+		assert_eq!(settings.page_size, 1);
+		assert_eq!(settings.soundtrack_volume, 100);
+	}
+
+	#[test]
+	fn merges_by_name() {
+		let mut profiles = vec![profile("Review", 3), profile("Signage", 0)];
+		merge(
+			&mut profiles,
+			vec![profile("Music", 0), profile("Review", 5)],
+		);
+		let names: Vec<_> = profiles.iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, vec!["Music", "Review", "Signage"]);
+		assert_eq!(profiles[1].settings.filter_rating, 5);
+	}
+}
@@ -0,0 +1,323 @@
+//! Settings encoded in URL query strings, so a slideshow can be shared as a link.
+//!
+//! Only settings differing from the defaults are written. Rule settings are keyed by kind, and by
+//! matcher for narrowed down rules, e.g. `image.delay=5` or `image:.gif.loops=3`. The remaining
+//! settings are grouped as `filter.*`, `page.*`, `soundtrack.*` and `thumbnails.*`.
+
+use std::time::Duration;
+
+use crate::{
+	library::MAX_RATING,
+	media::Kind,
+	settings::{Matcher, Offset, Rule, RuleChange, Settings},
+	url,
+};
+
+fn kind_key(kind: Kind) -> &'static str {
+	match kind {
+		Kind::Audio => "audio",
+		Kind::Image => "image",
+		Kind::Video => "video",
+	}
+}
+
+fn rule_key(rule: &Rule) -> String {
+	match &rule.matcher {
+		Some(matcher) => format!("{}:{}", kind_key(rule.kind), matcher),
+		None => String::from(kind_key(rule.kind)),
+	}
+}
+
+fn parse_rule_key(key: &str) -> Option<(Kind, Option<Matcher>)> {
+	let (kind, matcher) = match key.split_once(':') {
+		Some((kind, matcher)) => (kind, Some(Matcher::parse(matcher)?)),
+		None => (key, None),
+	};
+	let kind = Kind::ALL.iter().copied().find(|&k| kind_key(k) == kind)?;
+	Some((kind, matcher))
+}
+
+fn seconds(duration: Duration) -> String {
+	duration.as_secs_f64().to_string()
+}
+
+fn parse_seconds(value: &str) -> Option<Duration> {
+	value
+		.parse::<f64>()
+		.ok()
+		.and_then(|s| Duration::try_from_secs_f64(s).ok())
+}
+
+fn rule_params(rule: &Rule, params: &mut Vec<(String, String)>) {
+	let default = Rule::new(rule.kind, rule.matcher.clone());
+	let key = rule_key(rule);
+	let mut param = |field: &str, value: String| params.push((format!("{}.{}", key, field), value));
+	// Narrowed down rules only exist when asked for, so they are always written.
+	if rule.enabled != default.enabled || rule.matcher.is_some() {
+		param("enabled", rule.enabled.to_string());
+	}
+	if rule.autoplay != default.autoplay {
+		param("autoplay", rule.autoplay.to_string());
+	}
+	if rule.delay != default.delay {
+		param("delay", seconds(rule.delay));
+	}
+	if let Some(max) = rule.max_display_time {
+		param("max", seconds(max));
+	}
+	match rule.offset {
+		Offset::Start => {}
+		Offset::Fixed(offset) => param("offset", seconds(offset)),
+		Offset::Random => param("offset", String::from("random")),
+	}
+	if rule.volume != default.volume {
+		param("volume", rule.volume.to_string());
+	}
+	if rule.looping != default.looping {
+		param("loop", rule.looping.to_string());
+	}
+	if let Some(loops) = rule.animation_loops {
+		param("loops", loops.to_string());
+	}
+}
+
+/// Query string, without the leading `?`, holding the settings which differ from the defaults.
+pub fn encode(settings: &Settings) -> String {
+	let default = Settings::default();
+	let mut params = Vec::new();
+	for rule in &settings.rules {
+		rule_params(rule, &mut params);
+	}
+	if settings.filter_favorites != default.filter_favorites {
+		params.push((
+			String::from("filter.favorites"),
+			settings.filter_favorites.to_string(),
+		));
+	}
+	if settings.filter_rating != default.filter_rating {
+		params.push((
+			String::from("filter.rating"),
+			settings.filter_rating.to_string(),
+		));
+	}
+	if settings.page_by_folder != default.page_by_folder {
+		params.push((
+			String::from("page.folders"),
+			settings.page_by_folder.to_string(),
+		));
+	}
+	if settings.page_size != default.page_size {
+		params.push((String::from("page.size"), settings.page_size.to_string()));
+	}
+	if settings.soundtrack_fade != default.soundtrack_fade {
+		params.push((
+			String::from("soundtrack.fade"),
+			seconds(settings.soundtrack_fade),
+		));
+	}
+	if settings.soundtrack_sync != default.soundtrack_sync {
+		params.push((
+			String::from("soundtrack.sync"),
+			settings.soundtrack_sync.to_string(),
+		));
+	}
+	if settings.soundtrack_volume != default.soundtrack_volume {
+		params.push((
+			String::from("soundtrack.volume"),
+			settings.soundtrack_volume.to_string(),
+		));
+	}
+	if settings.thumbnail_cache_size != default.thumbnail_cache_size {
+		params.push((
+			String::from("thumbnails.cache"),
+			settings.thumbnail_cache_size.to_string(),
+		));
+	}
+	if settings.thumbnail_offset != default.thumbnail_offset {
+		params.push((
+			String::from("thumbnails.offset"),
+			seconds(settings.thumbnail_offset),
+		));
+	}
+	params
+		.iter()
+		.map(|(key, value)| format!("{}={}", url::encode(key), url::encode(value)))
+		.collect::<Vec<_>>()
+		.join("&")
+}
+
+fn set(rule: &mut Rule, field: &str, value: &str) -> bool {
+	// Toggles are set rather than toggled, so repeated parameters do not cancel out.
+	let flag = match field {
+		"autoplay" => Some(&mut rule.autoplay),
+		"enabled" => Some(&mut rule.enabled),
+		"loop" => Some(&mut rule.looping),
+		_ => None,
+	};
+	if let Some(flag) = flag {
+		return value.parse().map(|value| *flag = value).is_ok();
+	}
+	let change = match field {
+		"delay" => parse_seconds(value).map(RuleChange::Delay),
+		"max" => parse_seconds(value).map(|max| RuleChange::MaxDisplayTime(Some(max))),
+		"offset" => match value {
+			"start" => Some(RuleChange::Offset(Offset::Start)),
+			"random" => Some(RuleChange::Offset(Offset::Random)),
+			_ => parse_seconds(value).map(|offset| RuleChange::Offset(Offset::Fixed(offset))),
+		},
+		"volume" => value.parse().ok().map(RuleChange::Volume),
+		"loops" => value
+			.parse()
+			.ok()
+			.map(|loops| RuleChange::AnimationLoops(Some(loops))),
+		_ => None,
+	};
+	change.map(|change| rule.apply(change)).is_some()
+}
+
+fn apply_rule(settings: &mut Settings, key: &str, field: &str, value: &str) -> bool {
+	let (kind, matcher) = match parse_rule_key(key) {
+		Some(rule) => rule,
+		None => return false,
+	};
+	let index = settings
+		.rules
+		.iter()
+		.position(|r| r.kind == kind && r.matcher == matcher);
+	// Rules are only added once a setting of theirs is understood.
+	let mut rule = match index {
+		Some(index) => settings.rules[index].clone(),
+		None => Rule::new(kind, matcher),
+	};
+	if !set(&mut rule, field, value) {
+		return false;
+	}
+	match index {
+		Some(index) => settings.rules[index] = rule,
+		None => settings.rules.push(rule),
+	}
+	true
+}
+
+fn apply(settings: &mut Settings, key: &str, value: &str) -> bool {
+	match key {
+		"filter.favorites" => value.parse().map(|v| settings.filter_favorites = v).is_ok(),
+		"filter.rating" => value
+			.parse::<u8>()
+			.map(|v| settings.filter_rating = v.min(MAX_RATING))
+			.is_ok(),
+		"page.folders" => value.parse().map(|v| settings.page_by_folder = v).is_ok(),
+		"page.size" => value
+			.parse::<u32>()
+			.map(|v| settings.page_size = v.max(1))
+			.is_ok(),
+		"soundtrack.fade" => parse_seconds(value)
+			.map(|v| settings.soundtrack_fade = v)
+			.is_some(),
+		"soundtrack.sync" => value.parse().map(|v| settings.soundtrack_sync = v).is_ok(),
+		"soundtrack.volume" => value
+			.parse::<u8>()
+			.map(|v| settings.soundtrack_volume = v.min(100))
+			.is_ok(),
+		"thumbnails.cache" => value
+			.parse()
+			.map(|v| settings.thumbnail_cache_size = v)
+			.is_ok(),
+		"thumbnails.offset" => parse_seconds(value)
+			.map(|v| settings.thumbnail_offset = v)
+			.is_some(),
+		_ => match key.rsplit_once('.') {
+			Some((rule, field)) => apply_rule(settings, rule, field, value),
+			None => false,
+		},
+	}
+}
+
+/// Settings held by a query string, with or without the leading `?`, on top of the defaults.
+/// Parameters which are not understood are skipped and handed back.
+pub fn decode(query: &str) -> (Settings, Vec<String>) {
+	let mut settings = Settings::default();
+	let mut ignored = Vec::new();
+	for param in query.trim_start_matches('?').split('&') {
+		if param.is_empty() {
+			continue;
+		}
+		let (key, value) = param.split_once('=').unwrap_or((param, ""));
+		let (key, value) = (url::decode(key), url::decode(value));
+		if !apply(&mut settings, &key, &value) {
+			ignored.push(key);
+		}
+	}
+	(settings, ignored)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn defaults_are_left_out() {
+		assert_eq!(encode(&Settings::default()), "");
+		assert_eq!(decode(""), (Settings::default(), Vec::new()));
+	}
+
+	#[test]
+	fn round_trips() {
+		let mut settings = Settings::default();
+		let video = settings.kind_rule_index(Kind::Video).unwrap();
+		settings.rules[video].apply(RuleChange::ToggleAutoplay);
+		settings.rules[video].apply(RuleChange::MaxDisplayTime(Some(Duration::from_secs(10))));
+		settings.rules[video].apply(RuleChange::Offset(Offset::Random));
+		settings.rules[video].apply(RuleChange::Volume(30));
+		let image = settings.kind_rule_index(Kind::Image).unwrap();
+		settings.rules[image].apply(RuleChange::Delay(Duration::from_millis(2500)));
+		let mut gif = Rule::new(Kind::Image, Matcher::parse(".gif"));
+		gif.apply(RuleChange::AnimationLoops(Some(3)));
+		settings.rules.push(gif);
+		settings
+			.rules
+			.push(Rule::new(Kind::Video, Matcher::parse("video/webm")));
+		settings.filter_favorites = true;
+		settings.filter_rating = 4;
+		settings.page_size = 50;
+		settings.soundtrack_sync = true;
+		settings.thumbnail_offset = Duration::from_millis(500);
+		let query = encode(&settings);
+		assert_eq!(
+			query,
+			"video.autoplay=false&video.max=10&video.offset=random&video.volume=30&\
+			image.delay=2.5&image%3A.gif.enabled=true&image%3A.gif.loops=3&\
+			video%3Avideo/webm.enabled=true&filter.favorites=true&filter.rating=4&page.size=50&\
+			soundtrack.sync=true&thumbnails.offset=0.5"
+		);
+		assert_eq!(decode(&format!("?{}", query)), (settings, Vec::new()));
+	}
+
+	#[test]
+	fn skips_unknown_parameters() {
+		let (settings, ignored) =
+			decode("image.delay=5&shuffle=true&video.volume=loud&sound:.x.delay=1&image:.gif.delay=x");
+		assert_eq!(
+			settings.kind_rule(Kind::Image).unwrap().delay,
+			Duration::from_secs(5)
+		);
+		assert_eq!(
+			ignored,
+			vec![
+				String::from("shuffle"),
+				String::from("video.volume"),
+				String::from("sound:.x.delay"),
+				String::from("image:.gif.delay")
+			]
+		);
+		assert_eq!(settings.rules.len(), Kind::ALL.len());
+	}
+
+	#[test]
+	fn clamps_values() {
+		let (settings, _) = decode("filter.rating=9&audio.volume=200&soundtrack.volume=101");
+		assert_eq!(settings.filter_rating, MAX_RATING);
+		assert_eq!(settings.kind_rule(Kind::Audio).unwrap().volume, 100);
+		assert_eq!(settings.soundtrack_volume, 100);
+	}
+}
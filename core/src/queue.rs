@@ -0,0 +1,258 @@
+use std::{cmp::Ordering, ops::Range};
+
+use crate::library::merge_sorted;
+
+/// Non-empty list of items being played, with the position of the current one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Queue<T> {
+	index: usize,
+	items: Vec<T>,
+}
+
+impl<T> Queue<T> {
+	/// Starts playing from the first item. There is nothing to play if there are no items.
+	pub fn new(items: Vec<T>) -> Option<Self> {
+		if items.is_empty() {
+			None
+		} else {
+			Some(Self { index: 0, items })
+		}
+	}
+
+	pub fn current(&self) -> &T {
+		&self.items[self.index]
+	}
+
+	pub fn index(&self) -> usize {
+		self.index
+	}
+
+	pub fn items(&self) -> &[T] {
+		&self.items
+	}
+
+	/// Positions of the items around the current one, up to `radius` on either side. Near either end
+	/// the range shifts to keep its width, so that it does not shrink as the end comes closer.
+	pub fn window(&self, radius: usize) -> Range<usize> {
+		let width = (radius * 2 + 1).min(self.items.len());
+		let start = self
+			.index
+			.saturating_sub(radius)
+			.min(self.items.len() - width);
+		start..start + width
+	}
+
+	/// Items which can be changed in place, though not added or removed.
+	pub fn items_mut(&mut self) -> &mut [T] {
+		&mut self.items
+	}
+
+	/// Adds items after the last one, leaving the current item as it is.
+	pub fn extend(&mut self, items: impl IntoIterator<Item = T>) {
+		self.items.extend(items);
+	}
+
+	/// Keeps the items passing a predicate, and nothing if none do. The current item stays current if
+	/// it is kept, and the following kept item takes its place otherwise.
+	pub fn retain(self, mut keep: impl FnMut(&T) -> bool) -> Option<Self> {
+		let mut index = 0;
+		let mut items = Vec::new();
+		for (i, item) in self.items.into_iter().enumerate() {
+			if i == self.index {
+				index = items.len();
+			}
+			if keep(&item) {
+				items.push(item);
+			}
+		}
+		let mut queue = Self::new(items)?;
+		queue.index = index % queue.items.len();
+		Some(queue)
+	}
+
+	/// Sorts the items, keeping the current one current.
+	pub fn sort_by(&mut self, mut compare: impl FnMut(&T, &T) -> Ordering) {
+		let current = self.index;
+		let mut items: Vec<(usize, T)> = self.items.drain(..).enumerate().collect();
+		items.sort_by(|(_, a), (_, b)| compare(a, b));
+		self.index = items.iter().position(|(i, _)| *i == current).unwrap_or(0);
+		self.items = items.into_iter().map(|(_, item)| item).collect();
+	}
+
+	/// Merges sorted items into the sorted queue, keeping the current item current.
+	pub fn merge(&mut self, items: Vec<T>, mut compare: impl FnMut(&T, &T) -> Ordering) {
+		let current = &self.items[self.index];
+		let before = items.partition_point(|item| compare(item, current) == Ordering::Less);
+		merge_sorted(&mut self.items, items, compare);
+		self.index += before;
+	}
+
+	/// Moves to the item at the given position, if there is one.
+	pub fn jump(&mut self, index: usize) -> bool {
+		if index < self.items.len() {
+			self.index = index;
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Moves by the given number of items, stopping at either end rather than wrapping around.
+	pub fn skip(&mut self, offset: isize) {
+		let last = self.items.len() as isize - 1;
+		self.index = (self.index as isize + offset).clamp(0, last) as usize;
+	}
+
+	/// Position of the first of the items leading up to the given one which share its key.
+	fn group_start<K: PartialEq>(&self, index: usize, key: &impl Fn(&T) -> K) -> usize {
+		let group = key(&self.items[index]);
+		(0..index)
+			.rev()
+			.find(|&i| key(&self.items[i]) != group)
+			.map_or(0, |i| i + 1)
+	}
+
+	/// Moves to the first item of the following group of items sharing a key, or of the current
+	/// group when going back, or of the preceding one when already there. Stops at either end.
+	pub fn skip_group<K: PartialEq>(&mut self, forward: bool, key: impl Fn(&T) -> K) {
+		self.index = if forward {
+			let group = key(self.current());
+			(self.index + 1..self.items.len())
+				.find(|&i| key(&self.items[i]) != group)
+				.unwrap_or(self.items.len() - 1)
+		} else {
+			match self.group_start(self.index, &key) {
+				start if start < self.index => start,
+				0 => 0,
+				start => self.group_start(start - 1, &key),
+			}
+		};
+	}
+
+	/// Moves to the following item, wrapping around after the last one.
+	pub fn next(&mut self) {
+		self.index = (self.index + 1) % self.items.len();
+	}
+
+	/// Moves to the preceding item, wrapping around before the first one.
+	pub fn previous(&mut self) {
+		self.index = (self.index + self.items.len() - 1) % self.items.len();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn refuses_empty_queues() {
+		assert!(Queue::<u8>::new(Vec::new()).is_none());
+	}
+
+	#[test]
+	fn wraps_around() {
+		let mut queue = Queue::new(vec!['a', 'b', 'c']).unwrap();
+		assert_eq!(*queue.current(), 'a');
+		queue.previous();
+		assert_eq!(*queue.current(), 'c');
+		queue.next();
+		queue.next();
+		assert_eq!((queue.index(), *queue.current()), (1, 'b'));
+	}
+
+	#[test]
+	fn jumps_within_bounds() {
+		let mut queue = Queue::new(vec![1, 2, 3]).unwrap();
+		assert!(queue.jump(2));
+		assert_eq!(*queue.current(), 3);
+		assert!(!queue.jump(3));
+		assert_eq!(queue.index(), 2);
+		assert_eq!(queue.items(), &[1, 2, 3]);
+	}
+
+	#[test]
+	fn retains_around_the_current_item() {
+		let mut queue = Queue::new(vec![1, 2, 3, 4]).unwrap();
+		queue.jump(1);
+		let queue = queue.retain(|&i| i != 2).unwrap();
+		assert_eq!((queue.items(), *queue.current()), (&[1, 3, 4][..], 3));
+		let mut queue = queue;
+		queue.jump(2);
+		let queue = queue.retain(|&i| i != 4).unwrap();
+		assert_eq!(*queue.current(), 1);
+		assert!(queue.retain(|_| false).is_none());
+	}
+
+	#[test]
+	fn sorts_around_the_current_item() {
+		let mut queue = Queue::new(vec![3, 1]).unwrap();
+		queue.extend(vec![2]);
+		queue.sort_by(|a, b| a.cmp(b));
+		assert_eq!((queue.items(), *queue.current()), (&[1, 2, 3][..], 3));
+	}
+
+	#[test]
+	fn merges_around_the_current_item() {
+		let mut queue = Queue::new(vec![2, 4, 6]).unwrap();
+		queue.next();
+		queue.merge(vec![1, 3, 4, 7], |a, b| a.cmp(b));
+		assert_eq!(
+			(queue.items(), *queue.current(), queue.index()),
+			(&[1, 2, 3, 4, 4, 6, 7][..], 4, 3)
+		);
+	}
+
+	#[test]
+	fn skips_within_bounds() {
+		let mut queue = Queue::new((0..10).collect()).unwrap();
+		queue.skip(4);
+		assert_eq!(queue.index(), 4);
+		queue.skip(10);
+		assert_eq!(queue.index(), 9);
+		queue.skip(-3);
+		assert_eq!(queue.index(), 6);
+		queue.skip(-10);
+		assert_eq!(queue.index(), 0);
+	}
+
+	#[test]
+	fn skips_groups() {
+		let mut queue = Queue::new(vec!["a/1", "a/2", "b/1", "b/2", "b/3", "c/1"]).unwrap();
+		let folder = |path: &&str| path.split('/').next().unwrap().to_string();
+		queue.skip_group(true, folder);
+		assert_eq!(*queue.current(), "b/1");
+		queue.skip_group(true, folder);
+		assert_eq!(*queue.current(), "c/1");
+		queue.skip_group(true, folder);
+		assert_eq!(*queue.current(), "c/1");
+		queue.jump(3);
+		queue.skip_group(false, folder);
+		assert_eq!(*queue.current(), "b/1");
+		queue.skip_group(false, folder);
+		assert_eq!(*queue.current(), "a/1");
+		queue.skip_group(false, folder);
+		assert_eq!(*queue.current(), "a/1");
+	}
+
+	#[test]
+	fn windows_around_the_current_item() {
+		let mut queue = Queue::new((0..10).collect()).unwrap();
+		assert_eq!(queue.window(2), 0..5);
+		queue.jump(5);
+		assert_eq!(queue.window(2), 3..8);
+		queue.jump(9);
+		assert_eq!(queue.window(2), 5..10);
+		assert_eq!(queue.window(20), 0..10);
+		assert_eq!(Queue::new(vec![1]).unwrap().window(0), 0..1);
+	}
+
+	#[test]
+	fn extends_past_the_end() {
+		let mut queue = Queue::new(vec![1, 2]).unwrap();
+		queue.next();
+		queue.extend(vec![3]);
+		assert_eq!(*queue.current(), 2);
+		queue.next();
+		assert_eq!(*queue.current(), 3);
+	}
+}
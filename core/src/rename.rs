@@ -0,0 +1,363 @@
+//! Renaming files in batches, after templates filled in from their metadata.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{cull::Move, exif::DateTime, library::folder_of};
+
+pub const DEFAULT_TEMPLATE: &str = "{date:%Y%m%d}_{seq:04}_{name}.{ext}";
+
+/// Pattern dates are written with when a template leaves it out.
+const DEFAULT_DATE_PATTERN: &str = "%Y-%m-%d";
+
+/// Characters which some file systems do not allow in names.
+const FORBIDDEN: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Metadata templates are filled in from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metadata {
+	/// When the file was captured according to its tags, or else last changed.
+	pub date: DateTime,
+	pub camera: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Part {
+	Text(String),
+	Camera,
+	Date(String),
+	Extension,
+	Name,
+	/// Position in the batch, padded with zeros to a width.
+	Sequence(usize),
+}
+
+/// Name template, made of text and fields in braces such as `{date:%Y%m%d}`, `{seq:04}`,
+/// `{name}`, `{ext}` and `{camera}`. Braces themselves are written doubled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template(Vec<Part>);
+
+impl Template {
+	pub fn parse(template: &str) -> Result<Self, String> {
+		let mut parts = Vec::new();
+		let mut text = String::new();
+		let mut chars = template.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'{' if chars.peek() == Some(&'{') => {
+					chars.next();
+					text.push('{');
+				}
+				'}' if chars.peek() == Some(&'}') => {
+					chars.next();
+					text.push('}');
+				}
+				'{' => {
+					let mut field = String::new();
+					loop {
+						match chars.next() {
+							Some('}') => break,
+							Some(c) => field.push(c),
+							None => return Err(String::from("Unmatched '{'")),
+						}
+					}
+					if !text.is_empty() {
+						parts.push(Part::Text(std::mem::take(&mut text)));
+					}
+					parts.push(Self::field(&field)?);
+				}
+				'}' => return Err(String::from("Unmatched '}'")),
+				c => text.push(c),
+			}
+		}
+		if !text.is_empty() {
+			parts.push(Part::Text(text));
+		}
+		Ok(Self(parts))
+	}
+
+	fn field(field: &str) -> Result<Part, String> {
+		let (name, format) = match field.split_once(':') {
+			Some((name, format)) => (name, Some(format)),
+			None => (field, None),
+		};
+		match (name.trim(), format) {
+			("camera", None) => Ok(Part::Camera),
+			("date", format) => Ok(Part::Date(
+				format.unwrap_or(DEFAULT_DATE_PATTERN).to_string(),
+			)),
+			("ext", None) => Ok(Part::Extension),
+			("name", None) => Ok(Part::Name),
+			("seq", None) => Ok(Part::Sequence(1)),
+			("seq", Some(width)) => width
+				.parse()
+				.map(Part::Sequence)
+				.map_err(|_| format!("Invalid sequence width '{}'", width)),
+			("camera", Some(_)) | ("ext", Some(_)) | ("name", Some(_)) => {
+				Err(format!("'{}' takes no format", name))
+			}
+			_ => Err(format!("Unknown field '{{{}}}'", field)),
+		}
+	}
+
+	/// New name of a file, given its current name, its metadata and its position in the batch,
+	/// counting from one.
+	pub fn render(&self, name: &str, metadata: &Metadata, sequence: usize) -> String {
+		let (stem, extension) = match name.rfind('.').filter(|&i| i > 0) {
+			Some(dot) => (&name[..dot], &name[dot + 1..]),
+			None => (name, ""),
+		};
+		let mut rendered = String::new();
+		for part in &self.0 {
+			match part {
+				Part::Text(text) => rendered.push_str(text),
+				Part::Camera => rendered.push_str(metadata.camera.as_deref().unwrap_or_default()),
+				Part::Date(pattern) => rendered.push_str(&metadata.date.format(pattern)),
+				Part::Extension => rendered.push_str(extension),
+				Part::Name => rendered.push_str(stem),
+				Part::Sequence(width) => rendered.push_str(&format!("{:0width$}", sequence, width = width)),
+			}
+		}
+		// Files without an extension would otherwise end with a dot.
+		rendered
+			.trim_end_matches(|c: char| c == '.' || c.is_whitespace())
+			.to_string()
+	}
+}
+
+/// Outcome of a rename, as shown before it is applied.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+	/// The file is named as the template would have it already.
+	Unchanged,
+	Ready,
+	/// The new name cannot be used for files.
+	Invalid,
+	/// The new name is taken by another file, or by another file of the batch.
+	Collision,
+}
+
+impl Status {
+	pub fn applies(self) -> bool {
+		self == Self::Ready
+	}
+}
+
+/// Path of a file renamed within its folder.
+pub fn renamed_path(path: &str, name: &str) -> String {
+	match folder_of(path) {
+		"" => name.to_string(),
+		folder => format!("{}/{}", folder, name),
+	}
+}
+
+fn valid_name(name: &str) -> bool {
+	!name.is_empty()
+		&& name != "."
+		&& name != ".."
+		&& !name
+			.chars()
+			.any(|c| FORBIDDEN.contains(&c) || c.is_control())
+}
+
+/// Checks renames against one another and against the paths of the other files. Names are compared
+/// regardless of case, which some file systems ignore.
+pub fn check<'a>(renames: &[Move], paths: impl IntoIterator<Item = &'a str>) -> Vec<Status> {
+	let paths: Vec<String> = paths.into_iter().map(str::to_lowercase).collect();
+	let mut targets: HashMap<String, usize> = HashMap::new();
+	for rename in renames {
+		*targets.entry(rename.to.to_lowercase()).or_default() += 1;
+	}
+	let mut moving: HashSet<String> = renames
+		.iter()
+		.filter(|rename| rename.from != rename.to)
+		.map(|rename| rename.from.to_lowercase())
+		.collect();
+	// Files which cannot be renamed keep their names, which may leave other renames colliding.
+	loop {
+		let taken: HashSet<&String> = paths
+			.iter()
+			.filter(|path| !moving.contains(*path))
+			.collect();
+		let statuses: Vec<Status> = renames
+			.iter()
+			.map(|rename| {
+				let to = rename.to.to_lowercase();
+				if rename.from == rename.to {
+					Status::Unchanged
+				} else if !valid_name(rename.to.rsplit('/').next().unwrap_or_default()) {
+					Status::Invalid
+				} else if taken.contains(&to) || targets[&to] > 1 {
+					Status::Collision
+				} else {
+					Status::Ready
+				}
+			})
+			.collect();
+		let ready: HashSet<String> = renames
+			.iter()
+			.zip(&statuses)
+			.filter(|(_, status)| status.applies())
+			.map(|(rename, _)| rename.from.to_lowercase())
+			.collect();
+		if ready == moving {
+			return statuses;
+		}
+		moving = ready;
+	}
+}
+
+/// Name a file is given while renames go around in a cycle.
+fn temporary_path(path: &str) -> String {
+	let name = path.rsplit('/').next().unwrap_or_default();
+	renamed_path(path, &format!(".{}.renaming", name))
+}
+
+/// Whether a path is a temporary name given while renames go around in a cycle.
+pub fn is_temporary(path: &str) -> bool {
+	let name = path.rsplit('/').next().unwrap_or_default();
+	name.starts_with('.') && name.ends_with(".renaming")
+}
+
+/// Orders renames so that none of them takes the name of a file which is still to be renamed.
+/// Renames going around in a cycle, or changing case only, go through a temporary name first.
+pub fn steps(renames: &[Move]) -> Vec<Move> {
+	let mut pending: Vec<Move> = renames.to_vec();
+	let mut steps = Vec::new();
+	while !pending.is_empty() {
+		let free = pending.iter().position(|rename| {
+			let to = rename.to.to_lowercase();
+			!pending.iter().any(|other| other.from.to_lowercase() == to)
+		});
+		match free {
+			Some(i) => steps.push(pending.remove(i)),
+			None => {
+				let rename = &mut pending[0];
+				let temporary = temporary_path(&rename.from);
+				steps.push(Move {
+					from: std::mem::replace(&mut rename.from, temporary.clone()),
+					to: temporary,
+				});
+			}
+		}
+	}
+	steps
+}
+
+/// Steps undoing renames which were applied.
+pub fn reversed(steps: &[Move]) -> Vec<Move> {
+	steps
+		.iter()
+		.rev()
+		.map(|step| Move {
+			from: step.to.clone(),
+			to: step.from.clone(),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metadata() -> Metadata {
+		Metadata {
+			date: DateTime {
+				year: 2021,
+				month: 6,
+				day: 5,
+				hour: 14,
+				minute: 3,
+				second: 9,
+			},
+			camera: Some(String::from("Camera")),
+		}
+	}
+
+	fn rename(from: &str, to: &str) -> Move {
+		Move {
+			from: String::from(from),
+			to: String::from(to),
+		}
+	}
+
+	#[test]
+	fn renders_templates() {
+		let template = Template::parse(DEFAULT_TEMPLATE).unwrap();
+		assert_eq!(
+			template.render("IMG_1.jpg", &metadata(), 7),
+			"20210605_0007_IMG_1.jpg"
+		);
+		let template = Template::parse("{{{camera}}} {date} {seq}.{ext}").unwrap();
+		assert_eq!(
+			template.render("README", &metadata(), 12),
+			"{Camera} 2021-06-05 12"
+		);
+		assert!(Template::parse("{size}").is_err());
+		assert!(Template::parse("{seq:x}").is_err());
+		assert!(Template::parse("{name:x}").is_err());
+		assert!(Template::parse("name}").is_err());
+		assert_eq!(Template::parse("{name"), Err(String::from("Unmatched '{'")));
+	}
+
+	#[test]
+	fn checks_for_collisions() {
+		let renames = vec![
+			rename("a/1.jpg", "a/x.jpg"),
+			rename("a/2.jpg", "a/X.jpg"),
+			rename("a/3.jpg", "a/4.jpg"),
+			// The file at 6 is renamed away.
+			rename("a/5.jpg", "a/6.jpg"),
+			rename("a/6.jpg", "a/9.jpg"),
+			rename("a/7.jpg", "a/7.jpg"),
+			rename("a/8.jpg", "a/?.jpg"),
+			// The file at 8 stays, since its new name is invalid.
+			rename("a/10.jpg", "a/8.jpg"),
+		];
+		let paths = vec![
+			"a/1.jpg", "a/2.jpg", "a/3.jpg", "a/4.jpg", "a/5.jpg", "a/6.jpg", "a/8.jpg",
+		];
+		assert_eq!(
+			check(&renames, paths),
+			vec![
+				Status::Collision,
+				Status::Collision,
+				Status::Collision,
+				Status::Ready,
+				Status::Ready,
+				Status::Unchanged,
+				Status::Invalid,
+				Status::Collision,
+			]
+		);
+	}
+
+	#[test]
+	fn orders_renames() {
+		let renames = vec![rename("a/1", "a/2"), rename("a/2", "a/3")];
+		assert_eq!(
+			steps(&renames),
+			vec![rename("a/2", "a/3"), rename("a/1", "a/2")]
+		);
+		let swap = vec![rename("a/1", "a/2"), rename("a/2", "a/1")];
+		let swapped = vec![
+			rename("a/1", "a/.1.renaming"),
+			rename("a/2", "a/1"),
+			rename("a/.1.renaming", "a/2"),
+		];
+		assert_eq!(steps(&swap), swapped);
+		assert!(is_temporary(&swapped[0].to));
+		assert!(!is_temporary(&swapped[1].to));
+		assert_eq!(
+			steps(&[rename("A", "a")]),
+			vec![rename("A", ".A.renaming"), rename(".A.renaming", "a")]
+		);
+		assert_eq!(
+			reversed(&swapped),
+			vec![
+				rename("a/2", "a/.1.renaming"),
+				rename("a/1", "a/2"),
+				rename("a/.1.renaming", "a/1"),
+			]
+		);
+	}
+}
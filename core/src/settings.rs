@@ -0,0 +1,428 @@
+use std::{
+	fmt::{self, Display, Formatter},
+	time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	library::{Mark, MAX_RATING},
+	media::Kind,
+};
+
+pub const MAX_DELAY_SECONDS: Duration = Duration::from_millis(u32::MAX as u64);
+
+/// Serializes durations as fractional seconds, which keeps settings short and readable.
+mod seconds {
+	use std::time::Duration;
+
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(duration.as_secs_f64())
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+		let seconds = f64::deserialize(deserializer)?;
+		Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom)
+	}
+
+	pub mod option {
+		use std::time::Duration;
+
+		use serde::{Deserialize, Deserializer, Serializer};
+
+		pub fn serialize<S: Serializer>(
+			duration: &Option<Duration>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			match duration {
+				Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+				None => serializer.serialize_none(),
+			}
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<Duration>, D::Error> {
+			Option::<f64>::deserialize(deserializer)?
+				.map(|seconds| Duration::try_from_secs_f64(seconds).map_err(serde::de::Error::custom))
+				.transpose()
+		}
+	}
+}
+
+/// Narrows a rule down to some files of its kind.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Matcher {
+	/// Lowercase file extension without the leading dot.
+	Extension(String),
+	/// Exact MIME type, e.g. `image/gif`.
+	Mime(String),
+}
+
+impl Matcher {
+	/// Parses user input, where anything containing a `/` is taken as a MIME type.
+	pub fn parse(s: &str) -> Option<Self> {
+		let s = s.trim().to_ascii_lowercase();
+		if s.contains('/') {
+			Some(Matcher::Mime(s))
+		} else {
+			let extension = s.trim_start_matches('.');
+			if extension.is_empty() {
+				None
+			} else {
+				Some(Matcher::Extension(extension.to_string()))
+			}
+		}
+	}
+
+	fn matches(&self, mime: &str, name: &str) -> bool {
+		match self {
+			Matcher::Extension(extension) => name
+				.rsplit_once('.')
+				.is_some_and(|(_, e)| e.eq_ignore_ascii_case(extension)),
+			Matcher::Mime(m) => mime.eq_ignore_ascii_case(m),
+		}
+	}
+
+	/// Higher is more specific.
+	fn specificity(&self) -> u8 {
+		match self {
+			Matcher::Extension(_) => 1,
+			Matcher::Mime(_) => 2,
+		}
+	}
+}
+
+impl Display for Matcher {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Matcher::Extension(extension) => write!(f, ".{}", extension),
+			Matcher::Mime(mime) => write!(f, "{}", mime),
+		}
+	}
+}
+
+/// Where playback starts when items are only sampled for their maximum play time.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Offset {
+	Start,
+	/// Fixed position, moved back if the remainder would be shorter than the maximum play time.
+	Fixed(#[serde(with = "seconds")] Duration),
+	/// Random position leaving room for the maximum play time.
+	Random,
+}
+
+impl Offset {
+	/// Position in seconds to start playing an item of the given duration from, where `random` is
+	/// a number between 0 and 1 picking the position of random offsets.
+	pub fn position(&self, duration: f64, max_display_time: Duration, random: f64) -> f64 {
+		if !duration.is_finite() {
+			return 0.0;
+		}
+		let latest = (duration - max_display_time.as_secs_f64()).max(0.0);
+		match self {
+			Offset::Start => 0.0,
+			Offset::Fixed(offset) => offset.as_secs_f64().min(latest),
+			Offset::Random => random.clamp(0.0, 1.0) * latest,
+		}
+	}
+}
+
+/// Playback behaviour for files of a kind, optionally narrowed down by a matcher.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Rule {
+	/// Animated photos end after playing this many loops rather than as soon as they are shown.
+	pub animation_loops: Option<u32>,
+	pub autoplay: bool,
+	/// Time to wait after an item ended before advancing. Photos end as soon as they are shown.
+	#[serde(with = "seconds")]
+	pub delay: Duration,
+	pub enabled: bool,
+	pub kind: Kind,
+	pub looping: bool,
+	pub matcher: Option<Matcher>,
+	/// Time after which an item is considered ended even though it is still playing.
+	#[serde(with = "seconds::option")]
+	pub max_display_time: Option<Duration>,
+	/// Start position of items sampled for `max_display_time`.
+	pub offset: Offset,
+	/// Volume in percent.
+	pub volume: u8,
+}
+
+impl Rule {
+	pub fn new(kind: Kind, matcher: Option<Matcher>) -> Self {
+		Self {
+			animation_loops: None,
+			autoplay: true,
+			delay: match kind {
+				Kind::Image => Duration::from_secs(2),
+				_ => Duration::ZERO,
+			},
+			enabled: true,
+			kind,
+			looping: false,
+			matcher,
+			max_display_time: None,
+			offset: Offset::Start,
+			volume: 100,
+		}
+	}
+
+	pub fn apply(&mut self, change: RuleChange) {
+		match change {
+			RuleChange::AnimationLoops(loops) => self.animation_loops = loops,
+			RuleChange::Delay(delay) => self.delay = delay.min(MAX_DELAY_SECONDS),
+			RuleChange::MaxDisplayTime(max) => {
+				self.max_display_time = max.map(|m| m.min(MAX_DELAY_SECONDS))
+			}
+			RuleChange::Offset(offset) => {
+				self.offset = match offset {
+					Offset::Fixed(o) => Offset::Fixed(o.min(MAX_DELAY_SECONDS)),
+					_ => offset,
+				}
+			}
+			RuleChange::ToggleAutoplay => self.autoplay = !self.autoplay,
+			RuleChange::ToggleEnabled => self.enabled = !self.enabled,
+			RuleChange::ToggleLoop => self.looping = !self.looping,
+			RuleChange::Volume(volume) => self.volume = volume.min(100),
+		}
+	}
+
+	pub fn name(&self) -> String {
+		match &self.matcher {
+			Some(matcher) => format!("{} ({})", self.kind.name(), matcher),
+			None => String::from(self.kind.name()),
+		}
+	}
+}
+
+/// Edit to a single field of a rule.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleChange {
+	AnimationLoops(Option<u32>),
+	Delay(Duration),
+	MaxDisplayTime(Option<Duration>),
+	Offset(Offset),
+	ToggleAutoplay,
+	ToggleEnabled,
+	ToggleLoop,
+	Volume(u8),
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Settings {
+	pub filter_favorites: bool,
+	pub filter_rating: u8,
+	/// Whether Page Up and Page Down move to the previous and next folder rather than by a number of
+	/// items.
+	pub page_by_folder: bool,
+	/// Number of items Page Up and Page Down move by.
+	pub page_size: u32,
+	/// Per media type rules, starting with one rule without matcher for every kind.
+	pub rules: Vec<Rule>,
+	#[serde(with = "seconds")]
+	pub soundtrack_fade: Duration,
+	pub soundtrack_sync: bool,
+	pub soundtrack_volume: u8,
+	/// Disk space thumbnails may take up, in megabytes, before the least recently shown are dropped.
+	pub thumbnail_cache_size: u32,
+	/// Position of the frames shown as thumbnails of videos.
+	#[serde(with = "seconds")]
+	pub thumbnail_offset: Duration,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			filter_favorites: false,
+			filter_rating: 0,
+			page_by_folder: false,
+			page_size: 10,
+			rules: Kind::ALL
+				.iter()
+				.map(|&kind| Rule::new(kind, None))
+				.collect(),
+			soundtrack_fade: Duration::from_secs(2),
+			soundtrack_sync: false,
+			soundtrack_volume: 50,
+			thumbnail_cache_size: 200,
+			thumbnail_offset: Duration::from_secs(1),
+		}
+	}
+}
+
+impl Settings {
+	/// Makes settings read from a file fit for use, keeping values within the limits the controls
+	/// would.
+	pub fn normalize(&mut self) {
+		for rule in &mut self.rules {
+			rule.apply(RuleChange::Delay(rule.delay));
+			rule.apply(RuleChange::MaxDisplayTime(rule.max_display_time));
+			rule.apply(RuleChange::Offset(rule.offset));
+			rule.apply(RuleChange::Volume(rule.volume));
+		}
+		self.filter_rating = self.filter_rating.min(MAX_RATING);
+		self.page_size = self.page_size.max(1);
+		self.soundtrack_volume = self.soundtrack_volume.min(100);
+	}
+
+	/// The catch-all rule of a kind, without which only files matched by other rules are shown.
+	pub fn kind_rule(&self, kind: Kind) -> Option<&Rule> {
+		self
+			.rules
+			.iter()
+			.find(|r| r.kind == kind && r.matcher.is_none())
+	}
+
+	pub fn kind_rule_index(&self, kind: Kind) -> Option<usize> {
+		self
+			.rules
+			.iter()
+			.position(|r| r.kind == kind && r.matcher.is_none())
+	}
+
+	/// The most specific rule applying to a file, if it is of a displayable kind.
+	pub fn rule(&self, mime: &str, name: &str) -> Option<&Rule> {
+		let kind = Kind::from_mime(mime)?;
+		self
+			.rules
+			.iter()
+			.filter(|r| r.kind == kind)
+			.filter(|r| r.matcher.as_ref().is_none_or(|m| m.matches(mime, name)))
+			.max_by_key(|r| r.matcher.as_ref().map_or(0, |m| m.specificity()))
+	}
+
+	/// Whether a file is played at all given its rule and the favorite and rating filters.
+	pub fn passes(&self, mime: &str, name: &str, mark: Mark) -> bool {
+		self.rule(mime, name).is_some_and(|rule| rule.enabled)
+			&& (!self.filter_favorites || mark.favorite)
+			&& mark.rating >= self.filter_rating
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_matchers() {
+		assert_eq!(
+			Matcher::parse(" .GIF "),
+			Some(Matcher::Extension(String::from("gif")))
+		);
+		assert_eq!(
+			Matcher::parse("webp"),
+			Some(Matcher::Extension(String::from("webp")))
+		);
+		assert_eq!(
+			Matcher::parse("Image/GIF"),
+			Some(Matcher::Mime(String::from("image/gif")))
+		);
+		assert_eq!(Matcher::parse("."), None);
+		assert_eq!(Matcher::parse(""), None);
+	}
+
+	#[test]
+	fn most_specific_rule_wins() {
+		let mut settings = Settings::default();
+		settings.rules.push(Rule::new(
+			Kind::Image,
+			Some(Matcher::Extension(String::from("gif"))),
+		));
+		settings.rules.push(Rule::new(
+			Kind::Image,
+			Some(Matcher::Mime(String::from("image/gif"))),
+		));
+		let matcher = |mime, name| settings.rule(mime, name).and_then(|r| r.matcher.clone());
+		assert_eq!(matcher("image/png", "a.png"), None);
+		assert_eq!(
+			matcher("image/gif", "a.gif"),
+			Some(Matcher::Mime(String::from("image/gif")))
+		);
+		assert_eq!(
+			matcher("image/x-gif", "a.GIF"),
+			Some(Matcher::Extension(String::from("gif")))
+		);
+		assert!(settings.rule("text/plain", "a.txt").is_none());
+	}
+
+	#[test]
+	fn filters_by_rule_and_mark() {
+		let mut settings = Settings::default();
+		let rated = Mark {
+			favorite: false,
+			rating: 3,
+		};
+		assert!(settings.passes("video/mp4", "a.mp4", Mark::default()));
+		settings.filter_rating = 3;
+		assert!(!settings.passes("video/mp4", "a.mp4", Mark::default()));
+		assert!(settings.passes("video/mp4", "a.mp4", rated));
+		settings.filter_favorites = true;
+		assert!(!settings.passes("video/mp4", "a.mp4", rated));
+		let index = settings.kind_rule_index(Kind::Video).unwrap();
+		settings.rules[index].apply(RuleChange::ToggleEnabled);
+		let favorite = Mark {
+			favorite: true,
+			rating: 5,
+		};
+		assert!(!settings.passes("video/mp4", "a.mp4", favorite));
+		assert!(!settings.passes("text/plain", "a.txt", favorite));
+	}
+
+	#[test]
+	fn clamps_rule_changes() {
+		let mut rule = Rule::new(Kind::Video, None);
+		rule.apply(RuleChange::Volume(150));
+		assert_eq!(rule.volume, 100);
+		rule.apply(RuleChange::Delay(Duration::from_secs(u64::MAX)));
+		assert_eq!(rule.delay, MAX_DELAY_SECONDS);
+		rule.apply(RuleChange::ToggleLoop);
+		assert!(rule.looping);
+	}
+
+	#[test]
+	fn offsets_leave_room_for_max_display_time() {
+		let max = Duration::from_secs(10);
+		assert_eq!(Offset::Start.position(60.0, max, 0.5), 0.0);
+		assert_eq!(
+			Offset::Fixed(Duration::from_secs(5)).position(60.0, max, 0.5),
+			5.0
+		);
+		assert_eq!(
+			Offset::Fixed(Duration::from_secs(55)).position(60.0, max, 0.5),
+			50.0
+		);
+		assert_eq!(Offset::Random.position(60.0, max, 0.5), 25.0);
+		assert_eq!(Offset::Random.position(5.0, max, 1.0), 0.0);
+		assert_eq!(Offset::Random.position(f64::INFINITY, max, 1.0), 0.0);
+	}
+
+	#[test]
+	fn settings_round_trip_through_json() {
+		let mut settings = Settings {
+			soundtrack_fade: Duration::from_millis(1500),
+			..Settings::default()
+		};
+		let mut rule = Rule::new(Kind::Image, Some(Matcher::Extension(String::from("gif"))));
+		rule.apply(RuleChange::MaxDisplayTime(Some(Duration::from_millis(
+			2500,
+		))));
+		rule.apply(RuleChange::Offset(Offset::Fixed(Duration::from_secs(3))));
+		settings.rules.push(rule);
+		let json = serde_json::to_string(&settings).unwrap();
+		assert!(json.contains("\"soundtrack_fade\":1.5"));
+		assert!(json.contains("{\"Fixed\":3.0}"));
+		assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+	}
+
+	#[test]
+	fn missing_settings_fall_back_to_defaults() {
+		let settings: Settings = serde_json::from_str(r#"{"filter_rating":2}"#).unwrap();
+		assert_eq!(settings.filter_rating, 2);
+		assert_eq!(settings.rules, Settings::default().rules);
+	}
+}
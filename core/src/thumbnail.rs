@@ -0,0 +1,346 @@
+/// Longest edge of thumbnails, in pixels.
+pub const MAX_EDGE: u32 = 256;
+
+/// Size fitting within a square of the given edge while keeping the aspect ratio. Small pictures
+/// are left as they are rather than blown up.
+pub fn fit(width: u32, height: u32, max: u32) -> (u32, u32) {
+	if width <= max && height <= max {
+		return (width.max(1), height.max(1));
+	}
+	let scale = max as f64 / width.max(height) as f64;
+	let scaled = |side: u32| ((side as f64 * scale).round() as u32).max(1);
+	(scaled(width), scaled(height))
+}
+
+/// Picture embedded in the tags of an audio file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoverArt<'a> {
+	pub mime: String,
+	pub data: &'a [u8],
+}
+
+/// ID3v2 and FLAC picture type of front covers, which are preferred over other pictures.
+const FRONT_COVER: u8 = 3;
+
+fn u32_be(bytes: &[u8], at: usize) -> Option<u32> {
+	let b = bytes.get(at..at.checked_add(4)?)?;
+	Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// Reads a 28 bit ID3v2 integer, which keeps the high bit of every byte clear.
+fn syncsafe(bytes: &[u8], at: usize) -> Option<usize> {
+	let b = bytes.get(at..at.checked_add(4)?)?;
+	Some(b.iter().fold(0, |n, &b| (n << 7) | (b & 0x7f) as usize))
+}
+
+/// Position past a string terminated according to an ID3v2 text encoding.
+fn skip_terminated(bytes: &[u8], at: usize, encoding: u8) -> Option<usize> {
+	let rest = bytes.get(at..)?;
+	match encoding {
+		// UTF-16 strings end with two zero bytes on a character boundary.
+		1 | 2 => rest
+			.chunks(2)
+			.position(|c| c == [0, 0])
+			.map(|i| at + i * 2 + 2),
+		_ => rest.iter().position(|&b| b == 0).map(|i| at + i + 1),
+	}
+}
+
+/// Picture type and cover art of an `APIC` frame, or of a `PIC` frame in ID3v2.2 tags.
+fn id3_picture(frame: &[u8], version: u8) -> Option<(u8, CoverArt<'_>)> {
+	let encoding = *frame.first()?;
+	let (mime, at) = if version == 2 {
+		let format = std::str::from_utf8(frame.get(1..4)?).ok()?;
+		(format!("image/{}", format.to_ascii_lowercase()), 4)
+	} else {
+		let end = skip_terminated(frame, 1, 0)?;
+		(
+			String::from_utf8_lossy(&frame[1..end - 1]).into_owned(),
+			end,
+		)
+	};
+	let mime = match mime.as_str() {
+		"image/jpg" => String::from("image/jpeg"),
+		// Some taggers leave the MIME type out.
+		"" | "image/" => String::from("image/jpeg"),
+		_ => mime,
+	};
+	let picture_type = *frame.get(at)?;
+	let data = frame.get(skip_terminated(frame, at + 1, encoding)?..)?;
+	Some((picture_type, CoverArt { mime, data }))
+}
+
+fn id3_cover_art(bytes: &[u8]) -> Option<CoverArt<'_>> {
+	if bytes.get(..3)? != b"ID3" {
+		return None;
+	}
+	let version = *bytes.get(3)?;
+	let flags = *bytes.get(5)?;
+	let end = (10 + syncsafe(bytes, 6)?).min(bytes.len());
+	let mut at: usize = 10;
+	if flags & 0x40 != 0 {
+		// Sizes read from the file may overflow 32 bit positions.
+		at = at.checked_add(match version {
+			3 => (u32_be(bytes, at)? as usize).checked_add(4)?,
+			_ => syncsafe(bytes, at)?,
+		})?;
+	}
+	let (id_len, header_len) = if version == 2 { (3, 6) } else { (4, 10) };
+	let mut best: Option<(u8, CoverArt)> = None;
+	while let Some(body) = at.checked_add(header_len).filter(|&body| body <= end) {
+		let id = bytes.get(at..at + id_len)?;
+		if id[0] == 0 {
+			// Padding.
+			break;
+		}
+		let size = match version {
+			2 => bytes[at + 3..at + 6]
+				.iter()
+				.fold(0, |n, &b| (n << 8) | b as usize),
+			3 => u32_be(bytes, at + 4)? as usize,
+			_ => syncsafe(bytes, at + 4)?,
+		};
+		let frame_end = body.checked_add(size)?;
+		let frame = bytes.get(body..frame_end.min(end))?;
+		if id == b"APIC" || id == b"PIC" {
+			if let Some(picture) = id3_picture(frame, version) {
+				if picture.0 == FRONT_COVER {
+					return Some(picture.1);
+				}
+				best.get_or_insert(picture);
+			}
+		}
+		at = frame_end;
+	}
+	best.map(|(_, art)| art)
+}
+
+fn flac_cover_art(bytes: &[u8]) -> Option<CoverArt<'_>> {
+	if bytes.get(..4)? != b"fLaC" {
+		return None;
+	}
+	let mut at = 4;
+	let mut best: Option<(u8, CoverArt)> = None;
+	loop {
+		let header = *bytes.get(at)?;
+		let length = u32_be(bytes, at)? as usize & 0x00ff_ffff;
+		let block_end = at.checked_add(4 + length)?;
+		let block = bytes.get(at + 4..block_end)?;
+		// PICTURE block, whose lengths may overflow 32 bit positions.
+		if header & 0x7f == 6 {
+			let picture_type = u32_be(block, 0)?;
+			let mime_end = 8usize.checked_add(u32_be(block, 4)? as usize)?;
+			let mime = String::from_utf8_lossy(block.get(8..mime_end)?).into_owned();
+			let description_length = u32_be(block, mime_end)? as usize;
+			// Width, height, color depth and number of colors follow the description.
+			let data_at = (mime_end + 4)
+				.checked_add(description_length)?
+				.checked_add(16)?;
+			let data_length = u32_be(block, data_at)? as usize;
+			let data = block.get(data_at + 4..(data_at + 4).checked_add(data_length)?)?;
+			let art = CoverArt { mime, data };
+			if picture_type == FRONT_COVER as u32 {
+				return Some(art);
+			}
+			best.get_or_insert((picture_type as u8, art));
+		}
+		// Last block.
+		if header & 0x80 != 0 {
+			break;
+		}
+		at = block_end;
+	}
+	best.map(|(_, art)| art)
+}
+
+/// Cover art of an audio file, read from its ID3v2 tag or FLAC metadata. Both come first in a file,
+/// so the start of it is enough.
+pub fn cover_art(bytes: &[u8]) -> Option<CoverArt<'_>> {
+	id3_cover_art(bytes).or_else(|| flac_cover_art(bytes))
+}
+
+/// Cached thumbnail, as far as eviction is concerned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+	pub key: String,
+	pub bytes: u64,
+	/// Timestamp of the last time the thumbnail was shown.
+	pub used: f64,
+}
+
+/// Keys of the thumbnails to drop for the cache to fit its budget, least recently used first.
+pub fn evict(mut entries: Vec<Entry>, budget: u64) -> Vec<String> {
+	let mut total: u64 = entries.iter().map(|e| e.bytes).sum();
+	entries.sort_by(|a, b| a.used.total_cmp(&b.used));
+	entries
+		.into_iter()
+		.take_while(|entry| {
+			let over = total > budget;
+			total -= entry.bytes;
+			over
+		})
+		.map(|entry| entry.key)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn syncsafe_bytes(n: usize) -> [u8; 4] {
+		[
+			(n >> 21) as u8 & 0x7f,
+			(n >> 14) as u8 & 0x7f,
+			(n >> 7) as u8 & 0x7f,
+			n as u8 & 0x7f,
+		]
+	}
+
+	fn id3(version: u8, frames: &[(&[u8], Vec<u8>)]) -> Vec<u8> {
+		let mut body = Vec::new();
+		for (id, frame) in frames {
+			body.extend_from_slice(id);
+			match version {
+				2 => body.extend_from_slice(&(frame.len() as u32).to_be_bytes()[1..]),
+				3 => body.extend_from_slice(&(frame.len() as u32).to_be_bytes()),
+				_ => body.extend_from_slice(&syncsafe_bytes(frame.len())),
+			}
+			if version > 2 {
+				body.extend_from_slice(&[0, 0]);
+			}
+			body.extend_from_slice(frame);
+		}
+		// Padding.
+		body.extend_from_slice(&[0; 16]);
+		let mut tag = vec![b'I', b'D', b'3', version, 0, 0];
+		tag.extend_from_slice(&syncsafe_bytes(body.len()));
+		tag.extend(body);
+		tag.extend_from_slice(b"audio frames");
+		tag
+	}
+
+	fn apic(mime: &str, picture_type: u8, description: &[u8], data: &[u8]) -> Vec<u8> {
+		let mut frame = vec![0];
+		frame.extend_from_slice(mime.as_bytes());
+		frame.push(0);
+		frame.push(picture_type);
+		frame.extend_from_slice(description);
+		frame.extend_from_slice(data);
+		frame
+	}
+
+	#[test]
+	fn fits_thumbnails() {
+		assert_eq!(fit(1024, 768, 256), (256, 192));
+		assert_eq!(fit(768, 1024, 256), (192, 256));
+		assert_eq!(fit(100, 50, 256), (100, 50));
+		assert_eq!(fit(10_000, 10, 256), (256, 1));
+		assert_eq!(fit(0, 0, 256), (1, 1));
+	}
+
+	#[test]
+	fn reads_id3_front_covers() {
+		let tag = id3(
+			3,
+			&[
+				(b"TIT2", b"\0Song".to_vec()),
+				(b"APIC", apic("image/png", 0, b"back\0", b"other")),
+				(b"APIC", apic("image/jpeg", 3, b"\0", b"front")),
+			],
+		);
+		assert_eq!(
+			cover_art(&tag),
+			Some(CoverArt {
+				mime: String::from("image/jpeg"),
+				data: b"front",
+			})
+		);
+	}
+
+	#[test]
+	fn reads_other_id3_versions() {
+		let mut frame = vec![1];
+		frame.extend_from_slice(b"image/png\0");
+		frame.push(0);
+		// UTF-16 description with its two byte terminator.
+		frame.extend_from_slice(&[0xff, 0xfe, b'a', 0, 0, 0]);
+		frame.extend_from_slice(b"png");
+		let tag = id3(4, &[(b"APIC", frame)]);
+		assert_eq!(cover_art(&tag).map(|art| art.data), Some(&b"png"[..]));
+		let tag = id3(2, &[(b"PIC", b"\0JPG\x03\0jpg".to_vec())]);
+		assert_eq!(
+			cover_art(&tag),
+			Some(CoverArt {
+				mime: String::from("image/jpeg"),
+				data: b"jpg",
+			})
+		);
+		assert_eq!(cover_art(&id3(3, &[(b"TIT2", b"\0Song".to_vec())])), None);
+	}
+
+	#[test]
+	fn stops_at_huge_id3_frames() {
+		let mut tag = id3(
+			3,
+			&[
+				(b"TIT2", b"\0Song".to_vec()),
+				(b"APIC", apic("image/jpeg", 3, b"\0", b"front")),
+			],
+		);
+		// Frames claiming to run past the tag leave nothing after them to read.
+		tag[14..18].copy_from_slice(&u32::MAX.to_be_bytes());
+		assert_eq!(cover_art(&tag), None);
+	}
+
+	#[test]
+	fn reads_flac_pictures() {
+		let mut picture = Vec::new();
+		picture.extend_from_slice(&3u32.to_be_bytes());
+		picture.extend_from_slice(&9u32.to_be_bytes());
+		picture.extend_from_slice(b"image/png");
+		picture.extend_from_slice(&0u32.to_be_bytes());
+		picture.extend_from_slice(&[0; 16]);
+		picture.extend_from_slice(&4u32.to_be_bytes());
+		picture.extend_from_slice(b"flac");
+		let mut bytes = b"fLaC".to_vec();
+		// STREAMINFO, then the PICTURE as last block.
+		bytes.extend_from_slice(&[0, 0, 0, 34]);
+		bytes.extend_from_slice(&[0; 34]);
+		bytes.push(0x80 | 6);
+		bytes.extend_from_slice(&(picture.len() as u32).to_be_bytes()[1..]);
+		bytes.extend(&picture);
+		assert_eq!(
+			cover_art(&bytes),
+			Some(CoverArt {
+				mime: String::from("image/png"),
+				data: b"flac",
+			})
+		);
+		// Lengths running past the block are not followed.
+		let overflowing = bytes.len() - picture.len() + 4;
+		bytes[overflowing..overflowing + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+		assert_eq!(cover_art(&bytes), None);
+		assert_eq!(cover_art(b"RIFF...."), None);
+	}
+
+	#[test]
+	fn evicts_least_recently_used() {
+		let entry = |key: &str, bytes, used| Entry {
+			key: String::from(key),
+			bytes,
+			used,
+		};
+		let entries = vec![
+			entry("recent", 40, 3.0),
+			entry("oldest", 30, 1.0),
+			entry("old", 50, 2.0),
+		];
+		assert_eq!(evict(entries.clone(), 120), Vec::<String>::new());
+		assert_eq!(evict(entries.clone(), 100), vec![String::from("oldest")]);
+		assert_eq!(
+			evict(entries.clone(), 60),
+			vec![String::from("oldest"), String::from("old")]
+		);
+		assert_eq!(evict(entries, 0).len(), 3);
+	}
+}
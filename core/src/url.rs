@@ -0,0 +1,52 @@
+/// Percent-encodes everything but unreserved characters and `/`, so paths stay readable in URLs.
+pub fn encode(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for b in s.bytes() {
+		match b {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+				out.push(b as char)
+			}
+			_ => out.push_str(&format!("%{:02X}", b)),
+		}
+	}
+	out
+}
+
+/// Decodes percent-encoded characters, leaving malformed sequences as they are.
+pub fn decode(s: &str) -> String {
+	let bytes = s.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		if bytes[i] == b'%' && i + 2 < bytes.len() {
+			let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+			if let Some(b) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+				out.push(b);
+				i += 3;
+				continue;
+			}
+		}
+		out.push(bytes[i]);
+		i += 1;
+	}
+	String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn encodes_paths() {
+		assert_eq!(encode("Trip/day 1/a&b.jpg"), "Trip/day%201/a%26b.jpg");
+		assert_eq!(encode("été"), "%C3%A9t%C3%A9");
+	}
+
+	#[test]
+	fn decodes_paths() {
+		assert_eq!(decode("Trip/day%201/a%26b.jpg"), "Trip/day 1/a&b.jpg");
+		assert_eq!(decode("%C3%A9t%C3%A9"), "été");
+		assert_eq!(decode("100%"), "100%");
+		assert_eq!(decode("%zz"), "%zz");
+	}
+}
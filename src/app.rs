@@ -1,49 +1,27 @@
-use std::time::Duration;
-
+use log::warn;
 use yew::{html, Component, ComponentLink, Html, ShouldRender};
 use yew_router::{route::Route, router::Router, switch::Permissive};
 
-use crate::routes::{home::Home, AppRoute};
-
-pub const MAX_DELAY_SECONDS: Duration = Duration::from_millis(u32::MAX as u64);
+use crate::{
+	browser,
+	routes::{home::Home, AppRoute},
+};
 
-#[derive(Clone, Copy, Eq, PartialEq)]
-pub struct Settings {
-	pub config_audio_autoplay_delay: Duration,
-	pub config_image_autoplay_delay: Duration,
-	pub config_video_autoplay_delay: Duration,
-	pub toggle_audio: bool,
-	pub toggle_audio_autoplay: bool,
-	pub toggle_image: bool,
-	pub toggle_image_autoplay: bool,
-	pub toggle_video: bool,
-	pub toggle_video_autoplay: bool,
+pub struct App {
+	/// Query string the app was opened with, holding shared settings.
+	query: String,
 }
 
-impl Settings {
-	pub fn new() -> Self {
-		Self {
-			config_audio_autoplay_delay: Duration::ZERO,
-			config_image_autoplay_delay: Duration::from_secs(2),
-			config_video_autoplay_delay: Duration::ZERO,
-			toggle_audio: true,
-			toggle_audio_autoplay: true,
-			toggle_image: true,
-			toggle_image_autoplay: true,
-			toggle_video: true,
-			toggle_video_autoplay: true,
-		}
-	}
-}
-
-pub struct App;
-
 impl Component for App {
 	type Message = ();
 	type Properties = ();
 
 	fn create(_: Self::Properties, _: ComponentLink<Self>) -> Self {
-		Self {}
+		let query = browser::take_query().unwrap_or_else(|err| {
+			warn!("Failed to read the query string: {}", err);
+			String::new()
+		});
+		Self { query }
 	}
 
 	fn change(&mut self, _: Self::Properties) -> ShouldRender {
@@ -54,15 +32,19 @@ impl Component for App {
 		false
 	}
 
+	// Properties set through `html!` expand to statements which this lint takes for no-ops.
+	#[allow(clippy::unnecessary_operation)]
 	fn view(&self) -> Html {
+		let query = self.query.clone();
 		html! {
 			<>
 				<Router<AppRoute, ()>
-					render = Router::render(|switch: AppRoute| {
+					render = Router::render(move |switch: AppRoute| {
 						match switch {
-							AppRoute::Home => html!{ <Home/> },
 							AppRoute::PageNotFound(Permissive(None)) => html!{"Page not found!"},
 							AppRoute::PageNotFound(Permissive(Some(missed_route))) => html!{format!("Page '{}' not found!", missed_route)},
+							// Every other route is a view of the same library, so it stays loaded across them.
+							route => html!{ <Home query=query.clone() route=route /> },
 						}
 					} )
 					redirect = Router::redirect(|route: Route<()>| {
@@ -0,0 +1,104 @@
+use gloo_timers::callback::Timeout;
+use js_sys::{Array, JsString, Uint8Array};
+use log::warn;
+use sha2::{Digest, Sha256};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::web_sys::{window, Blob, BlobPropertyBag, File, HtmlAnchorElement, Url};
+
+use crate::error::Error;
+
+/// Milliseconds download URLs are kept for, as some browsers only start reading them later on.
+const DOWNLOAD_URL_LIFETIME: u32 = 1000;
+/// Bytes of a file read at once while hashing it.
+const HASH_CHUNK_BYTES: f64 = 4.0 * 1024.0 * 1024.0;
+
+/// Absolute URL of the app, which links to it start from.
+pub fn base_url() -> Result<String, Error> {
+	window()
+		.and_then(|w| w.document())
+		.ok_or_else(|| JsValue::from_str("No document available"))?
+		.base_uri()?
+		.ok_or_else(|| Error::from(JsValue::from_str("No base URL available")))
+}
+
+/// Copies text to the clipboard.
+pub async fn copy_text(text: &str) -> Result<(), Error> {
+	let clipboard = window()
+		.ok_or_else(|| JsValue::from_str("No window available"))?
+		.navigator()
+		.clipboard();
+	JsFuture::from(clipboard.write_text(text)).await?;
+	Ok(())
+}
+
+/// Offers the given contents to the user as a file download.
+pub fn download(name: &str, mime: &str, contents: &str) -> Result<(), Error> {
+	let document = window()
+		.and_then(|w| w.document())
+		.ok_or_else(|| JsValue::from_str("No document available"))?;
+	let options = BlobPropertyBag::new();
+	options.set_type(mime);
+	let parts = Array::of1(&JsValue::from_str(contents));
+	let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+	let url = Url::create_object_url_with_blob(&blob)?;
+	let anchor: HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+	anchor.set_href(&url);
+	anchor.set_download(name);
+	anchor.click();
+	Timeout::new(DOWNLOAD_URL_LIFETIME, move || {
+		if let Err(err) = Url::revoke_object_url(&url) {
+			warn!("Failed to revoke download URL: {:?}", err);
+		}
+	})
+	.forget();
+	Ok(())
+}
+
+/// Reads up to the given number of bytes from the start of a file.
+pub async fn read_head(file: &File, length: usize) -> Result<Vec<u8>, Error> {
+	let head = file.slice_with_f64_and_f64(0.0, length as f64)?;
+	let buffer = JsFuture::from(head.array_buffer()).await?;
+	Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// Reads the whole contents of a file.
+pub async fn read_bytes(file: &File) -> Result<Vec<u8>, Error> {
+	let buffer = JsFuture::from(file.array_buffer()).await?;
+	Ok(Uint8Array::new(&buffer).to_vec())
+}
+
+/// SHA-256 hash of the contents of a file, read a chunk at a time.
+pub async fn content_hash(file: &File) -> Result<[u8; 32], Error> {
+	let size = file.size();
+	let mut hasher = Sha256::new();
+	let mut start = 0.0;
+	while start < size {
+		let end = (start + HASH_CHUNK_BYTES).min(size);
+		let chunk = file.slice_with_f64_and_f64(start, end)?;
+		hasher.update(Uint8Array::new(&JsFuture::from(chunk.array_buffer()).await?).to_vec());
+		start = end;
+	}
+	Ok(hasher.finalize().into())
+}
+
+/// Reads the whole contents of a file as text.
+pub async fn read_text(file: &File) -> Result<String, Error> {
+	let text = JsFuture::from(file.text()).await?;
+	Ok(text.unchecked_into::<JsString>().into())
+}
+
+/// Removes the query string from the address bar, so that routes match, and returns it without
+/// the leading `?`.
+pub fn take_query() -> Result<String, Error> {
+	let window = window().ok_or_else(|| JsValue::from_str("No window available"))?;
+	let location = window.location();
+	let query = location.search()?;
+	if !query.is_empty() {
+		let path = format!("{}{}", location.pathname()?, location.hash()?);
+		window
+			.history()?
+			.replace_state_with_url(&JsValue::NULL, "", Some(&path))?;
+	}
+	Ok(String::from(query.trim_start_matches('?')))
+}
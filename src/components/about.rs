@@ -0,0 +1,81 @@
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_octicons::{Icon, IconKind};
+
+/// Keys understood while viewing, along with what they do.
+const SHORTCUTS: [(&str, &str); 17] = [
+	("← →", "Previous and next item"),
+	("Home End", "First and last item"),
+	("PgUp PgDn", "Previous and next page or folder"),
+	("Space", "Hold or resume the slideshow"),
+	("Escape", "Close panels, then quit"),
+	("A", "Add to the active playlist"),
+	("C", "Culling"),
+	("D", "Find duplicates"),
+	("K X", "Keep or reject while culling"),
+	("N", "Rename files"),
+	("R", "Review rejects"),
+	("F", "Toggle favorite"),
+	("0 - 5", "Rate"),
+	("G", "Browse folders"),
+	("P", "Playlists"),
+	("S", "Filmstrip"),
+	("T", "Tags"),
+];
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	pub onclose: Callback<()>,
+}
+
+pub struct About {
+	class: String,
+	on_close: Callback<()>,
+}
+
+impl Component for About {
+	type Message = ();
+	type Properties = Props;
+
+	fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+		Self {
+			class: props.class,
+			on_close: props.onclose,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.class = props.class;
+		self.on_close = props.onclose;
+		true
+	}
+
+	fn update(&mut self, _: Self::Message) -> ShouldRender {
+		false
+	}
+
+	fn view(&self) -> Html {
+		html! {
+			<div class=format!("bg-gray-700 text-white flex flex-col place-content-center place-items-center text-3xl lg:text-xl select-none {}", &self.class)>
+				<div class="absolute top-0 right-0 m-4 cursor-pointer" onclick=self.on_close.reform(|_| ())>
+					{ Icon::new_sized(IconKind::X, 64) }
+				</div>
+				<h1 class="text-9xl m-2">{ "OmnivYou" }</h1>
+				<p class="m-2">{ "Media viewer for video, audio, and images alike, running entirely in your browser." }</p>
+				<table class="m-4">
+					{for SHORTCUTS.iter().map(|(keys, action)| html! {
+						<tr>
+							<td class="pr-8 text-right font-mono">{ keys }</td>
+							<td>{ action }</td>
+						</tr>
+					})}
+				</table>
+				<a class="flex place-items-center m-2 hover:underline" href="https://github.com/nicholaschiasson/omnivyou" target="_blank" rel="noopener">
+					<span class="mr-2">{ Icon::new_sized(IconKind::MarkGithub, 32) }</span>
+					{ "Source code" }
+				</a>
+			</div>
+		}
+	}
+}
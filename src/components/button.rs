@@ -0,0 +1,23 @@
+use yew::{html, Callback, Html};
+use yew_octicons::{Icon, IconKind};
+
+/// Button which is disabled without a callback.
+pub fn action_button(icon: IconKind, label: &str, onclick: Option<Callback<()>>) -> Html {
+	let class = "flex place-items-center px-4 py-2 mr-4 mb-2 rounded-full border-2 transition";
+	let contents = html! {
+		<>
+			{ Icon::new_sized(icon, 32) }
+			<span class="ml-2">{ label }</span>
+		</>
+	};
+	match onclick {
+		Some(onclick) => html! {
+			<div class=format!("{} cursor-pointer hover:bg-white hover:text-black", class) onclick=onclick.reform(|_| ())>
+				{ contents }
+			</div>
+		},
+		None => html! {
+			<div class=format!("{} opacity-50 cursor-not-allowed", class)>{ contents }</div>
+		},
+	}
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashSet, time::Duration};
+
+use omnivyou_core::{cull, library::folder_of};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_octicons::{Icon, IconKind};
+
+use crate::{
+	components::{
+		button::action_button,
+		thumbnail::{kind_icon, Thumbnail},
+	},
+	finder::Group,
+	library::Item,
+};
+
+pub enum Msg {
+	/// Asks to confirm deleting the copies of a group, by its position.
+	Confirm(Option<usize>),
+	Delete,
+	Hide(usize),
+	/// Keeps the item at a path rather than the others of its group.
+	Keep(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Whether files are being moved, renamed or deleted.
+	pub busy: bool,
+	/// Top folders of the copies, along with whether changes to them were allowed.
+	pub folders: Vec<(String, bool)>,
+	/// Groups of copies found by the last search, if it finished.
+	pub groups: Option<Vec<Group>>,
+	pub oncancel: Callback<()>,
+	pub onclose: Callback<()>,
+	pub ondelete: Callback<Vec<String>>,
+	pub ongrant: Callback<String>,
+	pub onhide: Callback<Vec<String>>,
+	pub onsearch: Callback<()>,
+	/// Number of files compared so far and in all, while searching.
+	pub progress: Option<(usize, usize)>,
+	/// Position of the frames shown for videos.
+	pub thumbnail_offset: Duration,
+}
+
+fn format_size(bytes: f64) -> String {
+	if bytes >= 1024.0 * 1024.0 {
+		format!("{:.1} MB", bytes / (1024.0 * 1024.0))
+	} else {
+		format!("{:.0} KB", (bytes / 1024.0).ceil())
+	}
+}
+
+/// Shows groups of copies side by side, to keep one of each and hide or delete the others.
+pub struct Duplicates {
+	/// Position of the group whose copies are about to be deleted.
+	confirm: Option<usize>,
+	/// Paths of the items picked to be kept, the first of a group being kept otherwise.
+	kept: HashSet<String>,
+	link: ComponentLink<Self>,
+	props: Props,
+}
+
+impl Duplicates {
+	fn groups(&self) -> &[Group] {
+		self.props.groups.as_deref().unwrap_or_default()
+	}
+
+	/// Paths of the items of a group other than the one kept.
+	fn others(&self, group: &Group) -> Vec<String> {
+		let kept = self.kept_path(group);
+		group
+			.items
+			.iter()
+			.filter(|item| item.path != kept)
+			.map(|item| item.path.clone())
+			.collect()
+	}
+
+	fn kept_path<'a>(&self, group: &'a Group) -> &'a str {
+		group
+			.items
+			.iter()
+			.find(|item| self.kept.contains(&item.path))
+			.or_else(|| group.items.first())
+			.map_or("", |item| item.path.as_str())
+	}
+
+	/// Whether changes to the folders of every file were allowed.
+	fn allowed(&self, paths: &[String]) -> bool {
+		paths.iter().all(|path| match cull::split_top(path) {
+			Some((top, _)) => self.props.folders.iter().any(|(f, a)| f == top && *a),
+			// Files added on their own cannot be changed.
+			None => false,
+		})
+	}
+
+	fn view_progress(&self) -> Html {
+		match self.props.progress {
+			Some((done, total)) => html! {
+				<div class="flex flex-wrap place-items-center px-4">
+					<span class="mr-4 mb-2">{ format!("Comparing files: {} of {}", done, total) }</span>
+					{ action_button(IconKind::X, "Cancel", Some(self.props.oncancel.clone())) }
+				</div>
+			},
+			None if self.groups().is_empty() && self.props.groups.is_some() => html! {
+				<p class="px-4 text-white text-opacity-50">{ "No copies were found." }</p>
+			},
+			None => html!(),
+		}
+	}
+
+	#[allow(clippy::unnecessary_operation)]
+	fn view_item(&self, item: &Item, kept: bool) -> Html {
+		let path = item.path.clone();
+		html! {
+			<div class=format!("flex flex-col place-items-center flex-shrink-0 w-40 lg:w-32 p-2 mr-2 rounded-lg border-2 cursor-pointer transition {}", if kept { "border-green-400" } else { "border-transparent opacity-50" })
+				title=item.path.clone()
+				onclick=self.link.callback(move |_| Msg::Keep(path.clone()))>
+				<div class="h-32 lg:h-24 w-full flex place-content-center place-items-center">
+					<Thumbnail
+						fallback=html! { Icon::new_sized(kind_icon(&item.media_type()), 64) }
+						item=item.clone()
+						offset=self.props.thumbnail_offset
+					/>
+				</div>
+				<span class="w-full truncate text-center">{ item.file.name() }</span>
+				<span class="w-full truncate text-center text-white text-opacity-50">{ folder_of(&item.path) }</span>
+				<span class="text-white text-opacity-50">{ format_size(item.file.size()) }</span>
+			</div>
+		}
+	}
+
+	fn view_group(&self, i: usize, group: &Group) -> Html {
+		let kept = self.kept_path(group);
+		let others = self.others(group);
+		let enabled = !self.props.busy;
+		let actions = if self.confirm == Some(i) {
+			html! {
+				<>
+					<span class="mr-4 mb-2">{ format!("Delete {} files? This cannot be undone.", others.len()) }</span>
+					{ action_button(IconKind::Check, "Confirm", enabled.then(|| self.link.callback(|_| Msg::Delete))) }
+					{ action_button(IconKind::X, "Cancel", Some(self.link.callback(|_| Msg::Confirm(None)))) }
+				</>
+			}
+		} else {
+			let allowed = enabled && self.allowed(&others);
+			html! {
+				<>
+					{ action_button(IconKind::EyeClosed, "Hide others", Some(self.link.callback(move |_| Msg::Hide(i)))) }
+					{ action_button(IconKind::Trash, "Delete others", allowed.then(|| self.link.callback(move |_| Msg::Confirm(Some(i))))) }
+				</>
+			}
+		};
+		html! {
+			<div class="mb-4">
+				<div class="mb-2 text-white text-opacity-70">
+					{ format!("{} · {} files", if group.exact { "Exact copies" } else { "Look alike" }, group.items.len()) }
+				</div>
+				<div class="flex overflow-x-auto mb-2">
+					{for group.items.iter().map(|item| self.view_item(item, item.path == kept))}
+				</div>
+				<div class="flex flex-wrap place-items-center">{ actions }</div>
+			</div>
+		}
+	}
+}
+
+impl Component for Duplicates {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		Self {
+			confirm: None,
+			kept: HashSet::new(),
+			link,
+			props,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		// Deleting is only confirmed for the group it was asked for.
+		if props.groups != self.props.groups {
+			self.confirm = None;
+		}
+		self.props = props;
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Confirm(group) => {
+				self.confirm = group;
+				true
+			}
+			Msg::Delete => {
+				if let Some(group) = self.confirm.take().and_then(|i| self.groups().get(i)) {
+					self.props.ondelete.emit(self.others(group));
+				}
+				true
+			}
+			Msg::Hide(i) => {
+				if let Some(group) = self.groups().get(i) {
+					self.props.onhide.emit(self.others(group));
+				}
+				false
+			}
+			Msg::Keep(path) => {
+				if let Some(group) = self
+					.props
+					.groups
+					.iter()
+					.flatten()
+					.find(|group| group.items.iter().any(|item| item.path == path))
+				{
+					for item in &group.items {
+						self.kept.remove(&item.path);
+					}
+				}
+				self.kept.insert(path);
+				self.confirm = None;
+				true
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		let groups = self.groups();
+		let copies: usize = groups.iter().map(|group| group.items.len() - 1).sum();
+		html! {
+			<div class=format!("bg-gray-800 text-white flex flex-col text-3xl lg:text-xl select-none {}", &self.props.class)>
+				<div class="flex place-items-center p-4">
+					<div class="flex-1">{ format!("Duplicates: {} copies in {} groups", copies, groups.len()) }</div>
+					{if self.props.progress.is_none() {
+						html! {
+							<div class="cursor-pointer ml-4 hover:underline" onclick=self.props.onsearch.reform(|_| ())>{ "Search again" }</div>
+						}
+					} else {
+						html!()
+					}}
+					<div class="cursor-pointer ml-4" onclick=self.props.onclose.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				{ self.view_progress() }
+				<div class="flex-1 overflow-y-auto p-4">
+					{for groups.iter().enumerate().map(|(i, group)| self.view_group(i, group))}
+				</div>
+				{for self.props.folders.iter().filter(|(_, allowed)| !allowed).map(|(folder, _)| {
+					let folder = folder.clone();
+					html! {
+						<div class="flex flex-wrap place-items-center px-4 pt-4 border-t">
+							<span class="mr-4 mb-2">{ format!("Deleting files needs access to '{}'.", folder) }</span>
+							{ action_button(IconKind::Unlock, "Allow changes", Some(self.props.ongrant.reform(move |_| folder.clone()))) }
+						</div>
+					}
+				})}
+			</div>
+		}
+	}
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_octicons::Icon;
+
+use crate::{
+	components::thumbnail::{kind_icon, Thumbnail},
+	library::Item,
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Position of the current item in the queue.
+	pub current: usize,
+	/// Items around the current one, along with their position in the queue.
+	pub items: Vec<(usize, Item)>,
+	pub onjump: Callback<usize>,
+	/// Position of the frames shown for videos.
+	pub thumbnail_offset: Duration,
+}
+
+/// Strip of thumbnails of the items surrounding the current one.
+pub struct Filmstrip {
+	class: String,
+	current: usize,
+	items: Vec<(usize, Item)>,
+	on_jump: Callback<usize>,
+	thumbnail_offset: Duration,
+}
+
+impl Component for Filmstrip {
+	type Message = ();
+	type Properties = Props;
+
+	fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+		Self {
+			class: props.class,
+			current: props.current,
+			items: props.items,
+			on_jump: props.onjump,
+			thumbnail_offset: props.thumbnail_offset,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.class = props.class;
+		self.current = props.current;
+		self.items = props.items;
+		self.on_jump = props.onjump;
+		self.thumbnail_offset = props.thumbnail_offset;
+		true
+	}
+
+	fn update(&mut self, _: Self::Message) -> ShouldRender {
+		false
+	}
+
+	#[allow(clippy::unnecessary_operation)]
+	fn view(&self) -> Html {
+		html! {
+			<div class=format!("flex place-content-center place-items-center select-none {}", &self.class)>
+				{for self.items.iter().map(|(index, item)| {
+					let index = *index;
+					let border = if index == self.current { "border-white" } else { "border-transparent opacity-60 hover:opacity-100" };
+					html! {
+						<div class=format!("h-24 w-24 lg:h-16 lg:w-16 mx-1 p-1 rounded-lg border-2 bg-gray-800 bg-opacity-70 flex place-content-center place-items-center cursor-pointer transition {}", border)
+							title=item.path.clone()
+							onclick=self.on_jump.reform(move |_| index)>
+							<Thumbnail
+								fallback=html! { Icon::new_sized(kind_icon(&item.media_type()), 32) }
+								item=item.clone()
+								offset=self.thumbnail_offset
+							/>
+						</div>
+					}
+				})}
+			</div>
+		}
+	}
+}
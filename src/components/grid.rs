@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use omnivyou_core::library::folder_entries;
+use yew::{
+	html, web_sys::MouseEvent, Callback, Component, ComponentLink, Html, Properties, ShouldRender,
+};
+use yew_octicons::{Icon, IconKind};
+
+use crate::{
+	components::thumbnail::{kind_icon, Thumbnail},
+	library::Item,
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Folder to list, the root folder being the empty string.
+	pub folder: String,
+	/// Every indexed item.
+	pub items: Vec<Item>,
+	pub onclose: Callback<()>,
+	pub onfolder: Callback<String>,
+	pub onopen: Callback<String>,
+	/// Position of the frames shown for videos.
+	pub thumbnail_offset: Duration,
+}
+
+/// Browses the library folder by folder.
+pub struct Grid {
+	class: String,
+	folder: String,
+	items: Vec<Item>,
+	on_close: Callback<()>,
+	on_folder: Callback<String>,
+	on_open: Callback<String>,
+	thumbnail_offset: Duration,
+}
+
+impl Grid {
+	fn view_breadcrumbs(&self) -> Html {
+		let mut crumbs = vec![(String::new(), String::from("All"))];
+		if !self.folder.is_empty() {
+			let mut path = String::new();
+			for segment in self.folder.split('/') {
+				if !path.is_empty() {
+					path.push('/');
+				}
+				path.push_str(segment);
+				crumbs.push((path.clone(), String::from(segment)));
+			}
+		}
+		html! {
+			<div class="flex-1 flex flex-wrap place-items-center">
+				{for crumbs.into_iter().map(|(path, name)| {
+					let current = path == self.folder;
+					html! {
+						<>
+							<span class="mx-2 text-opacity-50 text-white">{ "/" }</span>
+							<span
+								class=if current { "" } else { "cursor-pointer hover:underline" }
+								onclick=self.on_folder.reform(move |_| path.clone())
+							>
+								{ name }
+							</span>
+						</>
+					}
+				})}
+			</div>
+		}
+	}
+
+	fn view_tile(&self, icon: Html, name: &str, onclick: Callback<MouseEvent>) -> Html {
+		html! {
+			<div class="flex flex-col place-items-center p-4 rounded-lg bg-white bg-opacity-0 hover:bg-opacity-20 transition cursor-pointer" title=name.to_string() onclick=onclick>
+				<div class="h-32 lg:h-24 flex place-items-center">{ icon }</div>
+				<span class="w-full truncate text-center">{ name }</span>
+			</div>
+		}
+	}
+}
+
+impl Component for Grid {
+	type Message = ();
+	type Properties = Props;
+
+	fn create(props: Self::Properties, _: ComponentLink<Self>) -> Self {
+		Self {
+			class: props.class,
+			folder: props.folder,
+			items: props.items,
+			on_close: props.onclose,
+			on_folder: props.onfolder,
+			on_open: props.onopen,
+			thumbnail_offset: props.thumbnail_offset,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.class = props.class;
+		self.folder = props.folder;
+		self.items = props.items;
+		self.on_close = props.onclose;
+		self.on_folder = props.onfolder;
+		self.on_open = props.onopen;
+		self.thumbnail_offset = props.thumbnail_offset;
+		true
+	}
+
+	fn update(&mut self, _: Self::Message) -> ShouldRender {
+		false
+	}
+
+	#[allow(clippy::unnecessary_operation)]
+	fn view(&self) -> Html {
+		let (folders, files) = folder_entries(
+			&self.folder,
+			self.items.iter().map(|item| item.path.as_str()),
+		);
+		html! {
+			<div class=format!("bg-gray-800 text-white flex flex-col text-3xl lg:text-xl select-none {}", &self.class)>
+				<div class="flex place-items-center p-4">
+					{ self.view_breadcrumbs() }
+					<div class="cursor-pointer ml-2" onclick=self.on_close.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				<div class="flex-1 overflow-y-auto grid grid-cols-3 md:grid-cols-5 lg:grid-cols-8 gap-2 p-4 content-start">
+					{for folders.into_iter().map(|folder| {
+						let name = String::from(folder.rsplit('/').next().unwrap_or(&folder));
+						let icon = html! {
+							<span class="text-yellow-300">{ Icon::new_sized(IconKind::FileDirectoryFill, 64) }</span>
+						};
+						self.view_tile(icon, &name, self.on_folder.reform(move |_| folder.clone()))
+					})}
+					{for files.into_iter().map(|i| {
+						let item = &self.items[i];
+						let icon = kind_icon(&item.media_type());
+						let path = item.path.clone();
+						self.view_tile(
+							html! {
+								<Thumbnail
+									fallback=html! { Icon::new_sized(icon, 64) }
+									item=item.clone()
+									offset=self.thumbnail_offset
+								/>
+							},
+							&item.file.name(),
+							self.on_open.reform(move |_| path.clone()),
+						)
+					})}
+				</div>
+			</div>
+		}
+	}
+}
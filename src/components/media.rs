@@ -1,42 +1,63 @@
 use std::time::Duration;
 
-use js_sys::JsString;
+use js_sys::{Date, JsString};
 use log::warn;
-use wasm_bindgen::JsCast;
+use omnivyou_core::{
+	animation::{self, Animation, RgbaImage},
+	autoplay::{Event, State, Timer},
+	media::Type,
+	settings::Rule,
+};
+use wasm_bindgen::{Clamped, JsCast};
 use yew::{
 	html,
-	services::timeout::{TimeoutService, TimeoutTask},
-	web_sys::{File, Url},
-	Callback, Component, ComponentLink, Html, Properties, ShouldRender,
+	services::{
+		interval::IntervalTask,
+		timeout::{TimeoutService, TimeoutTask},
+		IntervalService,
+	},
+	web_sys::{CanvasRenderingContext2d, File, HtmlCanvasElement, HtmlMediaElement, ImageData, Url},
+	Callback, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender,
 };
+use yew_octicons::{Icon, IconKind};
+use yewtil::future::LinkFuture;
 
-use crate::app::Settings;
-
-#[derive(Clone, PartialEq)]
-pub enum Type {
-	Audio(String),
-	Image(String),
-	Video(String),
-	Invalid(String),
-}
+use crate::{browser, error::Error};
 
-impl From<String> for Type {
-	fn from(s: String) -> Self {
-		if s.starts_with("audio/") {
-			Type::Audio(s)
-		} else if s.starts_with("image/") {
-			Type::Image(s)
-		} else if s.starts_with("video/") {
-			Type::Video(s)
-		} else {
-			Type::Invalid(s)
-		}
-	}
-}
+/// How often the autoplay progress is updated.
+const PROGRESS_TICK: Duration = Duration::from_millis(100);
 
 pub enum Msg {
+	/// Frame timing and contents of the image behind the given object url, if it is animated.
+	Animation(String, Option<(Animation, Vec<u8>)>),
+	CancelAutoplay,
+	/// The timer of the autoplay state is up.
+	Elapsed,
 	Ended,
+	/// Reading the file behind the given object url failed.
+	Failed(String, Error),
+	LoadedMetadata,
+	NextFrame,
+	PauseAnimation,
+	PlayAnimation,
 	Seeked,
+	StepFrame(isize),
+	Tick,
+}
+
+/// Reads an image along with its frame timing if it is animated, looking at its header first so
+/// that still images are not read whole.
+async fn read_animation(file: &File) -> Result<Option<(Animation, Vec<u8>)>, Error> {
+	let header = browser::read_head(file, animation::HEADER_BYTES).await?;
+	if !animation::may_be_animated(&header) {
+		return Ok(None);
+	}
+	let bytes = if header.len() as f64 >= file.size() {
+		header
+	} else {
+		browser::read_bytes(file).await?
+	};
+	Ok(Animation::parse(&bytes).map(|animation| (animation, bytes)))
 }
 
 #[derive(Clone, PartialEq, Properties)]
@@ -44,32 +65,321 @@ pub struct Props {
 	#[prop_or_default]
 	pub class: String,
 	pub file: File,
+	/// Whether the slideshow is held, freezing autoplay timers and playback.
+	#[prop_or_default]
+	pub held: bool,
 	pub onended: Callback<()>,
-	pub settings: Settings,
+	pub onerror: Callback<Error>,
+	pub rule: Rule,
+	/// Whether the file is known to be a still image, which spares probing it for animation.
+	#[prop_or_default]
+	pub still: bool,
 }
 
 pub struct Media {
+	animation: Option<Animation>,
+	/// Contents of the animated image, kept around to decode its frames on demand.
+	bytes: Vec<u8>,
+	canvas_ref: NodeRef,
 	class: String,
-	ended: bool,
 	file: File,
+	frame: usize,
+	frame_timeout: Option<TimeoutTask>,
+	/// Decoded animation frames. Once available, animations are drawn frame by frame onto a canvas.
+	frames: Option<Vec<RgbaImage>>,
+	held: bool,
 	link: ComponentLink<Self>,
 	media_type: Type,
+	node_ref: NodeRef,
 	on_ended: Callback<()>,
-	settings: Settings,
-	src: String,
+	on_error: Callback<Error>,
+	/// Animation time played up until `playing_since`.
+	played: Duration,
+	/// Timestamp in milliseconds since when the animation is playing.
+	playing_since: Option<f64>,
+	progress_interval: Option<IntervalTask>,
+	rule: Rule,
+	/// Object url of the file, unless it could not be created.
+	src: Option<String>,
+	state: State,
+	still: bool,
+	timer: Option<Timer>,
 	timeout: Option<TimeoutTask>,
 }
 
 impl Media {
+	fn object_url(file: &File) -> Result<String, Error> {
+		Ok(Url::create_object_url_with_blob(&file.slice()?)?)
+	}
+
+	/// Creates the object url of the current file, reporting failures.
+	fn create_src(&mut self) {
+		self.src = match Self::object_url(&self.file) {
+			Ok(src) => Some(src),
+			Err(err) => {
+				self.on_error.emit(Error::load(&self.file.name(), err));
+				None
+			}
+		};
+	}
+
 	fn revoke_src(&self) {
-		if let Err(err) = Url::revoke_object_url(&self.src) {
-			if let Some(err_str) = err.dyn_ref::<JsString>() {
-				warn!("{}", err_str);
-			} else if let Some(err_code) = err.as_f64() {
-				warn!("{}", err_code);
+		if let Some(src) = &self.src {
+			if let Err(err) = Url::revoke_object_url(src) {
+				if let Some(err_str) = err.dyn_ref::<JsString>() {
+					warn!("{}", err_str);
+				} else if let Some(err_code) = err.as_f64() {
+					warn!("{}", err_code);
+				} else {
+					warn!("Failed to revoke object url '{}'", src);
+				}
+			}
+		}
+	}
+
+	/// Photos have nothing to play, so they count as ended as soon as they are shown, unless
+	/// animations should play for some loops.
+	fn is_still(&self) -> bool {
+		matches!(self.media_type, Type::Image(_))
+			&& (self.animation.is_none() || self.rule.animation_loops.is_none())
+	}
+
+	/// Applies an autoplay event, (re)spawning the timers implied by the resulting state.
+	fn transition(&mut self, event: Event) -> bool {
+		let state = match self.state.next(event, &self.rule) {
+			Some(state) => state,
+			None => return false,
+		};
+		let animation_remaining = match (&self.animation, self.rule.animation_loops) {
+			(Some(animation), Some(loops)) => {
+				Some(animation.play_time(loops).saturating_sub(self.elapsed()))
+			}
+			_ => None,
+		};
+		let now = Date::now();
+		self.state = state;
+		self.timer = state.next_timer(event, self.timer, &self.rule, animation_remaining, now);
+		let running = self.timer.filter(|timer| !timer.is_frozen());
+		self.timeout = running.map(|timer| {
+			TimeoutService::spawn(timer.remaining(now), self.link.callback(|_| Msg::Elapsed))
+		});
+		self.progress_interval =
+			running.map(|_| IntervalService::spawn(PROGRESS_TICK, self.link.callback(|_| Msg::Tick)));
+		if state == State::Advancing {
+			self.on_ended.emit(());
+		} else if self.held {
+			self.hold();
+		}
+		true
+	}
+
+	/// Freezes whatever plays or counts down while the slideshow is held.
+	fn hold(&mut self) -> bool {
+		if self.state.next(Event::Pause, &self.rule).is_none() {
+			return false;
+		}
+		// Animations which cannot be decoded keep moving, but their timers freeze all the same.
+		if self.animation.is_some() && self.update(Msg::PauseAnimation) {
+			return true;
+		}
+		if let Some(media) = self.node_ref.cast::<HtmlMediaElement>() {
+			if let Err(err) = media.pause() {
+				warn!("Failed to pause '{}': {:?}", self.file.name(), err);
+			}
+		}
+		self.transition(Event::Pause)
+	}
+
+	/// Picks up where the slideshow was held.
+	fn release(&mut self) -> bool {
+		if !self.state.is_paused() {
+			return false;
+		}
+		if self.animation.is_some() {
+			return self.update(Msg::PlayAnimation);
+		}
+		if let Some(media) = self.node_ref.cast::<HtmlMediaElement>() {
+			// Playback failures surface through the error event of the element.
+			let _ = media.play();
+		}
+		self.transition(Event::Resume)
+	}
+
+	/// Resets the state of the previous file and starts probing images for animations.
+	fn load(&mut self) {
+		self.animation = None;
+		self.bytes = Vec::new();
+		self.frame = 0;
+		self.frame_timeout = None;
+		self.frames = None;
+		self.played = Duration::ZERO;
+		self.playing_since = None;
+		self.transition(Event::Load);
+		if self.src.is_none() {
+			// Files which failed to load have nothing to show, so autoplay can move on.
+			self.transition(Event::End);
+		} else if let (false, true, Some(src)) = (
+			self.still,
+			animation::animatable(&self.file.type_()),
+			&self.src,
+		) {
+			let file = self.file.clone();
+			let src = src.clone();
+			self.link.send_future(async move {
+				match read_animation(&file).await {
+					Ok(animation) => Msg::Animation(src, animation),
+					Err(err) => Msg::Failed(src, Error::load(&file.name(), err)),
+				}
+			});
+		}
+	}
+
+	/// Animation time played so far.
+	fn elapsed(&self) -> Duration {
+		let playing = self
+			.playing_since
+			.map_or(0.0, |since| ((Date::now() - since) / 1000.0).max(0.0));
+		self.played + Duration::from_secs_f64(playing)
+	}
+
+	/// (Re)spawns the timer showing the next frame on the canvas.
+	fn schedule_frame(&mut self) {
+		self.frame_timeout = match (&self.animation, &self.frames) {
+			(Some(animation), Some(_)) if !self.state.is_paused() => Some(TimeoutService::spawn(
+				animation.frames[self.frame],
+				self.link.callback(|_| Msg::NextFrame),
+			)),
+			_ => None,
+		};
+	}
+
+	fn apply_volume(&self) {
+		if let Some(media) = self.node_ref.cast::<HtmlMediaElement>() {
+			media.set_volume(self.rule.volume as f64 / 100.0);
+		}
+	}
+
+	/// Decodes the animation frames unless that happened already, returning whether they are available.
+	fn decode_frames(&mut self) -> bool {
+		if self.frames.is_none() {
+			if let Some(animation) = &self.animation {
+				match animation.decode(&self.bytes) {
+					Ok(frames) if frames.len() == animation.frames.len() => self.frames = Some(frames),
+					Ok(_) => self.on_error.emit(Error::load(
+						&self.file.name(),
+						"animation frames do not match their timing",
+					)),
+					Err(err) => self.on_error.emit(Error::load(&self.file.name(), err)),
+				}
+			}
+		}
+		self.frames.is_some()
+	}
+
+	fn draw_frame(&self) {
+		let (canvas, frame) = match (
+			self.canvas_ref.cast::<HtmlCanvasElement>(),
+			self
+				.frames
+				.as_ref()
+				.and_then(|frames| frames.get(self.frame)),
+		) {
+			(Some(canvas), Some(frame)) => (canvas, frame),
+			_ => return,
+		};
+		let context = canvas
+			.get_context("2d")
+			.ok()
+			.flatten()
+			.and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok());
+		let image = ImageData::new_with_u8_clamped_array_and_sh(
+			Clamped(frame.as_raw()),
+			frame.width(),
+			frame.height(),
+		);
+		if let (Some(context), Ok(image)) = (context, image) {
+			if let Err(err) = context.put_image_data(&image, 0.0, 0.0) {
+				warn!("Failed to draw frame: {:?}", err);
+			}
+		}
+	}
+
+	/// Time left until the next item follows, with a button to stay on the current one instead.
+	fn view_countdown(&self) -> Html {
+		let remaining = match (self.state, self.timer) {
+			(State::Countdown, Some(timer)) => timer.remaining(Date::now()).as_secs_f64(),
+			_ => return html!(),
+		};
+		html! {
+			<div class="absolute top-0 mt-4 px-4 py-2 rounded-full bg-gray-700 bg-opacity-25 hover:bg-opacity-70 transition duration-500 flex place-items-center select-none text-white text-3xl lg:text-xl">
+				{ Icon::new_sized(IconKind::Clock, 32) }
+				<span class="mx-2">{ format!("Next in {}s", remaining.ceil()) }</span>
+				<div class="cursor-pointer mx-2" title="Cancel autoplay" onclick=self.link.callback(|_| Msg::CancelAutoplay)>
+					{ Icon::new_sized(IconKind::X, 32) }
+				</div>
+			</div>
+		}
+	}
+
+	/// Bar shrinking along with the time left until autoplay moves on, dimmed while frozen.
+	fn view_progress(&self) -> Html {
+		let timer = match self.timer {
+			Some(timer) => timer,
+			None => return html!(),
+		};
+		let class = format!(
+			"absolute bottom-0 left-0 h-2 lg:h-1 transition-all duration-100 ease-linear pointer-events-none {} {}",
+			if self.state == State::Countdown {
+				"bg-white"
+			} else {
+				"bg-blue-400"
+			},
+			if timer.is_frozen() {
+				"bg-opacity-40"
 			} else {
-				warn!("Failed to revoke object url '{}'", self.src);
+				"bg-opacity-80"
 			}
+		);
+		html! {
+			<div class=class style=format!("width: {}%", timer.progress(Date::now()) * 100.0) />
+		}
+	}
+
+	fn view_animation_controls(&self) -> Html {
+		let animation = match &self.animation {
+			Some(animation) => animation,
+			None => return html!(),
+		};
+		let button_class = "cursor-pointer mx-2";
+		html! {
+			<div class="absolute bottom-0 mb-24 px-4 py-2 rounded-full bg-gray-700 bg-opacity-25 hover:bg-opacity-70 transition duration-500 flex place-items-center select-none text-white">
+				<div class=button_class onclick=self.link.callback(|_| Msg::StepFrame(-1))>
+					{ Icon::new_sized(IconKind::TriangleLeft, 32) }
+				</div>
+				{if self.state.is_paused() {
+					html! {
+						<div class=button_class onclick=self.link.callback(|_| Msg::PlayAnimation)>
+							{ Icon::new_sized(IconKind::Play, 32) }
+						</div>
+					}
+				} else {
+					html! {
+						<div class=button_class onclick=self.link.callback(|_| Msg::PauseAnimation)>
+							{ Icon::new_sized(IconKind::SquareFill, 32) }
+						</div>
+					}
+				}}
+				<div class=button_class onclick=self.link.callback(|_| Msg::StepFrame(1))>
+					{ Icon::new_sized(IconKind::TriangleRight, 32) }
+				</div>
+				{if self.state.is_paused() {
+					html! {
+						<span class="mx-2 text-xl">{ format!("{}/{}", self.frame + 1, animation.frames.len()) }</span>
+					}
+				} else {
+					html!()
+				}}
+			</div>
 		}
 	}
 }
@@ -80,30 +390,34 @@ impl Component for Media {
 
 	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
 		let media_type = Type::from(props.file.type_());
-		let timeout = match media_type {
-			Type::Image(_) => {
-				if props.settings.toggle_image_autoplay {
-					Some(TimeoutService::spawn(
-						props.settings.config_image_autoplay_delay,
-						props.onended.clone(),
-					))
-				} else {
-					None
-				}
-			}
-			_ => None,
-		};
-		Self {
+		let mut media = Self {
+			animation: None,
+			bytes: Vec::new(),
+			canvas_ref: NodeRef::default(),
 			class: props.class,
-			ended: false,
-			file: props.file.clone(),
+			file: props.file,
+			frame: 0,
+			frame_timeout: None,
+			frames: None,
+			held: props.held,
 			link,
 			media_type,
-			on_ended: props.onended.clone(),
-			settings: props.settings,
-			src: Url::create_object_url_with_blob(&props.file.slice().ok().unwrap()).unwrap(),
-			timeout,
-		}
+			node_ref: NodeRef::default(),
+			on_ended: props.onended,
+			on_error: props.onerror,
+			played: Duration::ZERO,
+			playing_since: None,
+			progress_interval: None,
+			rule: props.rule,
+			src: None,
+			state: State::Loading,
+			still: props.still,
+			timer: None,
+			timeout: None,
+		};
+		media.create_src();
+		media.load();
+		media
 	}
 
 	fn change(&mut self, props: Self::Properties) -> ShouldRender {
@@ -112,75 +426,35 @@ impl Component for Media {
 			should_render = true;
 			self.class = props.class;
 		}
-		let media_type = Type::from(props.file.type_());
-		let new_media_type = self.media_type != media_type;
-		let new_media = new_media_type || self.file != props.file;
+		let new_rule = self.rule != props.rule;
+		if new_rule {
+			should_render = true;
+			self.rule = props.rule;
+			self.apply_volume();
+		}
+		let new_media = self.file != props.file;
 		if new_media {
 			should_render = true;
 			self.revoke_src();
-			self.ended = false;
-			self.file = props.file.clone();
-			self.media_type = media_type.clone();
-			self.src = Url::create_object_url_with_blob(&props.file.slice().ok().unwrap()).unwrap();
-			self.timeout = None;
-		}
-		let new_callback = self.on_ended != props.onended;
-		if new_callback {
-			should_render = true;
-			self.on_ended = props.onended;
-		}
-		let new_settings = self.settings != props.settings;
-		let new_audio_setting = self.settings.toggle_audio_autoplay
-			!= props.settings.toggle_audio_autoplay
-			|| self.settings.config_audio_autoplay_delay != props.settings.config_audio_autoplay_delay;
-		let new_image_setting = self.settings.toggle_image_autoplay
-			!= props.settings.toggle_image_autoplay
-			|| self.settings.config_image_autoplay_delay != props.settings.config_image_autoplay_delay;
-		let new_video_setting = self.settings.toggle_video_autoplay
-			!= props.settings.toggle_video_autoplay
-			|| self.settings.config_video_autoplay_delay != props.settings.config_video_autoplay_delay;
-		if new_settings {
-			if !new_media_type && !new_callback {
-				match media_type {
-					Type::Audio(_) => {
-						if new_audio_setting {
-							self.timeout = if props.settings.toggle_audio_autoplay && self.ended {
-								Some(TimeoutService::spawn(
-									props.settings.config_audio_autoplay_delay,
-									self.on_ended.clone(),
-								))
-							} else {
-								None
-							};
-						}
-					}
-					Type::Video(_) => {
-						if new_video_setting {
-							self.timeout = if props.settings.toggle_video_autoplay && self.ended {
-								Some(TimeoutService::spawn(
-									props.settings.config_video_autoplay_delay,
-									self.on_ended.clone(),
-								))
-							} else {
-								None
-							};
-						}
-					}
-					_ => (),
-				}
-			}
-			self.settings = props.settings;
+			self.file = props.file;
+			self.media_type = Type::from(self.file.type_());
+			self.still = props.still;
+			self.create_src();
+			self.load();
 		}
-		if matches!(media_type, Type::Image(_)) {
-			if props.settings.toggle_image_autoplay {
-				if new_media || new_callback || new_image_setting {
-					self.timeout = Some(TimeoutService::spawn(
-						props.settings.config_image_autoplay_delay,
-						self.on_ended.clone(),
-					));
-				}
+		self.on_ended = props.onended;
+		self.on_error = props.onerror;
+		if new_rule && !new_media {
+			let still = self.is_still();
+			self.transition(Event::Rule { still });
+		}
+		if self.held != props.held {
+			should_render = true;
+			self.held = props.held;
+			if self.held {
+				self.hold();
 			} else {
-				self.timeout = None;
+				self.release();
 			}
 		}
 		should_render
@@ -188,48 +462,165 @@ impl Component for Media {
 
 	fn update(&mut self, msg: Self::Message) -> ShouldRender {
 		match msg {
-			Msg::Ended => {
-				self.ended = true;
-				let (set_timeout, delay) = match self.media_type {
-					Type::Audio(_) => (
-						self.settings.toggle_audio_autoplay,
-						self.settings.config_audio_autoplay_delay,
-					),
-					Type::Image(_) => (
-						self.settings.toggle_image_autoplay,
-						self.settings.config_image_autoplay_delay,
-					),
-					Type::Video(_) => (
-						self.settings.toggle_video_autoplay,
-						self.settings.config_video_autoplay_delay,
-					),
-					Type::Invalid(_) => (false, Duration::ZERO),
-				};
-				if set_timeout {
-					self.timeout = Some(TimeoutService::spawn(delay, self.on_ended.clone()));
+			Msg::Animation(src, animation) => {
+				// The file may have changed while it was being read.
+				if Some(&src) != self.src.as_ref() {
+					return false;
+				}
+				if let Some((animation, bytes)) = animation {
+					self.animation = Some(animation);
+					self.bytes = bytes;
+					self.playing_since = Some(Date::now());
+				}
+				let still = self.is_still();
+				self.transition(Event::Loaded { still });
+				true
+			}
+			Msg::CancelAutoplay => self.transition(Event::Cancel),
+			Msg::Elapsed => self.transition(Event::Elapsed),
+			Msg::Ended => self.transition(Event::End),
+			Msg::Failed(src, err) => {
+				self.on_error.emit(err);
+				if Some(&src) == self.src.as_ref() {
+					return self.transition(Event::End);
 				}
 				false
 			}
-			Msg::Seeked => {
-				self.ended = false;
-				self.timeout = None;
+			Msg::LoadedMetadata => {
+				self.apply_volume();
+				self.transition(Event::Loaded { still: false });
+				if let (true, Some(max), Some(media)) = (
+					self.rule.autoplay,
+					self.rule.max_display_time,
+					self.node_ref.cast::<HtmlMediaElement>(),
+				) {
+					let position = self
+						.rule
+						.offset
+						.position(media.duration(), max, js_sys::Math::random());
+					if position > 0.0 {
+						// Restarts the max play time countdown once seeked.
+						media.set_current_time(position);
+					}
+				}
 				false
 			}
+			Msg::NextFrame => {
+				if let Some(animation) = &self.animation {
+					let elapsed = self.elapsed();
+					self.frame = animation.frame_at(elapsed);
+					let stopped = animation
+						.plays
+						.is_some_and(|plays| elapsed >= animation.duration() * plays);
+					if stopped {
+						self.frame_timeout = None;
+					} else {
+						self.schedule_frame();
+					}
+				}
+				true
+			}
+			Msg::PauseAnimation => {
+				if self.state.next(Event::Pause, &self.rule).is_none() || !self.decode_frames() {
+					return false;
+				}
+				self.played = self.elapsed();
+				self.playing_since = None;
+				self.transition(Event::Pause);
+				if let Some(animation) = &self.animation {
+					self.frame = animation.frame_at(self.played);
+				}
+				self.schedule_frame();
+				true
+			}
+			Msg::PlayAnimation => {
+				if !self.state.is_paused() {
+					return false;
+				}
+				self.playing_since = Some(Date::now());
+				self.transition(Event::Resume);
+				self.schedule_frame();
+				true
+			}
+			Msg::Seeked => self.transition(Event::Seeked),
+			Msg::StepFrame(step) => {
+				if !self.state.is_paused() && !self.update(Msg::PauseAnimation) {
+					return false;
+				}
+				if let Some(animation) = &self.animation {
+					let count = animation.frames.len() as isize;
+					self.frame = (self.frame as isize + step).rem_euclid(count) as usize;
+					// Keep the played loops, so resuming continues from the shown frame.
+					let duration = animation.duration();
+					let loops = if duration.is_zero() {
+						0
+					} else {
+						(self.played.as_nanos() / duration.as_nanos()) as u32
+					};
+					self.played = duration * loops + animation.frame_start(self.frame);
+				}
+				true
+			}
+			Msg::Tick => true,
 		}
 	}
 
+	fn rendered(&mut self, _first_render: bool) {
+		self.draw_frame();
+	}
+
 	fn view(&self) -> Html {
-		match &self.media_type {
-			Type::Audio(_) => {
-				html!(<audio autoplay="" class=&self.class controls=true src=self.src.clone() onended=self.link.callback(|_| Msg::Ended) onseeked=self.link.callback(|_| Msg::Seeked) />)
+		let src = match &self.src {
+			Some(src) => src,
+			None => {
+				return html! {
+					<>
+						{ format!("Failed to load '{}'", self.file.name()) }
+						{ self.view_countdown() }
+						{ self.view_progress() }
+					</>
+				}
 			}
-			Type::Image(_) => {
-				html!(<img class=&self.class src=self.src.clone() />)
+		};
+		let onerror = {
+			let name = self.file.name();
+			let src = src.clone();
+			self.link.callback(move |_| {
+				Msg::Failed(
+					src.clone(),
+					Error::load(&name, "the browser cannot play it"),
+				)
+			})
+		};
+		let media = match &self.media_type {
+			Type::Audio(_) => {
+				html!(<audio ref=self.node_ref.clone() autoplay="" class=&self.class controls=true loop=self.rule.looping src=src.clone() onended=self.link.callback(|_| Msg::Ended) onerror=onerror onloadedmetadata=self.link.callback(|_| Msg::LoadedMetadata) onseeked=self.link.callback(|_| Msg::Seeked) />)
 			}
+			Type::Image(_) => match self.frames.as_ref().and_then(|frames| frames.first()) {
+				Some(first) => html! {
+					<>
+						<canvas ref=self.canvas_ref.clone() class=&self.class width=first.width().to_string() height=first.height().to_string() />
+						{ self.view_animation_controls() }
+					</>
+				},
+				None => html! {
+					<>
+						<img class=&self.class src=src.clone() />
+						{ self.view_animation_controls() }
+					</>
+				},
+			},
 			Type::Video(_) => {
-				html!(<video autoplay="" class=&self.class controls=true src=self.src.clone() onended=self.link.callback(|_| Msg::Ended) onseeked=self.link.callback(|_| Msg::Seeked) />)
+				html!(<video ref=self.node_ref.clone() autoplay="" class=&self.class controls=true loop=self.rule.looping src=src.clone() onended=self.link.callback(|_| Msg::Ended) onerror=onerror onloadedmetadata=self.link.callback(|_| Msg::LoadedMetadata) onseeked=self.link.callback(|_| Msg::Seeked) />)
 			}
 			Type::Invalid(t) => html!(format!("Invalid media type '{}'", t)),
+		};
+		html! {
+			<>
+				{ media }
+				{ self.view_countdown() }
+				{ self.view_progress() }
+			</>
 		}
 	}
 
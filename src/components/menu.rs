@@ -1,46 +1,148 @@
-use yew::{html, Callback, ChangeData, Component, ComponentLink, Html, Properties, ShouldRender};
+use std::time::Duration;
+
+use yew::{
+	html, Callback, ChangeData, Component, ComponentLink, Html, InputData, Properties, ShouldRender,
+};
 use yew_octicons::{Icon, IconKind};
 
-use crate::app::{self, Settings};
+use omnivyou_core::{
+	media::Kind,
+	playlist::Format,
+	settings::{self, Matcher, Offset, Rule, RuleChange, Settings},
+};
+
+use crate::library::Source;
 
 pub enum Msg {
+	AddRule,
+	ChangeRule(usize, RuleChange),
 	Close,
+	DeleteProfile,
+	NewProfileName(String),
+	NewRuleKind(Kind),
+	NewRulePattern(String),
+	None,
+	RemoveRule(usize),
+	SaveProfile,
 	ToggleVisible,
 }
 
+fn parse_seconds(value: ChangeData) -> Option<Duration> {
+	match value {
+		ChangeData::Value(v) => v
+			.parse::<f64>()
+			.ok()
+			.and_then(|s| Duration::try_from_secs_f64(s).ok()),
+		_ => None,
+	}
+}
+
 #[derive(Clone, PartialEq, Properties)]
 pub struct Props {
 	#[prop_or_default]
 	pub button_class: String,
+	/// Adds the selected folder or files to the library.
+	#[prop_or_default]
+	pub add_source_callback: Option<Callback<ChangeData>>,
 	#[prop_or_default]
 	pub class: String,
+	/// Asks for a link opening the app with the current settings to be copied.
+	#[prop_or_default]
+	pub copy_link_callback: Option<Callback<()>>,
+	#[prop_or_default]
+	pub export_playlist_callback: Option<Callback<Format>>,
+	#[prop_or_default]
+	pub import_playlist_callback: Option<Callback<ChangeData>>,
 	pub settings: Settings,
-	pub toggle_audio_callback: Callback<ChangeData>,
-	pub toggle_audio_autoplay_callback: Callback<ChangeData>,
-	pub config_audio_autoplay_delay_callback: Callback<ChangeData>,
-	pub toggle_image_callback: Callback<ChangeData>,
-	pub toggle_image_autoplay_callback: Callback<ChangeData>,
-	pub config_image_autoplay_delay_callback: Callback<ChangeData>,
-	pub toggle_video_callback: Callback<ChangeData>,
-	pub toggle_video_autoplay_callback: Callback<ChangeData>,
-	pub config_video_autoplay_delay_callback: Callback<ChangeData>,
+	pub add_rule_callback: Callback<Rule>,
+	pub remove_rule_callback: Callback<usize>,
+	pub rule_callback: Callback<(usize, RuleChange)>,
+	#[prop_or_default]
+	pub remove_source_callback: Callback<usize>,
+	/// Folders and files the library was put together from.
+	#[prop_or_default]
+	pub sources: Vec<Source>,
+	/// Currently selected soundtrack file or folder.
+	#[prop_or_default]
+	pub soundtrack: Option<String>,
+	/// Audio files and folders which can be selected as soundtrack.
+	#[prop_or_default]
+	pub soundtrack_options: Vec<String>,
+	#[prop_or_default]
+	pub select_soundtrack_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub config_soundtrack_fade_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub config_soundtrack_volume_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub toggle_soundtrack_sync_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub config_page_size_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub toggle_page_by_folder_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub config_thumbnail_cache_size_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub config_thumbnail_offset_callback: Callback<ChangeData>,
+	/// Profile the settings were last switched to or saved as.
+	#[prop_or_default]
+	pub profile: Option<String>,
+	/// Names of the saved profiles.
+	#[prop_or_default]
+	pub profiles: Vec<String>,
+	#[prop_or_default]
+	pub delete_profile_callback: Callback<String>,
+	#[prop_or_default]
+	pub export_profiles_callback: Callback<()>,
+	#[prop_or_default]
+	pub import_profiles_callback: Callback<ChangeData>,
+	#[prop_or_default]
+	pub save_profile_callback: Callback<String>,
+	#[prop_or_default]
+	pub select_profile_callback: Callback<String>,
+	/// Whether the panel is open, which is up to the settings route.
+	#[prop_or_default]
+	pub visible: bool,
+	/// Asks for the panel to open or close.
+	pub visible_callback: Callback<bool>,
 }
 
 pub struct Menu {
+	add_source_callback: Option<Callback<ChangeData>>,
 	button_class: String,
 	class: String,
+	copy_link_callback: Option<Callback<()>>,
+	export_playlist_callback: Option<Callback<Format>>,
+	import_playlist_callback: Option<Callback<ChangeData>>,
 	link: ComponentLink<Self>,
+	new_profile_name: String,
+	new_rule_kind: Kind,
+	new_rule_pattern: String,
 	settings: Settings,
-	toggle_audio_callback: Callback<ChangeData>,
-	toggle_audio_autoplay_callback: Callback<ChangeData>,
-	config_audio_autoplay_delay_callback: Callback<ChangeData>,
-	toggle_image_callback: Callback<ChangeData>,
-	toggle_image_autoplay_callback: Callback<ChangeData>,
-	config_image_autoplay_delay_callback: Callback<ChangeData>,
-	toggle_video_callback: Callback<ChangeData>,
-	toggle_video_autoplay_callback: Callback<ChangeData>,
-	config_video_autoplay_delay_callback: Callback<ChangeData>,
+	add_rule_callback: Callback<Rule>,
+	remove_rule_callback: Callback<usize>,
+	rule_callback: Callback<(usize, RuleChange)>,
+	remove_source_callback: Callback<usize>,
+	sources: Vec<Source>,
+	soundtrack: Option<String>,
+	soundtrack_options: Vec<String>,
+	select_soundtrack_callback: Callback<ChangeData>,
+	config_soundtrack_fade_callback: Callback<ChangeData>,
+	config_soundtrack_volume_callback: Callback<ChangeData>,
+	toggle_soundtrack_sync_callback: Callback<ChangeData>,
+	config_page_size_callback: Callback<ChangeData>,
+	toggle_page_by_folder_callback: Callback<ChangeData>,
+	config_thumbnail_cache_size_callback: Callback<ChangeData>,
+	config_thumbnail_offset_callback: Callback<ChangeData>,
+	profile: Option<String>,
+	profiles: Vec<String>,
+	delete_profile_callback: Callback<String>,
+	export_profiles_callback: Callback<()>,
+	import_profiles_callback: Callback<ChangeData>,
+	save_profile_callback: Callback<String>,
+	select_profile_callback: Callback<String>,
 	visible: bool,
+	visible_callback: Callback<bool>,
 }
 
 impl Component for Menu {
@@ -49,48 +151,145 @@ impl Component for Menu {
 
 	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
 		Self {
+			add_source_callback: props.add_source_callback,
 			button_class: props.button_class,
 			class: props.class,
+			copy_link_callback: props.copy_link_callback,
+			export_playlist_callback: props.export_playlist_callback,
+			import_playlist_callback: props.import_playlist_callback,
 			link,
+			new_profile_name: String::new(),
+			new_rule_kind: Kind::Image,
+			new_rule_pattern: String::new(),
 			settings: props.settings,
-			toggle_audio_callback: props.toggle_audio_callback,
-			toggle_audio_autoplay_callback: props.toggle_audio_autoplay_callback,
-			config_audio_autoplay_delay_callback: props.config_audio_autoplay_delay_callback,
-			toggle_image_callback: props.toggle_image_callback,
-			toggle_image_autoplay_callback: props.toggle_image_autoplay_callback,
-			config_image_autoplay_delay_callback: props.config_image_autoplay_delay_callback,
-			toggle_video_callback: props.toggle_video_callback,
-			toggle_video_autoplay_callback: props.toggle_video_autoplay_callback,
-			config_video_autoplay_delay_callback: props.config_video_autoplay_delay_callback,
-			visible: false,
+			add_rule_callback: props.add_rule_callback,
+			remove_rule_callback: props.remove_rule_callback,
+			rule_callback: props.rule_callback,
+			remove_source_callback: props.remove_source_callback,
+			sources: props.sources,
+			soundtrack: props.soundtrack,
+			soundtrack_options: props.soundtrack_options,
+			select_soundtrack_callback: props.select_soundtrack_callback,
+			config_soundtrack_fade_callback: props.config_soundtrack_fade_callback,
+			config_soundtrack_volume_callback: props.config_soundtrack_volume_callback,
+			toggle_soundtrack_sync_callback: props.toggle_soundtrack_sync_callback,
+			config_page_size_callback: props.config_page_size_callback,
+			toggle_page_by_folder_callback: props.toggle_page_by_folder_callback,
+			config_thumbnail_cache_size_callback: props.config_thumbnail_cache_size_callback,
+			config_thumbnail_offset_callback: props.config_thumbnail_offset_callback,
+			profile: props.profile,
+			profiles: props.profiles,
+			delete_profile_callback: props.delete_profile_callback,
+			export_profiles_callback: props.export_profiles_callback,
+			import_profiles_callback: props.import_profiles_callback,
+			save_profile_callback: props.save_profile_callback,
+			select_profile_callback: props.select_profile_callback,
+			visible: props.visible,
+			visible_callback: props.visible_callback,
 		}
 	}
 
 	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.add_source_callback = props.add_source_callback;
 		self.button_class = props.button_class;
 		self.class = props.class;
+		self.copy_link_callback = props.copy_link_callback;
+		self.export_playlist_callback = props.export_playlist_callback;
+		self.import_playlist_callback = props.import_playlist_callback;
 		self.settings = props.settings;
-		self.toggle_audio_callback = props.toggle_audio_callback;
-		self.toggle_audio_autoplay_callback = props.toggle_audio_autoplay_callback;
-		self.config_audio_autoplay_delay_callback = props.config_audio_autoplay_delay_callback;
-		self.toggle_image_callback = props.toggle_image_callback;
-		self.toggle_image_autoplay_callback = props.toggle_image_autoplay_callback;
-		self.config_image_autoplay_delay_callback = props.config_image_autoplay_delay_callback;
-		self.toggle_video_callback = props.toggle_video_callback;
-		self.toggle_video_autoplay_callback = props.toggle_video_autoplay_callback;
-		self.config_video_autoplay_delay_callback = props.config_video_autoplay_delay_callback;
+		self.add_rule_callback = props.add_rule_callback;
+		self.remove_rule_callback = props.remove_rule_callback;
+		self.rule_callback = props.rule_callback;
+		self.remove_source_callback = props.remove_source_callback;
+		self.sources = props.sources;
+		self.soundtrack = props.soundtrack;
+		self.soundtrack_options = props.soundtrack_options;
+		self.select_soundtrack_callback = props.select_soundtrack_callback;
+		self.config_soundtrack_fade_callback = props.config_soundtrack_fade_callback;
+		self.config_soundtrack_volume_callback = props.config_soundtrack_volume_callback;
+		self.toggle_soundtrack_sync_callback = props.toggle_soundtrack_sync_callback;
+		self.config_page_size_callback = props.config_page_size_callback;
+		self.toggle_page_by_folder_callback = props.toggle_page_by_folder_callback;
+		self.config_thumbnail_cache_size_callback = props.config_thumbnail_cache_size_callback;
+		self.config_thumbnail_offset_callback = props.config_thumbnail_offset_callback;
+		self.profile = props.profile;
+		self.profiles = props.profiles;
+		self.delete_profile_callback = props.delete_profile_callback;
+		self.export_profiles_callback = props.export_profiles_callback;
+		self.import_profiles_callback = props.import_profiles_callback;
+		self.save_profile_callback = props.save_profile_callback;
+		self.select_profile_callback = props.select_profile_callback;
+		self.visible = props.visible;
+		self.visible_callback = props.visible_callback;
 		true
 	}
 
 	fn update(&mut self, msg: Self::Message) -> ShouldRender {
 		match msg {
+			Msg::AddRule => {
+				if let Some(matcher) = Matcher::parse(&self.new_rule_pattern) {
+					// A MIME type decides the kind on its own.
+					let kind = match &matcher {
+						Matcher::Mime(mime) => Kind::from_mime(mime),
+						Matcher::Extension(_) => Some(self.new_rule_kind),
+					};
+					if let Some(kind) = kind {
+						let mut rule = self
+							.settings
+							.kind_rule(kind)
+							.cloned()
+							.unwrap_or_else(|| Rule::new(kind, None));
+						rule.matcher = Some(matcher);
+						self.add_rule_callback.emit(rule);
+						self.new_rule_pattern.clear();
+						return true;
+					}
+				}
+				false
+			}
+			Msg::ChangeRule(index, change) => {
+				self.rule_callback.emit((index, change));
+				false
+			}
 			Msg::Close => {
-				self.visible = false;
+				self.visible_callback.emit(false);
+				false
+			}
+			Msg::DeleteProfile => {
+				if let Some(profile) = &self.profile {
+					self.delete_profile_callback.emit(profile.clone());
+				}
+				false
+			}
+			Msg::NewProfileName(name) => {
+				self.new_profile_name = name;
+				false
+			}
+			Msg::NewRuleKind(kind) => {
+				self.new_rule_kind = kind;
+				false
+			}
+			Msg::NewRulePattern(pattern) => {
+				self.new_rule_pattern = pattern;
+				false
+			}
+			Msg::None => false,
+			Msg::RemoveRule(index) => {
+				self.remove_rule_callback.emit(index);
+				false
+			}
+			Msg::SaveProfile => {
+				let name = self.new_profile_name.trim();
+				if name.is_empty() {
+					return false;
+				}
+				self.save_profile_callback.emit(String::from(name));
+				self.new_profile_name.clear();
 				true
 			}
 			Msg::ToggleVisible => {
-				self.visible = !self.visible;
-				true
+				self.visible_callback.emit(!self.visible);
+				false
 			}
 		}
 	}
@@ -113,81 +312,373 @@ impl Component for Menu {
 				}}
 				<div class=format!("{} {}", panel_class, if self.visible { "translate-x-0" } else { "-translate-x-full" })>
 					<form class=form_class>
+						{match &self.add_source_callback {
+							Some(add_source) => html! {
+								<fieldset class=fieldset_class>
+									<legend>{ "Sources" }</legend>
+									{for self.sources.iter().map(|source| {
+										let id = source.id;
+										html! {
+											<div class=field_class>
+												<span class="flex-1 truncate" title=source.name.clone()>{ &source.name }</span>
+												<span class="text-opacity-50 text-white mx-4">{ source.count }</span>
+												<span class="cursor-pointer" title="Remove source" onclick=self.remove_source_callback.reform(move |_| id)>
+													{ Icon::new_sized(IconKind::Trash, 32) }
+												</span>
+											</div>
+										}
+									})}
+									<label for="source-folder" class=format!("{} {}", field_class, "cursor-pointer")>
+										<span class="flex-1">{ "Add folder" }</span>
+										{ Icon::new_sized(IconKind::FileDirectory, 32) }
+									</label>
+									<input id="source-folder" type="file" value="" webkitdirectory="" class="hidden" onchange=add_source />
+									<label for="source-files" class=format!("{} {}", field_class, "cursor-pointer")>
+										<span class="flex-1">{ "Add files" }</span>
+										{ Icon::new_sized(IconKind::FileMedia, 32) }
+									</label>
+									<input id="source-files" type="file" value="" multiple=true class="hidden" onchange=add_source />
+								</fieldset>
+							},
+							None => html!(),
+						}}
 						<fieldset class=fieldset_class>
-							<legend>{ "Video" }</legend>
+							<legend>{ "Profile" }</legend>
 							<div class=field_class>
-								<label class="flex-1">{ "Autoplay" }</label>
-								<input type="checkbox" class=input_checkbox
-									checked=self.settings.toggle_video_autoplay
-									disabled=!self.settings.toggle_video
-									onchange=&self.toggle_video_autoplay_callback
+								<select class="flex-1 bg-gray-800 outline-none truncate"
+									onchange=self.select_profile_callback.reform(|value| match value {
+										ChangeData::Select(select) => select.value(),
+										_ => String::new(),
+									})
+								>
+									<option value="" disabled=true selected=self.profile.is_none()>{ "Custom" }</option>
+									{for self.profiles.iter().map(|name| html! {
+										<option value=name.clone() selected=self.profile.as_ref() == Some(name)>{ name }</option>
+									})}
+								</select>
+								{if self.profile.is_some() {
+									html! {
+										<span class="cursor-pointer ml-4" title="Delete profile" onclick=self.link.callback(|_| Msg::DeleteProfile)>
+											{ Icon::new_sized(IconKind::Trash, 32) }
+										</span>
+									}
+								} else {
+									html!()
+								}}
+							</div>
+							<div class=field_class>
+								<input type="text" class="flex-1 bg-white bg-opacity-10 outline-none px-2 w-1/3"
+									placeholder=self.profile.clone().unwrap_or_else(|| String::from("Profile name"))
+									value=self.new_profile_name.clone()
+									oninput=self.link.callback(|e: InputData| Msg::NewProfileName(e.value))
 								/>
+								<span class="cursor-pointer ml-4" title="Save settings as profile" onclick=self.link.callback(|_| Msg::SaveProfile)>
+									{ Icon::new_sized(IconKind::Plus, 32) }
+								</span>
 							</div>
+							<label for="profiles" class=format!("{} {}", field_class, "cursor-pointer")>
+								<span class="flex-1">{ "Import" }</span>
+								{ Icon::new_sized(IconKind::Upload, 32) }
+							</label>
+							<input id="profiles" type="file" value="" accept=".json" class="hidden" onchange=&self.import_profiles_callback />
+							<div class=format!("{} {}", field_class, "cursor-pointer") onclick=self.export_profiles_callback.reform(|_| ())>
+								<span class="flex-1">{ "Export" }</span>
+								{ Icon::new_sized(IconKind::Download, 32) }
+							</div>
+						</fieldset>
+						{for self.settings.rules.iter().enumerate().map(|(i, rule)| {
+							let playable = rule.kind != Kind::Image;
+							html! {
+								<fieldset class=fieldset_class>
+									<legend class="flex w-full">
+										<span class="flex-1">{ rule.name() }</span>
+										{if rule.matcher.is_some() {
+											html! {
+												<span class="cursor-pointer" onclick=self.link.callback(move |_| Msg::RemoveRule(i))>
+													{ Icon::new_sized(IconKind::Trash, 32) }
+												</span>
+											}
+										} else {
+											html!()
+										}}
+									</legend>
+									<div class=field_class>
+										<label class="flex-1">{ "Enabled" }</label>
+										<input type="checkbox" class=input_checkbox
+											checked=rule.enabled
+											onchange=self.link.callback(move |_| Msg::ChangeRule(i, RuleChange::ToggleEnabled))
+										/>
+									</div>
+									<div class=field_class>
+										<label class="flex-1" disabled=!rule.enabled>{ "Autoplay" }</label>
+										<input type="checkbox" class=input_checkbox
+											checked=rule.autoplay
+											disabled=!rule.enabled
+											onchange=self.link.callback(move |_| Msg::ChangeRule(i, RuleChange::ToggleAutoplay))
+										/>
+									</div>
+									<div class=field_class>
+										<label class="flex-1" disabled={ !rule.enabled || !rule.autoplay }>
+											{ if playable { "Delay" } else { "Duration" } }
+										</label>
+										<input type="number" class=input_number
+											disabled={ !rule.enabled || !rule.autoplay }
+											min=if playable { "0" } else { "0.1" }
+											max=format!("{}", settings::MAX_DELAY_SECONDS.as_secs())
+											step="0.1"
+											value=format!("{}", rule.delay.as_secs_f64())
+											onchange=self.link.callback(move |value| match parse_seconds(value) {
+												Some(delay) => Msg::ChangeRule(i, RuleChange::Delay(delay)),
+												None => Msg::None,
+											})
+										/>
+									</div>
+									{if playable {
+										html! {
+											<>
+												<div class=field_class>
+													<label class="flex-1" disabled={ !rule.enabled || !rule.autoplay }>{ "Max play time" }</label>
+													<input type="number" class=input_number
+														disabled={ !rule.enabled || !rule.autoplay }
+														min=0
+														max=format!("{}", settings::MAX_DELAY_SECONDS.as_secs())
+														placeholder="∞"
+														step="0.1"
+														value=rule.max_display_time.map(|m| format!("{}", m.as_secs_f64())).unwrap_or_default()
+														onchange=self.link.callback(move |value| match parse_seconds(value) {
+															// Zero or an empty field lets items play in full.
+															Some(max) if !max.is_zero() => Msg::ChangeRule(i, RuleChange::MaxDisplayTime(Some(max))),
+															_ => Msg::ChangeRule(i, RuleChange::MaxDisplayTime(None)),
+														})
+													/>
+												</div>
+												<div class=field_class>
+													<label class="flex-1" disabled={ !rule.enabled || !rule.autoplay || rule.max_display_time.is_none() }>{ "Start at" }</label>
+													<select class="bg-gray-800 outline-none mr-4"
+														disabled={ !rule.enabled || !rule.autoplay || rule.max_display_time.is_none() }
+														onchange=self.link.callback(move |value| match value {
+															ChangeData::Select(select) => match select.selected_index() {
+																0 => Msg::ChangeRule(i, RuleChange::Offset(Offset::Start)),
+																1 => Msg::ChangeRule(i, RuleChange::Offset(Offset::Random)),
+																_ => Msg::ChangeRule(i, RuleChange::Offset(Offset::Fixed(Duration::ZERO))),
+															},
+															_ => Msg::None,
+														})
+													>
+														<option selected=rule.offset == Offset::Start>{ "Start" }</option>
+														<option selected=rule.offset == Offset::Random>{ "Random" }</option>
+														<option selected=matches!(rule.offset, Offset::Fixed(_))>{ "Fixed" }</option>
+													</select>
+													{if let Offset::Fixed(offset) = rule.offset {
+														html! {
+															<input type="number" class=input_number
+																disabled={ !rule.enabled || !rule.autoplay || rule.max_display_time.is_none() }
+																min=0
+																max=format!("{}", settings::MAX_DELAY_SECONDS.as_secs())
+																step="0.1"
+																value=format!("{}", offset.as_secs_f64())
+																onchange=self.link.callback(move |value| match parse_seconds(value) {
+																	Some(offset) => Msg::ChangeRule(i, RuleChange::Offset(Offset::Fixed(offset))),
+																	None => Msg::None,
+																})
+															/>
+														}
+													} else {
+														html!()
+													}}
+												</div>
+												<div class=field_class>
+													<label class="flex-1" disabled=!rule.enabled>{ "Volume" }</label>
+													<input type="range" class="w-1/3"
+														disabled=!rule.enabled
+														min=0
+														max=100
+														value=format!("{}", rule.volume)
+														onchange=self.link.callback(move |value| match value {
+															ChangeData::Value(v) => match v.parse::<u8>() {
+																Ok(volume) => Msg::ChangeRule(i, RuleChange::Volume(volume)),
+																Err(_) => Msg::None,
+															},
+															_ => Msg::None,
+														})
+													/>
+												</div>
+												<div class=field_class>
+													<label class="flex-1" disabled=!rule.enabled>{ "Loop" }</label>
+													<input type="checkbox" class=input_checkbox
+														checked=rule.looping
+														disabled=!rule.enabled
+														onchange=self.link.callback(move |_| Msg::ChangeRule(i, RuleChange::ToggleLoop))
+													/>
+												</div>
+											</>
+										}
+									} else {
+										html! {
+											<div class=field_class>
+												<label class="flex-1" disabled={ !rule.enabled || !rule.autoplay }>{ "Animation loops" }</label>
+												<input type="number" class=input_number
+													disabled={ !rule.enabled || !rule.autoplay }
+													min=0
+													max=100
+													placeholder="-"
+													value=rule.animation_loops.map(|l| format!("{}", l)).unwrap_or_default()
+													onchange=self.link.callback(move |value| match value {
+														// Zero or an empty field shows animations for the delay only.
+														ChangeData::Value(v) => match v.parse::<u32>() {
+															Ok(loops) if loops > 0 => Msg::ChangeRule(i, RuleChange::AnimationLoops(Some(loops))),
+															_ => Msg::ChangeRule(i, RuleChange::AnimationLoops(None)),
+														},
+														_ => Msg::None,
+													})
+												/>
+											</div>
+										}
+									}}
+								</fieldset>
+							}
+						})}
+						<fieldset class=fieldset_class>
+							<legend>{ "New rule" }</legend>
 							<div class=field_class>
-								<label class="flex-1"
-									disabled={ !self.settings.toggle_video || !self.settings.toggle_video_autoplay }>
-									{ "Delay" }
-								</label>
-								<input type="number" class=input_number
-									disabled={ !self.settings.toggle_video || !self.settings.toggle_video_autoplay }
-									min=0
-									max=format!("{}", app::MAX_DELAY_SECONDS.as_secs())
-									pattern="^\\d{1,4}$"
-									value=format!("{}", self.settings.config_video_autoplay_delay.as_secs())
-									onchange=&self.config_video_autoplay_delay_callback
+								<select class="bg-gray-800 outline-none mr-4" onchange=self.link.callback(|value| match value {
+									ChangeData::Select(select) => match select.selected_index() {
+										0 => Msg::NewRuleKind(Kind::Video),
+										1 => Msg::NewRuleKind(Kind::Audio),
+										_ => Msg::NewRuleKind(Kind::Image),
+									},
+									_ => Msg::None,
+								})>
+									{for Kind::ALL.iter().map(|kind| html! {
+										<option selected=*kind == self.new_rule_kind>{ kind.name() }</option>
+									})}
+								</select>
+								<input type="text" class="flex-1 bg-white bg-opacity-10 outline-none px-2 w-1/3"
+									placeholder=".gif or image/gif"
+									value=self.new_rule_pattern.clone()
+									oninput=self.link.callback(|e: InputData| Msg::NewRulePattern(e.value))
 								/>
+								<span class="cursor-pointer ml-4" onclick=self.link.callback(|_| Msg::AddRule)>
+									{ Icon::new_sized(IconKind::Plus, 32) }
+								</span>
 							</div>
 						</fieldset>
+						{if self.soundtrack_options.is_empty() {
+							html!()
+						} else {
+							let disabled = self.soundtrack.is_none();
+							html! {
+								<fieldset class=fieldset_class>
+									<legend>{ "Soundtrack" }</legend>
+									<div class=field_class>
+										<select class="flex-1 bg-gray-800 outline-none truncate" onchange=&self.select_soundtrack_callback>
+											<option value="" selected=disabled>{ "None" }</option>
+											{for self.soundtrack_options.iter().map(|option| html! {
+												<option value=option.clone() selected=self.soundtrack.as_ref() == Some(option)>{ option }</option>
+											})}
+										</select>
+									</div>
+									<div class=field_class>
+										<label class="flex-1" disabled=disabled>{ "Volume" }</label>
+										<input type="range" class="w-1/3" disabled=disabled
+											min=0
+											max=100
+											value=format!("{}", self.settings.soundtrack_volume)
+											onchange=&self.config_soundtrack_volume_callback
+										/>
+									</div>
+									<div class=field_class>
+										<label class="flex-1" disabled=disabled>{ "Fade" }</label>
+										<input type="number" class=input_number disabled=disabled
+											min=0
+											max=format!("{}", settings::MAX_DELAY_SECONDS.as_secs())
+											pattern="^\\d{1,4}$"
+											value=format!("{}", self.settings.soundtrack_fade.as_secs())
+											onchange=&self.config_soundtrack_fade_callback
+										/>
+									</div>
+									<div class=field_class>
+										<label class="flex-1" disabled=disabled>{ "Sync photos to track length" }</label>
+										<input type="checkbox" class=input_checkbox disabled=disabled
+											checked=self.settings.soundtrack_sync
+											onchange=&self.toggle_soundtrack_sync_callback
+										/>
+									</div>
+								</fieldset>
+							}
+						}}
 						<fieldset class=fieldset_class>
-							<legend>{ "Audio" }</legend>
+							<legend>{ "Page Up and Page Down" }</legend>
 							<div class=field_class>
-								<label class="flex-1">{ "Autoplay" }</label>
-								<input type="checkbox" class=input_checkbox
-									checked=self.settings.toggle_audio_autoplay
-									disabled=!self.settings.toggle_audio
-									onchange=&self.toggle_audio_autoplay_callback
+								<label class="flex-1" disabled=self.settings.page_by_folder>{ "Items per page" }</label>
+								<input type="number" class=input_number disabled=self.settings.page_by_folder
+									min=1
+									pattern="^\\d{1,6}$"
+									value=format!("{}", self.settings.page_size)
+									onchange=&self.config_page_size_callback
 								/>
 							</div>
 							<div class=field_class>
-								<label class="flex-1"
-									disabled={ !self.settings.toggle_audio || !self.settings.toggle_audio_autoplay }>
-									{ "Delay" }
-								</label>
-								<input type="number" class=input_number
-									disabled={ !self.settings.toggle_audio || !self.settings.toggle_audio_autoplay }
-									min=0
-									max=format!("{}", app::MAX_DELAY_SECONDS.as_secs())
-									pattern="^\\d{1,4}$"
-									value=format!("{}", self.settings.config_audio_autoplay_delay.as_secs())
-									onchange=&self.config_audio_autoplay_delay_callback
+								<label class="flex-1">{ "Move by folder" }</label>
+								<input type="checkbox" class=input_checkbox
+									checked=self.settings.page_by_folder
+									onchange=&self.toggle_page_by_folder_callback
 								/>
 							</div>
 						</fieldset>
 						<fieldset class=fieldset_class>
-							<legend>{ "Photos" }</legend>
+							<legend>{ "Thumbnails" }</legend>
 							<div class=field_class>
-								<label class="flex-1">{ "Autoplay" }</label>
-								<input type="checkbox" class=input_checkbox
-									checked=self.settings.toggle_image_autoplay
-									disabled=!self.settings.toggle_image
-									onchange=&self.toggle_image_autoplay_callback
+								<label class="flex-1">{ "Cache size (MB)" }</label>
+								<input type="number" class=input_number
+									min=0
+									pattern="^\\d{1,6}$"
+									value=format!("{}", self.settings.thumbnail_cache_size)
+									onchange=&self.config_thumbnail_cache_size_callback
 								/>
 							</div>
 							<div class=field_class>
-								<label class="flex-1"
-									disabled={ !self.settings.toggle_image || !self.settings.toggle_image_autoplay }>
-									{ "Delay" }
-								</label>
+								<label class="flex-1">{ "Video frame at (s)" }</label>
 								<input type="number" class=input_number
-									disabled={ !self.settings.toggle_image || !self.settings.toggle_image_autoplay }
-									min=1
-									max=format!("{}", app::MAX_DELAY_SECONDS.as_secs())
+									min=0
+									max=format!("{}", settings::MAX_DELAY_SECONDS.as_secs())
 									pattern="^\\d{1,4}$"
-									value=format!("{}", self.settings.config_image_autoplay_delay.as_secs())
-									onchange=&self.config_image_autoplay_delay_callback
+									value=format!("{}", self.settings.thumbnail_offset.as_secs())
+									onchange=&self.config_thumbnail_offset_callback
 								/>
 							</div>
 						</fieldset>
+						{match (&self.import_playlist_callback, &self.export_playlist_callback) {
+							(Some(import), Some(export)) => html! {
+								<fieldset class=fieldset_class>
+									<legend>{ "Playlist" }</legend>
+									<label for="playlist" class=format!("{} {}", field_class, "cursor-pointer")>
+										<span class="flex-1">{ "Import" }</span>
+										{ Icon::new_sized(IconKind::Upload, 32) }
+									</label>
+									<input id="playlist" type="file" value="" accept=".m3u,.m3u8,.pls,.xspf" class="hidden" onchange=import />
+									{for Format::ALL.iter().map(|&format| html! {
+										<div class=format!("{} {}", field_class, "cursor-pointer") onclick=export.reform(move |_| format)>
+											<span class="flex-1">{ format!("Export {}", format.name()) }</span>
+											{ Icon::new_sized(IconKind::Download, 32) }
+										</div>
+									})}
+								</fieldset>
+							},
+							_ => html!(),
+						}}
+						{match &self.copy_link_callback {
+							Some(copy_link) => html! {
+								<fieldset class=fieldset_class>
+									<legend>{ "Share" }</legend>
+									<div class=format!("{} {}", field_class, "cursor-pointer") onclick=copy_link.reform(|_| ())>
+										<span class="flex-1">{ "Copy link with these settings" }</span>
+										{ Icon::new_sized(IconKind::Link, 32) }
+									</div>
+								</fieldset>
+							},
+							None => html!(),
+						}}
 					</form>
 				</div>
 				<div class=&self.button_class onclick=self.link.callback(|_| Msg::ToggleVisible)>
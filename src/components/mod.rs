@@ -1,3 +1,15 @@
+pub mod about;
+pub mod button;
+pub mod duplicates;
+pub mod filmstrip;
+pub mod grid;
 pub mod media;
 pub mod menu;
-pub mod warning;
+pub mod notifications;
+pub mod playlists;
+pub mod rejects;
+pub mod renamer;
+pub mod scrubber;
+pub mod soundtrack;
+pub mod tags;
+pub mod thumbnail;
@@ -0,0 +1,237 @@
+use std::{collections::HashMap, time::Duration};
+
+use yew::{
+	html,
+	services::{timeout::TimeoutTask, TimeoutService},
+	Callback, Component, ComponentLink, Html, Properties, ShouldRender,
+};
+use yew_octicons::{Icon, IconKind};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Severity {
+	Info,
+	Warning,
+	Error,
+}
+
+impl Severity {
+	/// How long notifications stay up unless dismissed. Errors stay until dismissed.
+	fn lifetime(&self) -> Option<Duration> {
+		match self {
+			Severity::Info => Some(Duration::from_secs(4)),
+			Severity::Warning => Some(Duration::from_secs(8)),
+			Severity::Error => None,
+		}
+	}
+
+	fn class(&self) -> &'static str {
+		match self {
+			Severity::Info => "bg-blue-200 border-blue-400 text-blue-700",
+			Severity::Warning => "bg-yellow-200 border-yellow-400 text-yellow-600",
+			Severity::Error => "bg-red-200 border-red-400 text-red-700",
+		}
+	}
+
+	fn icon(&self) -> IconKind {
+		match self {
+			Severity::Info => IconKind::Info,
+			Severity::Warning => IconKind::Alert,
+			Severity::Error => IconKind::XCircle,
+		}
+	}
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Notification {
+	/// Label and handler of a button offered along with the message, e.g. to undo or retry.
+	pub action: Option<(String, Callback<()>)>,
+	/// Whether the notification left the stack and only shows in the history.
+	pub dismissed: bool,
+	pub id: usize,
+	pub message: String,
+	pub severity: Severity,
+}
+
+pub enum Msg {
+	Action(usize),
+	Expire(usize),
+	ToggleHistory,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub button_class: String,
+	#[prop_or_default]
+	pub class: String,
+	/// Every notification so far, oldest first.
+	pub notifications: Vec<Notification>,
+	pub onclear: Callback<()>,
+	pub ondismiss: Callback<usize>,
+}
+
+/// Stack of live notifications with a history panel of past ones.
+pub struct Notifications {
+	button_class: String,
+	class: String,
+	expiries: HashMap<usize, TimeoutTask>,
+	history_visible: bool,
+	link: ComponentLink<Self>,
+	notifications: Vec<Notification>,
+	on_clear: Callback<()>,
+	on_dismiss: Callback<usize>,
+}
+
+impl Notifications {
+	/// Starts the life time of new notifications and forgets about dismissed ones.
+	fn schedule(&mut self) {
+		let live: Vec<usize> = self
+			.notifications
+			.iter()
+			.filter(|n| !n.dismissed)
+			.map(|n| n.id)
+			.collect();
+		self.expiries.retain(|id, _| live.contains(id));
+		for notification in self.notifications.iter().filter(|n| !n.dismissed) {
+			if let (false, Some(lifetime)) = (
+				self.expiries.contains_key(&notification.id),
+				notification.severity.lifetime(),
+			) {
+				let id = notification.id;
+				self.expiries.insert(
+					id,
+					TimeoutService::spawn(lifetime, self.link.callback(move |_| Msg::Expire(id))),
+				);
+			}
+		}
+	}
+
+	/// Live notifications come with their action and a dismiss button, history entries without.
+	fn view_notification(&self, notification: &Notification, live: bool) -> Html {
+		let id = notification.id;
+		html! {
+			<div class=format!("border-2 rounded-lg flex place-items-center mb-2 {}", notification.severity.class())>
+				<div class="m-1">
+					{ Icon::new_sized(notification.severity.icon(), 32) }
+				</div>
+				<div class="flex-1 m-1">
+					{ &notification.message }
+				</div>
+				{if let (true, Some((label, _))) = (live, &notification.action) {
+					html! {
+						<button class="m-1 px-2 rounded border-2 border-current" onclick=self.link.callback(move |_| Msg::Action(id))>
+							{ label }
+						</button>
+					}
+				} else {
+					html!()
+				}}
+				{if live {
+					html! {
+						<div class="cursor-pointer m-1" onclick=self.on_dismiss.reform(move |_| id)>
+							{ Icon::new_sized(IconKind::X, 24) }
+						</div>
+					}
+				} else {
+					html!()
+				}}
+			</div>
+		}
+	}
+}
+
+impl Component for Notifications {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		let mut notifications = Self {
+			button_class: props.button_class,
+			class: props.class,
+			expiries: HashMap::new(),
+			history_visible: false,
+			link,
+			notifications: props.notifications,
+			on_clear: props.onclear,
+			on_dismiss: props.ondismiss,
+		};
+		notifications.schedule();
+		notifications
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.button_class = props.button_class;
+		self.class = props.class;
+		self.on_clear = props.onclear;
+		self.on_dismiss = props.ondismiss;
+		if self.notifications != props.notifications {
+			self.notifications = props.notifications;
+			self.schedule();
+		}
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Action(id) => {
+				if let Some((_, action)) = self
+					.notifications
+					.iter()
+					.find(|n| n.id == id)
+					.and_then(|n| n.action.as_ref())
+				{
+					action.emit(());
+				}
+				self.on_dismiss.emit(id);
+				false
+			}
+			Msg::Expire(id) => {
+				self.expiries.remove(&id);
+				self.on_dismiss.emit(id);
+				false
+			}
+			Msg::ToggleHistory => {
+				self.history_visible = !self.history_visible;
+				true
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		let panel_class = "absolute right-0 top-0 mr-44 lg:mr-28 mt-4 w-3/5 md:w-3/5 lg:w-1/3 bg-gray-800 bg-opacity-90 text-white rounded-lg p-4 flex flex-col text-3xl lg:text-xl select-none max-h-[50vh] overflow-y-auto";
+		html! {
+			<>
+				<div class=format!("flex flex-col text-3xl lg:text-xl {}", &self.class)>
+					{for self.notifications.iter().filter(|n| !n.dismissed).map(|n| self.view_notification(n, true))}
+				</div>
+				{if self.notifications.is_empty() {
+					html!()
+				} else {
+					html! {
+						<div class=&self.button_class onclick=self.link.callback(|_| Msg::ToggleHistory)>
+							{ Icon::new_sized(IconKind::Bell, 32) }
+						</div>
+					}
+				}}
+				{if self.history_visible && !self.notifications.is_empty() {
+					html! {
+						<div class=panel_class>
+							<div class="flex place-items-center mb-2">
+								<span class="flex-1">{ Icon::new_sized(IconKind::History, 32) }</span>
+								<div class="cursor-pointer ml-2" onclick=self.on_clear.reform(|_| ())>
+									{ Icon::new_sized(IconKind::Trash, 32) }
+								</div>
+								<div class="cursor-pointer ml-2" onclick=self.link.callback(|_| Msg::ToggleHistory)>
+									{ Icon::new_sized(IconKind::X, 32) }
+								</div>
+							</div>
+							{for self.notifications.iter().rev().map(|n| self.view_notification(n, false))}
+						</div>
+					}
+				} else {
+					html!()
+				}}
+			</>
+		}
+	}
+}
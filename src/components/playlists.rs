@@ -0,0 +1,207 @@
+use yew::{
+	html,
+	web_sys::{DragEvent, FocusEvent},
+	Callback, Component, ComponentLink, Html, InputData, Properties, ShouldRender,
+};
+use yew_octicons::{Icon, IconKind};
+
+pub enum Msg {
+	Create,
+	DragEnd,
+	DragStart(usize),
+	Drop(usize),
+	Input(String),
+	None,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	/// Name of the playlist new items are added to.
+	pub active: Option<String>,
+	#[prop_or_default]
+	pub class: String,
+	pub onclose: Callback<()>,
+	pub oncreate: Callback<String>,
+	pub ondelete: Callback<String>,
+	pub onmove: Callback<(String, usize, usize)>,
+	pub onplay: Callback<String>,
+	pub onremove: Callback<(String, usize)>,
+	pub onselect: Callback<String>,
+	/// Saved playlists as names and relative paths of their entries.
+	pub playlists: Vec<(String, Vec<String>)>,
+}
+
+pub struct PlaylistManager {
+	active: Option<String>,
+	class: String,
+	dragging: Option<usize>,
+	input: String,
+	link: ComponentLink<Self>,
+	on_close: Callback<()>,
+	on_create: Callback<String>,
+	on_delete: Callback<String>,
+	on_move: Callback<(String, usize, usize)>,
+	on_play: Callback<String>,
+	on_remove: Callback<(String, usize)>,
+	on_select: Callback<String>,
+	playlists: Vec<(String, Vec<String>)>,
+}
+
+impl Component for PlaylistManager {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		Self {
+			active: props.active,
+			class: props.class,
+			dragging: None,
+			input: String::new(),
+			link,
+			on_close: props.onclose,
+			on_create: props.oncreate,
+			on_delete: props.ondelete,
+			on_move: props.onmove,
+			on_play: props.onplay,
+			on_remove: props.onremove,
+			on_select: props.onselect,
+			playlists: props.playlists,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.active = props.active;
+		self.class = props.class;
+		self.on_close = props.onclose;
+		self.on_create = props.oncreate;
+		self.on_delete = props.ondelete;
+		self.on_move = props.onmove;
+		self.on_play = props.onplay;
+		self.on_remove = props.onremove;
+		self.on_select = props.onselect;
+		self.playlists = props.playlists;
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Create => {
+				let name = self.input.trim().to_string();
+				self.input.clear();
+				if !name.is_empty() {
+					self.on_create.emit(name);
+				}
+				true
+			}
+			Msg::DragEnd => {
+				self.dragging = None;
+				true
+			}
+			Msg::DragStart(index) => {
+				self.dragging = Some(index);
+				true
+			}
+			Msg::Drop(to) => {
+				if let (Some(from), Some(name)) = (self.dragging.take(), &self.active) {
+					if from != to {
+						self.on_move.emit((name.clone(), from, to));
+					}
+				}
+				true
+			}
+			Msg::Input(value) => {
+				self.input = value;
+				false
+			}
+			Msg::None => false,
+		}
+	}
+
+	fn view(&self) -> Html {
+		let panel_class = "bg-gray-800 bg-opacity-90 text-white rounded-lg p-4 flex flex-col text-3xl lg:text-xl select-none max-h-[50vh] overflow-y-auto";
+		let row_class = "flex place-items-center rounded px-2 py-1 bg-white bg-opacity-0 hover:bg-opacity-20 transition";
+		let entries = self
+			.playlists
+			.iter()
+			.find(|(name, _)| Some(name) == self.active.as_ref())
+			.map(|(_, entries)| entries.clone())
+			.unwrap_or_default();
+		html! {
+			<div class=format!("{} {}", panel_class, &self.class)>
+				<div class="flex place-items-center mb-2">
+					<span class="flex-1">{ Icon::new_sized(IconKind::ListOrdered, 32) }</span>
+					<div class="cursor-pointer" onclick=self.on_close.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				<form class="flex mb-2" onsubmit=self.link.callback(|e: FocusEvent| {
+					e.prevent_default();
+					Msg::Create
+				})>
+					<input type="text" placeholder="New playlist"
+						class="flex-1 bg-white bg-opacity-10 rounded px-2 outline-none"
+						value=self.input.clone()
+						oninput=self.link.callback(|e: InputData| Msg::Input(e.value))
+					/>
+					<button type="submit" class="ml-2">{ Icon::new_sized(IconKind::Plus, 32) }</button>
+				</form>
+				{for self.playlists.iter().map(|(name, entries)| {
+					let active = Some(name) == self.active.as_ref();
+					let (select, play, delete) = (name.clone(), name.clone(), name.clone());
+					html! {
+						<div class=format!("{} {}", row_class, if active { "bg-opacity-30" } else { "" })>
+							<span class="flex-1 cursor-pointer" onclick=self.on_select.reform(move |_| select.clone())>
+								{ format!("{} ({})", name, entries.len()) }
+							</span>
+							<div class="cursor-pointer ml-2" onclick=self.on_play.reform(move |_| play.clone())>
+								{ Icon::new_sized(IconKind::Play, 24) }
+							</div>
+							<div class="cursor-pointer ml-2" onclick=self.on_delete.reform(move |_| delete.clone())>
+								{ Icon::new_sized(IconKind::Trash, 24) }
+							</div>
+						</div>
+					}
+				})}
+				{if let Some(name) = &self.active {
+					html! {
+						<ol class="mt-2 border-t pt-2 text-2xl lg:text-base">
+							{for entries.iter().enumerate().map(|(i, path)| {
+								let name = name.clone();
+								html! {
+									<li draggable="true"
+										class=format!("{} {}", row_class, if self.dragging == Some(i) { "opacity-50" } else { "cursor-move" })
+										ondragstart=self.link.callback(move |e: DragEvent| {
+											// Firefox only starts dragging once some data is set.
+											if let Some(data) = e.data_transfer() {
+												let _ = data.set_data("text/plain", &i.to_string());
+											}
+											Msg::DragStart(i)
+										})
+										ondragend=self.link.callback(|_| Msg::DragEnd)
+										ondragover=self.link.callback(|e: DragEvent| {
+											// Accept drops on entries of the list.
+											e.prevent_default();
+											Msg::None
+										})
+										ondrop=self.link.callback(move |e: DragEvent| {
+											e.prevent_default();
+											Msg::Drop(i)
+										})
+									>
+										<span class="mr-2">{ Icon::new_sized(IconKind::Grabber, 16) }</span>
+										<span class="flex-1 truncate">{ path }</span>
+										<div class="cursor-pointer ml-2" onclick=self.on_remove.reform(move |_| (name.clone(), i))>
+											{ Icon::new_sized(IconKind::X, 16) }
+										</div>
+									</li>
+								}
+							})}
+						</ol>
+					}
+				} else {
+					html!()
+				}}
+			</div>
+		}
+	}
+}
@@ -0,0 +1,237 @@
+use std::{collections::HashSet, time::Duration};
+
+use omnivyou_core::cull::{self, REJECTS_FOLDER};
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+use yew_octicons::{Icon, IconKind};
+
+use crate::{
+	components::{
+		button::action_button,
+		thumbnail::{kind_icon, Thumbnail},
+	},
+	library::Item,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+	Delete,
+	Move,
+}
+
+pub enum Msg {
+	Apply,
+	Confirm(Option<Action>),
+	Keep,
+	SelectAll(bool),
+	Toggle(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Whether rejects are being moved or deleted.
+	pub busy: bool,
+	/// Top folders of the rejects, along with whether changes to them were allowed.
+	pub folders: Vec<(String, bool)>,
+	/// Number of files moved by each batch which can be undone, oldest first.
+	pub log: Vec<usize>,
+	pub onclose: Callback<()>,
+	pub ondelete: Callback<Vec<String>>,
+	pub ongrant: Callback<String>,
+	pub onkeep: Callback<Vec<String>>,
+	pub onmove: Callback<Vec<String>>,
+	pub onundo: Callback<()>,
+	pub rejects: Vec<Item>,
+	/// Position of the frames shown for videos.
+	pub thumbnail_offset: Duration,
+}
+
+/// Reviews the rejected items, to keep some after all, then move the others aside or delete them.
+pub struct Rejects {
+	confirm: Option<Action>,
+	/// Paths of the rejects left out of the selection, so that new rejects start out selected.
+	deselected: HashSet<String>,
+	link: ComponentLink<Self>,
+	props: Props,
+}
+
+impl Rejects {
+	fn selected(&self) -> Vec<String> {
+		self
+			.props
+			.rejects
+			.iter()
+			.filter(|item| !self.deselected.contains(&item.path))
+			.map(|item| item.path.clone())
+			.collect()
+	}
+
+	/// Whether changes to the folders of every selected reject were allowed.
+	fn allowed(&self, selected: &[String]) -> bool {
+		selected.iter().all(|path| match cull::split_top(path) {
+			Some((top, _)) => self.props.folders.iter().any(|(f, a)| f == top && *a),
+			// Files added on their own are skipped.
+			None => true,
+		})
+	}
+
+	fn view_actions(&self) -> Html {
+		let selected = self.selected();
+		let count = selected.len();
+		if let Some(action) = self.confirm {
+			let question = match action {
+				Action::Delete => format!("Delete {} files? This cannot be undone.", count),
+				Action::Move => format!(
+					"Move {} files into '{}' subfolders of their folders?",
+					count, REJECTS_FOLDER
+				),
+			};
+			return html! {
+				<>
+					<p class="mb-2">{ question }</p>
+					<div class="flex flex-wrap">
+						{ action_button(IconKind::Check, "Confirm", (!self.props.busy).then(|| self.link.callback(|_| Msg::Apply))) }
+						{ action_button(IconKind::X, "Cancel", Some(self.link.callback(|_| Msg::Confirm(None)))) }
+					</div>
+				</>
+			};
+		}
+		let enabled = count > 0 && !self.props.busy;
+		let allowed = enabled && self.allowed(&selected);
+		html! {
+			<>
+				{for self.props.folders.iter().filter(|(_, allowed)| !allowed).map(|(folder, _)| {
+					let folder = folder.clone();
+					html! {
+						<div class="flex flex-wrap place-items-center">
+							<span class="mr-4 mb-2">{ format!("Moving or deleting files needs access to '{}'.", folder) }</span>
+							{ action_button(IconKind::Unlock, "Allow changes", Some(self.props.ongrant.reform(move |_| folder.clone()))) }
+						</div>
+					}
+				})}
+				<div class="flex flex-wrap">
+					{ action_button(IconKind::Check, "Keep", enabled.then(|| self.link.callback(|_| Msg::Keep))) }
+					{ action_button(IconKind::FileSubmodule, &format!("Move to '{}'", REJECTS_FOLDER), allowed.then(|| self.link.callback(|_| Msg::Confirm(Some(Action::Move))))) }
+					{ action_button(IconKind::Trash, "Delete", allowed.then(|| self.link.callback(|_| Msg::Confirm(Some(Action::Delete))))) }
+				</div>
+				{for self.props.log.iter().enumerate().rev().map(|(i, moved)| {
+					let last = i + 1 == self.props.log.len();
+					html! {
+						<div class="flex flex-wrap place-items-center text-opacity-70 text-white">
+							<span class="mr-4 mb-2">{ format!("Moved {} files into '{}' folders", moved, REJECTS_FOLDER) }</span>
+							{if last {
+								action_button(IconKind::History, "Undo", (!self.props.busy).then(|| self.props.onundo.clone()))
+							} else {
+								html!()
+							}}
+						</div>
+					}
+				})}
+			</>
+		}
+	}
+}
+
+impl Component for Rejects {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		Self {
+			confirm: None,
+			deselected: HashSet::new(),
+			link,
+			props,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		// Changes are only confirmed for the selection they were asked for.
+		if props.rejects != self.props.rejects {
+			self.confirm = None;
+		}
+		self.props = props;
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Apply => {
+				let selected = self.selected();
+				match self.confirm.take() {
+					Some(Action::Delete) => self.props.ondelete.emit(selected),
+					Some(Action::Move) => self.props.onmove.emit(selected),
+					None => {}
+				}
+				true
+			}
+			Msg::Confirm(action) => {
+				self.confirm = action;
+				true
+			}
+			Msg::Keep => {
+				self.props.onkeep.emit(self.selected());
+				false
+			}
+			Msg::SelectAll(all) => {
+				self.deselected = if all {
+					HashSet::new()
+				} else {
+					self
+						.props
+						.rejects
+						.iter()
+						.map(|item| item.path.clone())
+						.collect()
+				};
+				true
+			}
+			Msg::Toggle(path) => {
+				if !self.deselected.remove(&path) {
+					self.deselected.insert(path);
+				}
+				self.confirm = None;
+				true
+			}
+		}
+	}
+
+	#[allow(clippy::unnecessary_operation)]
+	fn view(&self) -> Html {
+		let selected = self.selected().len();
+		html! {
+			<div class=format!("bg-gray-800 text-white flex flex-col text-3xl lg:text-xl select-none {}", &self.props.class)>
+				<div class="flex place-items-center p-4">
+					<div class="flex-1">{ format!("Rejects: {} of {} selected", selected, self.props.rejects.len()) }</div>
+					<div class="cursor-pointer ml-4 hover:underline" onclick=self.link.callback(|_| Msg::SelectAll(true))>{ "All" }</div>
+					<div class="cursor-pointer ml-4 hover:underline" onclick=self.link.callback(|_| Msg::SelectAll(false))>{ "None" }</div>
+					<div class="cursor-pointer ml-4" onclick=self.props.onclose.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				<div class="flex-1 overflow-y-auto grid grid-cols-3 md:grid-cols-5 lg:grid-cols-8 gap-2 p-4 content-start">
+					{for self.props.rejects.iter().map(|item| {
+						let selected = !self.deselected.contains(&item.path);
+						let path = item.path.clone();
+						html! {
+							<div class=format!("flex flex-col place-items-center p-2 rounded-lg border-2 cursor-pointer transition {}", if selected { "border-red-400" } else { "border-transparent opacity-50" })
+								title=item.path.clone()
+								onclick=self.link.callback(move |_| Msg::Toggle(path.clone()))>
+								<div class="h-32 lg:h-24 w-full flex place-content-center place-items-center">
+									<Thumbnail
+										fallback=html! { Icon::new_sized(kind_icon(&item.media_type()), 64) }
+										item=item.clone()
+										offset=self.props.thumbnail_offset
+									/>
+								</div>
+								<span class="w-full truncate text-center">{ item.file.name() }</span>
+							</div>
+						}
+					})}
+				</div>
+				<div class="p-4 border-t">{ self.view_actions() }</div>
+			</div>
+		}
+	}
+}
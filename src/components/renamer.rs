@@ -0,0 +1,310 @@
+use std::collections::{HashMap, HashSet};
+
+use log::warn;
+use omnivyou_core::{
+	cull::{self, Move},
+	rename::{self, Metadata, Status, Template, DEFAULT_TEMPLATE},
+};
+use yew::{html, Callback, Component, ComponentLink, Html, InputData, Properties, ShouldRender};
+use yew_octicons::{Icon, IconKind};
+use yewtil::future::LinkFuture;
+
+use crate::{components::button::action_button, library::Item};
+
+pub enum Msg {
+	Apply,
+	/// Metadata read for some of the keys asked for.
+	Loaded(Vec<String>, Vec<(String, Metadata)>),
+	Template(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Whether files are being renamed.
+	pub busy: bool,
+	/// Top folders of the items, along with whether changes to them were allowed.
+	pub folders: Vec<(String, bool)>,
+	/// Files to rename, numbered in this order.
+	pub items: Vec<Item>,
+	/// Number of files renamed by each batch which can be undone, oldest first.
+	pub log: Vec<usize>,
+	pub onapply: Callback<Vec<Move>>,
+	pub onclose: Callback<()>,
+	pub ongrant: Callback<String>,
+	pub onundo: Callback<()>,
+	/// Paths of every file in the library, which renamed files must not take.
+	pub paths: Vec<String>,
+}
+
+/// Planned rename of an item, which waits for its metadata.
+struct Row {
+	from: String,
+	to: Option<String>,
+	status: Option<Status>,
+}
+
+/// Renames the current files after a template filled in from their metadata, previewing the new
+/// names before applying them.
+pub struct Renamer {
+	link: ComponentLink<Self>,
+	/// Metadata of the items by key, which is read in the background.
+	metadata: HashMap<String, Metadata>,
+	/// Keys of the items whose metadata is being read.
+	pending: HashSet<String>,
+	props: Props,
+	template: String,
+	parsed: Result<Template, String>,
+}
+
+impl Renamer {
+	/// Reads the metadata of the items which have none yet.
+	fn load(&mut self) {
+		let items: Vec<Item> = self
+			.props
+			.items
+			.iter()
+			.filter(|item| {
+				let key = item.key();
+				!self.metadata.contains_key(&key) && !self.pending.contains(&key)
+			})
+			.cloned()
+			.collect();
+		if items.is_empty() {
+			return;
+		}
+		let keys: Vec<String> = items.iter().map(Item::key).collect();
+		self.pending.extend(keys.iter().cloned());
+		self.link.send_future(async move {
+			let mut loaded = Vec::new();
+			for item in items {
+				match item.metadata().await {
+					Ok(metadata) => loaded.push((item.key(), metadata)),
+					Err(err) => warn!("Failed to read metadata of '{}': {:?}", item.path, err),
+				}
+			}
+			Msg::Loaded(keys, loaded)
+		});
+	}
+
+	fn rows(&self) -> Vec<Row> {
+		let template = match &self.parsed {
+			Ok(template) => template,
+			Err(_) => return Vec::new(),
+		};
+		let mut rows: Vec<Row> = self
+			.props
+			.items
+			.iter()
+			.enumerate()
+			.map(|(i, item)| {
+				let name = item.path.rsplit('/').next().unwrap_or_default();
+				Row {
+					from: item.path.clone(),
+					to: self.metadata.get(&item.key()).map(|metadata| {
+						rename::renamed_path(&item.path, &template.render(name, metadata, i + 1))
+					}),
+					status: None,
+				}
+			})
+			.collect();
+		let renames: Vec<Move> = rows
+			.iter()
+			.filter_map(|row| {
+				Some(Move {
+					from: row.from.clone(),
+					to: row.to.clone()?,
+				})
+			})
+			.collect();
+		let mut statuses =
+			rename::check(&renames, self.props.paths.iter().map(String::as_str)).into_iter();
+		for row in rows.iter_mut().filter(|row| row.to.is_some()) {
+			row.status = statuses.next();
+		}
+		rows
+	}
+
+	/// Whether changes to the folder of a file were allowed.
+	fn allowed(&self, path: &str) -> bool {
+		cull::split_top(path).is_some_and(|(top, _)| {
+			self
+				.props
+				.folders
+				.iter()
+				.any(|(folder, allowed)| folder == top && *allowed)
+		})
+	}
+
+	fn view_status(&self, row: &Row) -> Html {
+		let (text, class) = match row.status {
+			None if !self.pending.is_empty() => ("Reading…", "text-opacity-50"),
+			None => ("Unreadable", "text-red-400"),
+			Some(Status::Unchanged) => ("Unchanged", "text-opacity-50"),
+			Some(Status::Ready) if !self.allowed(&row.from) => ("No access", "text-yellow-300"),
+			Some(Status::Ready) => ("Ready", "text-green-400"),
+			Some(Status::Invalid) => ("Invalid name", "text-red-400"),
+			Some(Status::Collision) => ("Collision", "text-red-400"),
+		};
+		html! {
+			<td class=format!("px-2 whitespace-nowrap text-white {}", class)>{ text }</td>
+		}
+	}
+
+	fn view_actions(&self, rows: &[Row]) -> Html {
+		let ready: Vec<&Row> = rows
+			.iter()
+			.filter(|row| row.status.is_some_and(Status::applies))
+			.collect();
+		// Renames are applied all together or not at all, for names not to clash half way.
+		let allowed = ready.iter().all(|row| self.allowed(&row.from));
+		let collisions = rows
+			.iter()
+			.filter(|row| matches!(row.status, Some(Status::Collision) | Some(Status::Invalid)))
+			.count();
+		let enabled = !ready.is_empty()
+			&& allowed
+			&& collisions == 0
+			&& self.pending.is_empty()
+			&& !self.props.busy;
+		html! {
+			<>
+				{if collisions > 0 {
+					html! {
+						<p class="mb-2 text-red-400">
+							{ format!("{} files cannot take their new names. Change the template to rename any.", collisions) }
+						</p>
+					}
+				} else {
+					html!()
+				}}
+				{for self.props.folders.iter().filter(|(_, allowed)| !allowed).map(|(folder, _)| {
+					let folder = folder.clone();
+					html! {
+						<div class="flex flex-wrap place-items-center">
+							<span class="mr-4 mb-2">{ format!("Renaming files needs access to '{}'.", folder) }</span>
+							{ action_button(IconKind::Unlock, "Allow changes", Some(self.props.ongrant.reform(move |_| folder.clone()))) }
+						</div>
+					}
+				})}
+				<div class="flex flex-wrap">
+					{ action_button(IconKind::Pencil, &format!("Rename {} files", ready.len()), enabled.then(|| self.link.callback(|_| Msg::Apply))) }
+				</div>
+				{for self.props.log.iter().enumerate().rev().map(|(i, renamed)| {
+					let last = i + 1 == self.props.log.len();
+					html! {
+						<div class="flex flex-wrap place-items-center text-opacity-70 text-white">
+							<span class="mr-4 mb-2">{ format!("Renamed {} files", renamed) }</span>
+							{if last {
+								action_button(IconKind::History, "Undo", (!self.props.busy).then(|| self.props.onundo.clone()))
+							} else {
+								html!()
+							}}
+						</div>
+					}
+				})}
+			</>
+		}
+	}
+}
+
+impl Component for Renamer {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		let mut renamer = Self {
+			link,
+			metadata: HashMap::new(),
+			pending: HashSet::new(),
+			props,
+			template: String::from(DEFAULT_TEMPLATE),
+			parsed: Template::parse(DEFAULT_TEMPLATE),
+		};
+		renamer.load();
+		renamer
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		if self.props == props {
+			return false;
+		}
+		self.props = props;
+		self.load();
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Apply => {
+				let renames: Vec<Move> = self
+					.rows()
+					.into_iter()
+					.filter(|row| row.status.is_some_and(Status::applies))
+					.filter_map(|row| {
+						Some(Move {
+							from: row.from,
+							to: row.to?,
+						})
+					})
+					.collect();
+				self.props.onapply.emit(renames);
+				false
+			}
+			Msg::Loaded(keys, loaded) => {
+				for key in &keys {
+					self.pending.remove(key);
+				}
+				self.metadata.extend(loaded);
+				true
+			}
+			Msg::Template(template) => {
+				self.parsed = Template::parse(&template);
+				self.template = template;
+				true
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		let rows = self.rows();
+		html! {
+			<div class=format!("bg-gray-800 text-white flex flex-col text-3xl lg:text-xl select-none {}", &self.props.class)>
+				<div class="flex place-items-center p-4">
+					<div class="flex-1">{ format!("Rename {} files", self.props.items.len()) }</div>
+					<div class="cursor-pointer ml-4" onclick=self.props.onclose.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				<div class="px-4">
+					<input type="text" class="w-full px-2 py-1 bg-gray-700 rounded outline-none font-mono"
+						value=self.template.clone()
+						oninput=self.link.callback(|e: InputData| Msg::Template(e.value))
+					/>
+					<p class="mt-1 text-white text-opacity-50">
+						{match &self.parsed {
+							Ok(_) => String::from("Fields: {date:%Y%m%d}, {seq:04}, {name}, {ext} and {camera}"),
+							Err(err) => err.clone(),
+						}}
+					</p>
+				</div>
+				<div class="flex-1 overflow-y-auto p-4">
+					<table class="w-full">
+						<tbody>
+							{for rows.iter().enumerate().map(|(i, row)| html! {
+								<tr>
+									<td class="pr-2 text-right text-white text-opacity-50">{ i + 1 }</td>
+									<td class="px-2 truncate max-w-0 w-1/2" title=row.from.clone()>{ row.from.rsplit('/').next().unwrap_or_default() }</td>
+									<td class="px-2 truncate max-w-0 w-1/2 font-mono">{ row.to.as_deref().and_then(|to| to.rsplit('/').next()).unwrap_or_default() }</td>
+									{ self.view_status(row) }
+								</tr>
+							})}
+						</tbody>
+					</table>
+				</div>
+				<div class="p-4 border-t">{ self.view_actions(&rows) }</div>
+			</div>
+		}
+	}
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use yew::{
+	html,
+	web_sys::{HtmlInputElement, MouseEvent},
+	Callback, ChangeData, Component, ComponentLink, Html, InputData, NodeRef, Properties,
+	ShouldRender,
+};
+use yew_octicons::Icon;
+
+use crate::{
+	components::thumbnail::{kind_icon, Thumbnail},
+	library::Item,
+};
+
+pub enum Msg {
+	Hover(MouseEvent),
+	Jump(Option<usize>),
+	Leave,
+	Scrub(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Position of the current item in the queue.
+	pub current: usize,
+	/// Number of items in the queue.
+	pub len: usize,
+	pub onjump: Callback<usize>,
+	/// Called with the position hovered or dragged to, and with nothing once the slider is left.
+	pub onpreview: Callback<Option<usize>>,
+	/// Item at the position passed to `onpreview`.
+	#[prop_or_default]
+	pub preview: Option<Item>,
+	/// Position of the frames shown for videos.
+	pub thumbnail_offset: Duration,
+}
+
+/// Slider over the whole queue, previewing the item under the pointer, along with a field to jump
+/// to an item by number.
+pub struct Scrubber {
+	class: String,
+	current: usize,
+	/// Position hovered or dragged to.
+	hovered: Option<usize>,
+	len: usize,
+	link: ComponentLink<Self>,
+	on_jump: Callback<usize>,
+	on_preview: Callback<Option<usize>>,
+	preview: Option<Item>,
+	slider: NodeRef,
+	thumbnail_offset: Duration,
+}
+
+impl Scrubber {
+	fn last(&self) -> usize {
+		self.len.saturating_sub(1)
+	}
+
+	fn hover(&mut self, index: Option<usize>) -> ShouldRender {
+		let index = index.map(|i| i.min(self.last()));
+		if self.hovered == index {
+			return false;
+		}
+		self.hovered = index;
+		self.on_preview.emit(index);
+		true
+	}
+
+	#[allow(clippy::unnecessary_operation)]
+	fn view_preview(&self) -> Html {
+		let (index, item) = match (self.hovered, &self.preview) {
+			(Some(index), Some(item)) => (index, item),
+			_ => return html!(),
+		};
+		let left = index as f64 / self.last().max(1) as f64 * 100.0;
+		html! {
+			<div class="absolute bottom-0 mb-8 transform -translate-x-1/2 flex flex-col place-items-center pointer-events-none" style=format!("left: {}%", left)>
+				<div class="h-32 w-32 lg:h-24 lg:w-24 p-1 rounded-lg bg-gray-800 flex place-content-center place-items-center">
+					<Thumbnail
+						fallback=html! { Icon::new_sized(kind_icon(&item.media_type()), 32) }
+						item=item.clone()
+						offset=self.thumbnail_offset
+					/>
+				</div>
+				<span class="mt-1 px-2 rounded bg-gray-800">{ index + 1 }</span>
+			</div>
+		}
+	}
+}
+
+impl Component for Scrubber {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		Self {
+			class: props.class,
+			current: props.current,
+			hovered: None,
+			len: props.len,
+			link,
+			on_jump: props.onjump,
+			on_preview: props.onpreview,
+			preview: props.preview,
+			slider: NodeRef::default(),
+			thumbnail_offset: props.thumbnail_offset,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.class = props.class;
+		self.current = props.current;
+		self.len = props.len;
+		self.on_jump = props.onjump;
+		self.on_preview = props.onpreview;
+		self.preview = props.preview;
+		self.thumbnail_offset = props.thumbnail_offset;
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Hover(e) => {
+				let width = match self.slider.cast::<HtmlInputElement>() {
+					Some(slider) if slider.client_width() > 0 => slider.client_width(),
+					_ => return false,
+				};
+				let fraction = (e.offset_x() as f64 / width as f64).clamp(0.0, 1.0);
+				self.hover(Some((fraction * self.last() as f64).round() as usize))
+			}
+			Msg::Jump(index) => {
+				if let Some(index) = index {
+					self.on_jump.emit(index.min(self.last()));
+				}
+				false
+			}
+			Msg::Leave => self.hover(None),
+			Msg::Scrub(value) => self.hover(value.parse().ok()),
+		}
+	}
+
+	fn view(&self) -> Html {
+		html! {
+			<div class=format!("flex place-items-center px-4 select-none {}", &self.class)>
+				<div class="relative flex-1 flex place-items-center">
+					{ self.view_preview() }
+					<input ref=self.slider.clone() type="range" class="w-full cursor-pointer"
+						min=0
+						max=format!("{}", self.last())
+						value=format!("{}", self.current)
+						oninput=self.link.callback(|e: InputData| Msg::Scrub(e.value))
+						onchange=self.link.callback(|value| match value {
+							ChangeData::Value(value) => Msg::Jump(value.parse().ok()),
+							_ => Msg::Jump(None),
+						})
+						onmousemove=self.link.callback(Msg::Hover)
+						onmouseleave=self.link.callback(|_| Msg::Leave)
+					/>
+				</div>
+				<input type="number" class="w-24 lg:w-20 ml-4 px-1 bg-gray-800 rounded outline-none text-right"
+					title="Jump to item"
+					min=1
+					max=format!("{}", self.len)
+					value=format!("{}", self.current + 1)
+					onchange=self.link.callback(|value| match value {
+						// Items are numbered from one.
+						ChangeData::Value(value) => Msg::Jump(value.trim().parse::<usize>().ok().map(|n| n.saturating_sub(1))),
+						_ => Msg::Jump(None),
+					})
+				/>
+				<span class="ml-2 whitespace-nowrap">{ format!("/ {}", self.len) }</span>
+			</div>
+		}
+	}
+}
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use js_sys::JsString;
+use log::warn;
+use wasm_bindgen::JsCast;
+use yew::{
+	html,
+	services::{interval::IntervalTask, IntervalService},
+	web_sys::{File, HtmlMediaElement, Url},
+	Callback, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender,
+};
+
+const FADE_STEP: Duration = Duration::from_millis(50);
+
+pub enum Msg {
+	Ended,
+	FadeStep,
+	LoadedMetadata,
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	/// Whether the soundtrack should currently be audible.
+	pub active: bool,
+	pub fade: Duration,
+	pub ondurationchange: Callback<f64>,
+	pub tracks: Vec<File>,
+	/// Volume in percent.
+	pub volume: u8,
+}
+
+/// Background audio which loops through its tracks independently of the viewed item.
+pub struct Soundtrack {
+	active: bool,
+	fade: Duration,
+	fade_task: Option<IntervalTask>,
+	/// Fade multiplier applied on top of the volume, between 0 and 1.
+	gain: f64,
+	index: usize,
+	link: ComponentLink<Self>,
+	node_ref: NodeRef,
+	on_duration_change: Callback<f64>,
+	src: Option<String>,
+	tracks: Vec<File>,
+	volume: u8,
+}
+
+impl Soundtrack {
+	fn audio(&self) -> Option<HtmlMediaElement> {
+		self.node_ref.cast::<HtmlMediaElement>()
+	}
+
+	fn apply_volume(&self) {
+		if let Some(audio) = self.audio() {
+			audio.set_volume(self.gain * self.volume as f64 / 100.0);
+		}
+	}
+
+	fn load(&mut self) {
+		self.revoke_src();
+		self.src = self
+			.tracks
+			.get(self.index)
+			.and_then(|file| Url::create_object_url_with_blob(file).ok());
+	}
+
+	fn play(&self) {
+		if let Some(audio) = self.audio() {
+			if let Err(err) = audio.play() {
+				warn!("Failed to play soundtrack: {:?}", err);
+			}
+		}
+	}
+
+	fn revoke_src(&self) {
+		if let Some(src) = &self.src {
+			if let Err(err) = Url::revoke_object_url(src) {
+				if let Some(err_str) = err.dyn_ref::<JsString>() {
+					warn!("{}", err_str);
+				} else {
+					warn!("Failed to revoke object url '{}'", src);
+				}
+			}
+		}
+	}
+
+	fn start_fade(&mut self) {
+		if self.active {
+			self.play();
+		}
+		self.fade_task = Some(IntervalService::spawn(
+			FADE_STEP,
+			self.link.callback(|_| Msg::FadeStep),
+		));
+	}
+}
+
+impl Component for Soundtrack {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		let mut soundtrack = Self {
+			active: props.active,
+			fade: props.fade,
+			fade_task: None,
+			gain: 0.0,
+			index: 0,
+			link,
+			node_ref: NodeRef::default(),
+			on_duration_change: props.ondurationchange,
+			src: None,
+			tracks: props.tracks,
+			volume: props.volume,
+		};
+		soundtrack.load();
+		soundtrack
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		let mut should_render = false;
+		self.fade = props.fade;
+		self.on_duration_change = props.ondurationchange;
+		if self.tracks != props.tracks {
+			should_render = true;
+			self.tracks = props.tracks;
+			self.index = 0;
+			self.gain = 0.0;
+			self.load();
+		}
+		if self.volume != props.volume {
+			self.volume = props.volume;
+			self.apply_volume();
+		}
+		if self.active != props.active {
+			self.active = props.active;
+			self.start_fade();
+		}
+		should_render
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Ended => {
+				if !self.tracks.is_empty() {
+					self.index = (self.index + 1) % self.tracks.len();
+					self.load();
+					return true;
+				}
+				false
+			}
+			Msg::FadeStep => {
+				let step = if self.fade.is_zero() {
+					1.0
+				} else {
+					FADE_STEP.as_secs_f64() / self.fade.as_secs_f64()
+				};
+				let target = if self.active { 1.0 } else { 0.0 };
+				self.gain = if self.gain < target {
+					(self.gain + step).min(target)
+				} else {
+					(self.gain - step).max(target)
+				};
+				self.apply_volume();
+				if (self.gain - target).abs() < f64::EPSILON {
+					self.fade_task = None;
+					if !self.active {
+						if let Some(audio) = self.audio() {
+							let _ = audio.pause();
+						}
+					}
+				}
+				false
+			}
+			Msg::LoadedMetadata => {
+				self.apply_volume();
+				if self.active {
+					self.start_fade();
+				}
+				if let Some(audio) = self.audio() {
+					self.on_duration_change.emit(audio.duration());
+				}
+				false
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		match &self.src {
+			Some(src) => html! {
+				<audio ref=self.node_ref.clone() class="hidden" src=src.clone()
+					onended=self.link.callback(|_| Msg::Ended)
+					onloadedmetadata=self.link.callback(|_| Msg::LoadedMetadata)
+				/>
+			},
+			None => html!(),
+		}
+	}
+
+	fn destroy(&mut self) {
+		self.revoke_src();
+	}
+}
@@ -0,0 +1,185 @@
+use yew::{
+	html, web_sys::FocusEvent, Callback, Component, ComponentLink, Html, InputData, Properties,
+	ShouldRender,
+};
+use yew_octicons::{Icon, IconKind};
+
+use omnivyou_core::library::TagMatch;
+
+pub enum Msg {
+	Input(String),
+	Play,
+	Remove(String),
+	Submit,
+	ToggleMatch,
+	ToggleSelected(String),
+}
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	#[prop_or_default]
+	pub class: String,
+	/// Every tag known to the tag store, used for autocompletion and playback.
+	pub known: Vec<String>,
+	pub onadd: Callback<String>,
+	pub onclose: Callback<()>,
+	pub onplay: Callback<(Vec<String>, TagMatch)>,
+	pub onremove: Callback<String>,
+	/// Tags of the item currently being viewed.
+	pub tags: Vec<String>,
+}
+
+pub struct TagEditor {
+	class: String,
+	input: String,
+	known: Vec<String>,
+	link: ComponentLink<Self>,
+	on_add: Callback<String>,
+	on_close: Callback<()>,
+	on_play: Callback<(Vec<String>, TagMatch)>,
+	on_remove: Callback<String>,
+	selected: Vec<String>,
+	tag_match: TagMatch,
+	tags: Vec<String>,
+}
+
+impl Component for TagEditor {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		Self {
+			class: props.class,
+			input: String::new(),
+			known: props.known,
+			link,
+			on_add: props.onadd,
+			on_close: props.onclose,
+			on_play: props.onplay,
+			on_remove: props.onremove,
+			selected: Vec::new(),
+			tag_match: TagMatch::Any,
+			tags: props.tags,
+		}
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		self.class = props.class;
+		self.on_add = props.onadd;
+		self.on_close = props.onclose;
+		self.on_play = props.onplay;
+		self.on_remove = props.onremove;
+		self.known = props.known;
+		// Forget selected tags which no longer exist anywhere.
+		let known = &self.known;
+		self.selected.retain(|t| known.contains(t));
+		self.tags = props.tags;
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Input(value) => {
+				self.input = value;
+				false
+			}
+			Msg::Play => {
+				self.on_play.emit((self.selected.clone(), self.tag_match));
+				false
+			}
+			Msg::Remove(tag) => {
+				self.on_remove.emit(tag);
+				false
+			}
+			Msg::Submit => {
+				let tag = self.input.trim().to_string();
+				self.input.clear();
+				if !tag.is_empty() {
+					self.on_add.emit(tag);
+				}
+				true
+			}
+			Msg::ToggleMatch => {
+				self.tag_match = match self.tag_match {
+					TagMatch::All => TagMatch::Any,
+					TagMatch::Any => TagMatch::All,
+				};
+				true
+			}
+			Msg::ToggleSelected(tag) => {
+				if let Some(position) = self.selected.iter().position(|t| *t == tag) {
+					self.selected.remove(position);
+				} else {
+					self.selected.push(tag);
+				}
+				true
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		let panel_class = "bg-gray-800 bg-opacity-90 text-white rounded-lg p-4 flex flex-col text-3xl lg:text-xl select-none";
+		let chip_class = "rounded-full border-2 px-3 py-1 m-1 flex place-items-center cursor-pointer transition duration-200";
+		html! {
+			<div class=format!("{} {}", panel_class, &self.class)>
+				<div class="flex place-items-center mb-2">
+					<span class="flex-1">{ Icon::new_sized(IconKind::Tag, 32) }</span>
+					<div class="cursor-pointer" onclick=self.on_close.reform(|_| ())>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</div>
+				</div>
+				<div class="flex flex-wrap">
+					{for self.tags.iter().map(|tag| {
+						let t = tag.clone();
+						html! {
+							<div class=format!("{} {}", chip_class, "bg-white text-black")
+								onclick=self.link.callback(move |_| Msg::Remove(t.clone()))>
+								{ tag }
+								<span class="ml-1">{ Icon::new_sized(IconKind::X, 16) }</span>
+							</div>
+						}
+					})}
+				</div>
+				<form class="flex my-2" onsubmit=self.link.callback(|e: FocusEvent| {
+					e.prevent_default();
+					Msg::Submit
+				})>
+					<input type="text" list="known-tags" placeholder="Add a tag"
+						class="flex-1 bg-white bg-opacity-10 rounded px-2 outline-none"
+						value=self.input.clone()
+						oninput=self.link.callback(|e: InputData| Msg::Input(e.value))
+					/>
+					<datalist id="known-tags">
+						{for self.known.iter().filter(|t| !self.tags.contains(t)).map(|tag| html! {
+							<option value=tag.clone() />
+						})}
+					</datalist>
+				</form>
+				<div class="flex place-items-center mt-2 border-t pt-2">
+					<span class="flex-1">{ "Play tags" }</span>
+					<div class=format!("{} {}", chip_class, "text-base") onclick=self.link.callback(|_| Msg::ToggleMatch)>
+						{ match self.tag_match {
+							TagMatch::All => "All of",
+							TagMatch::Any => "Any of",
+						} }
+					</div>
+					<div class="cursor-pointer ml-2" onclick=self.link.callback(|_| Msg::Play)>
+						{ Icon::new_sized(IconKind::Play, 32) }
+					</div>
+				</div>
+				<div class="flex flex-wrap">
+					{for self.known.iter().map(|tag| {
+						let selected = self.selected.contains(tag);
+						let t = tag.clone();
+						html! {
+							<div class=format!("{} {}", chip_class, if selected { "bg-white text-black" } else { "bg-opacity-0" })
+								onclick=self.link.callback(move |_| Msg::ToggleSelected(t.clone()))>
+								{ tag }
+							</div>
+						}
+					})}
+				</div>
+			</div>
+		}
+	}
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use log::warn;
+use omnivyou_core::media::Type;
+use yew::{
+	agent::{Bridge, Bridged},
+	html,
+	web_sys::Url,
+	Component, ComponentLink, Html, Properties, ShouldRender,
+};
+use yew_octicons::IconKind;
+
+use crate::{
+	library::Item,
+	thumbnail,
+	thumbnailer::{Request, Response, Thumbnailer},
+};
+
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	/// Shown until the thumbnail is ready, and for items without any picture to show.
+	pub fallback: Html,
+	pub item: Item,
+	/// Position of the frames shown for videos.
+	pub offset: Duration,
+}
+
+pub enum Msg {
+	Ready(Response),
+}
+
+/// Icon standing for items of a media type which have no thumbnail.
+pub fn kind_icon(media_type: &Type) -> IconKind {
+	match media_type {
+		Type::Audio(_) => IconKind::Unmute,
+		Type::Image(_) => IconKind::Image,
+		Type::Video(_) => IconKind::DeviceCameraVideo,
+		Type::Invalid(_) => IconKind::File,
+	}
+}
+
+/// Downscaled picture of an item.
+pub struct Thumbnail {
+	props: Props,
+	src: Option<String>,
+	thumbnailer: Box<dyn Bridge<Thumbnailer>>,
+}
+
+impl Thumbnail {
+	fn request(&mut self) {
+		self.thumbnailer.send(Request::Thumbnail {
+			item: self.props.item.clone(),
+			offset: self.props.offset,
+		});
+	}
+
+	fn revoke_src(&mut self) {
+		if let Some(src) = self.src.take() {
+			if Url::revoke_object_url(&src).is_err() {
+				warn!("Failed to revoke object url '{}'", src);
+			}
+		}
+	}
+}
+
+impl Component for Thumbnail {
+	type Message = Msg;
+	type Properties = Props;
+
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+		let mut thumbnail = Self {
+			props,
+			src: None,
+			thumbnailer: Thumbnailer::bridge(link.callback(Msg::Ready)),
+		};
+		thumbnail.request();
+		thumbnail
+	}
+
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		if self.props == props {
+			return false;
+		}
+		let key = thumbnail::key(&props.item, props.offset);
+		let changed = key != thumbnail::key(&self.props.item, self.props.offset);
+		self.props = props;
+		if changed {
+			self.revoke_src();
+			self.request();
+		}
+		true
+	}
+
+	fn update(&mut self, msg: Self::Message) -> ShouldRender {
+		match msg {
+			Msg::Ready(Response::Ready { key, blob }) => {
+				if key != thumbnail::key(&self.props.item, self.props.offset) {
+					return false;
+				}
+				self.revoke_src();
+				self.src = blob.and_then(|blob| Url::create_object_url_with_blob(&blob).ok());
+				true
+			}
+		}
+	}
+
+	fn view(&self) -> Html {
+		match &self.src {
+			Some(src) => html! {
+				<img class="max-h-full max-w-full object-contain rounded" src=src.clone() />
+			},
+			None => self.props.fallback.clone(),
+		}
+	}
+
+	fn destroy(&mut self) {
+		self.revoke_src();
+	}
+}
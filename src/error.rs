@@ -0,0 +1,93 @@
+use std::{
+	error,
+	fmt::{self, Display, Formatter},
+	panic,
+};
+
+use log::error;
+use wasm_bindgen::{JsCast, JsValue};
+use yew::web_sys::window;
+
+/// Errors surfaced to the user instead of panicking.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+	/// A browser API call failed.
+	Js(JsValue),
+	/// A file could not be prepared for display.
+	Load { name: String, reason: String },
+}
+
+impl Error {
+	pub fn load(name: &str, err: impl Display) -> Self {
+		Self::Load {
+			name: String::from(name),
+			reason: err.to_string(),
+		}
+	}
+}
+
+/// Readable description of a value thrown by JavaScript.
+fn describe(value: &JsValue) -> String {
+	if let Some(err) = value.dyn_ref::<js_sys::Error>() {
+		String::from(err.message())
+	} else if let Some(s) = value.as_string() {
+		s
+	} else {
+		format!("{:?}", value)
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		match self {
+			Error::Js(value) => write!(f, "{}", describe(value)),
+			Error::Load { name, reason } => write!(f, "Failed to load '{}': {}", name, reason),
+		}
+	}
+}
+
+impl error::Error for Error {}
+
+impl From<JsValue> for Error {
+	fn from(value: JsValue) -> Self {
+		Self::Js(value)
+	}
+}
+
+/// Replaces the page with a recovery screen once the app panicked, since yew cannot continue.
+fn render_recovery(message: &str) -> Result<(), JsValue> {
+	let document = window()
+		.and_then(|w| w.document())
+		.ok_or_else(|| JsValue::from_str("No document available"))?;
+	let body = document
+		.body()
+		.ok_or_else(|| JsValue::from_str("No body available"))?;
+	let screen = document.create_element("div")?;
+	screen.set_class_name("bg-black text-white absolute inset-0 flex flex-col place-content-center place-items-center text-3xl lg:text-xl p-8");
+	let title = document.create_element("h1")?;
+	title.set_class_name("text-5xl lg:text-3xl mb-4");
+	title.set_text_content(Some("Something went wrong"));
+	let details = document.create_element("pre")?;
+	details.set_class_name("whitespace-pre-wrap text-red-300 mb-8 max-w-full overflow-auto");
+	details.set_text_content(Some(message));
+	let reload = document.create_element("a")?;
+	reload.set_class_name("px-4 py-2 rounded-full border-2 cursor-pointer");
+	reload.set_attribute("href", ".")?;
+	reload.set_text_content(Some("Reload"));
+	screen.append_child(&title)?;
+	screen.append_child(&details)?;
+	screen.append_child(&reload)?;
+	body.set_inner_html("");
+	body.append_child(&screen)?;
+	Ok(())
+}
+
+/// Logs panics and shows the recovery screen.
+pub fn set_panic_hook() {
+	panic::set_hook(Box::new(|info| {
+		error!("{}", info);
+		if let Err(err) = render_recovery(&info.to_string()) {
+			error!("Failed to render recovery screen: {}", describe(&err));
+		}
+	}));
+}
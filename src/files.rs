@@ -0,0 +1,240 @@
+//! Changes to files on disk through the File System Access API, which is only available in some
+//! browsers.
+
+use std::collections::HashMap;
+
+use js_sys::{Function, Object, Promise, Reflect};
+use omnivyou_core::{
+	cull::{self, Move},
+	rename,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::web_sys::{
+	window, File, FileSystemDirectoryHandle, FileSystemFileHandle, FileSystemGetDirectoryOptions,
+	FileSystemGetFileOptions, FileSystemWritableFileStream,
+};
+
+use crate::error::Error;
+
+/// Asks the user for a folder, with permission to change its files.
+pub async fn pick_folder() -> Result<FileSystemDirectoryHandle, Error> {
+	let window = window().ok_or_else(|| JsValue::from_str("No window available"))?;
+	// The picker is not part of the stable web-sys API.
+	let picker: Function = Reflect::get(&window, &JsValue::from_str("showDirectoryPicker"))?
+		.dyn_into()
+		.map_err(|_| JsValue::from_str("This browser cannot change files"))?;
+	let options = Object::new();
+	Reflect::set(
+		&options,
+		&JsValue::from_str("mode"),
+		&JsValue::from_str("readwrite"),
+	)?;
+	let picked = picker.call1(&window, &options)?.unchecked_into::<Promise>();
+	Ok(JsFuture::from(picked).await?.unchecked_into())
+}
+
+/// Splits a path into its folders and file name.
+fn split(path: &str) -> (Vec<&str>, &str) {
+	let mut segments: Vec<&str> = path.split('/').collect();
+	let name = segments.pop().unwrap_or_default();
+	(segments, name)
+}
+
+async fn folder(
+	root: &FileSystemDirectoryHandle,
+	folders: &[&str],
+	create: bool,
+) -> Result<FileSystemDirectoryHandle, Error> {
+	let mut folder = root.clone();
+	for name in folders {
+		let options = FileSystemGetDirectoryOptions::new();
+		options.set_create(create);
+		let handle = JsFuture::from(folder.get_directory_handle_with_options(name, &options)).await?;
+		folder = handle.unchecked_into();
+	}
+	Ok(folder)
+}
+
+async fn file_handle(
+	folder: &FileSystemDirectoryHandle,
+	name: &str,
+	create: bool,
+) -> Result<FileSystemFileHandle, Error> {
+	let options = FileSystemGetFileOptions::new();
+	options.set_create(create);
+	let handle = JsFuture::from(folder.get_file_handle_with_options(name, &options)).await?;
+	Ok(handle.unchecked_into())
+}
+
+/// Contents of a file, provided it still is the one which was added going by its size and time of
+/// change, so that a folder of the same name or a file changed since is left alone.
+async fn unchanged_file(
+	folder: &FileSystemDirectoryHandle,
+	name: &str,
+	expected: &File,
+) -> Result<File, Error> {
+	let handle = file_handle(folder, name, false).await?;
+	let file: File = JsFuture::from(handle.get_file()).await?.unchecked_into();
+	if file.size() != expected.size() || file.last_modified() != expected.last_modified() {
+		return Err(JsValue::from_str("The file on disk differs from the one added").into());
+	}
+	Ok(file)
+}
+
+/// Checks that a file on disk still is the one which was added.
+pub async fn check_file(
+	root: &FileSystemDirectoryHandle,
+	path: &str,
+	expected: &File,
+) -> Result<(), Error> {
+	let (folders, name) = split(path);
+	let folder = folder(root, &folders, false).await?;
+	unchanged_file(&folder, name, expected).await?;
+	Ok(())
+}
+
+/// Moves a file within a folder and returns the path and file it ended up at. A taken path is
+/// numbered if `number_taken` is set, and fails the move otherwise. Files cannot be moved at once,
+/// so they are copied before the original is removed, and the copy is removed again if anything
+/// fails on the way.
+pub async fn move_file(
+	root: &FileSystemDirectoryHandle,
+	from: &str,
+	to: &str,
+	expected: &File,
+	number_taken: bool,
+) -> Result<(String, File), Error> {
+	let (from_folders, from_name) = split(from);
+	let source = folder(root, &from_folders, false).await?;
+	let file = unchanged_file(&source, from_name, expected).await?;
+	let (to_folders, _) = split(to);
+	let target = folder(root, &to_folders, true).await?;
+	let mut number = 1;
+	let path = loop {
+		let path = cull::numbered(to, number);
+		if file_handle(&target, split(&path).1, false).await.is_err() {
+			break path;
+		}
+		if !number_taken {
+			return Err(JsValue::from_str(&format!("'{}' is taken", to)).into());
+		}
+		number += 1;
+	};
+	let name = split(&path).1;
+	let handle = file_handle(&target, name, true).await?;
+	let copied: Result<File, Error> = async {
+		let writable: FileSystemWritableFileStream = JsFuture::from(handle.create_writable())
+			.await?
+			.unchecked_into();
+		JsFuture::from(writable.write_with_blob(&file)?).await?;
+		JsFuture::from(writable.close()).await?;
+		let moved = JsFuture::from(handle.get_file()).await?.unchecked_into();
+		JsFuture::from(source.remove_entry(from_name)).await?;
+		Ok(moved)
+	}
+	.await;
+	match copied {
+		Ok(moved) => Ok((path, moved)),
+		Err(err) => {
+			// The original is still in place, so a partial copy would only be clutter.
+			let _ = JsFuture::from(target.remove_entry(name)).await;
+			Err(err)
+		}
+	}
+}
+
+/// Moves a file within the top folder of its paths, with the paths of the move made.
+async fn move_within_top(
+	root: &FileSystemDirectoryHandle,
+	m: &Move,
+	expected: &File,
+	number_taken: bool,
+) -> Result<(Move, File), Error> {
+	let (top, from, to) = match (cull::split_top(&m.from), cull::split_top(&m.to)) {
+		(Some((top, from)), Some((_, to))) => (top, from, to),
+		_ => return Err(JsValue::from_str("Files added on their own cannot be moved").into()),
+	};
+	let (path, file) = move_file(root, from, to, expected, number_taken).await?;
+	let made = Move {
+		from: m.from.clone(),
+		to: format!("{}/{}", top, path),
+	};
+	Ok((made, file))
+}
+
+/// Moves files within the top folders of their paths one after the other, numbering taken paths.
+/// Returns the moves made, along with the files moved, and the failures to make others.
+pub async fn move_files(
+	moves: Vec<(Move, File, FileSystemDirectoryHandle)>,
+) -> (Vec<(Move, File)>, Vec<String>) {
+	let mut moved = Vec::new();
+	let mut failed = Vec::new();
+	for (m, expected, root) in moves {
+		match move_within_top(&root, &m, &expected, true).await {
+			Ok(made) => moved.push(made),
+			Err(err) => failed.push(format!("'{}': {}", m.from, err)),
+		}
+	}
+	(moved, failed)
+}
+
+/// Takes renaming steps one after the other, failing at taken paths rather than numbering them.
+/// The first failure stops the batch and the steps taken so far are undone, so that a batch is
+/// applied whole or not at all. Returns the steps taken, including those undoing others, along
+/// with the files renamed, and the failures.
+///
+/// `files` holds the files the steps start from, by path. Files renamed along the way are
+/// tracked, so that later steps check them rather than the originals.
+pub async fn rename_files(
+	steps: Vec<(Move, FileSystemDirectoryHandle)>,
+	mut files: HashMap<String, File>,
+) -> (Vec<(Move, File)>, Vec<String>) {
+	let mut taken: Vec<(Move, File)> = Vec::new();
+	let mut roots = HashMap::new();
+	for (step, root) in steps {
+		let result = match files.remove(&step.from) {
+			Some(expected) => move_within_top(&root, &step, &expected, false).await,
+			None => Err(JsValue::from_str("The file is not in the library").into()),
+		};
+		match result {
+			Ok((made, file)) => {
+				files.insert(made.to.clone(), file.clone());
+				roots.insert(made.to.clone(), root);
+				taken.push((made, file));
+			}
+			Err(err) => {
+				let mut failed = vec![format!("'{}': {}", step.from, err)];
+				let steps: Vec<Move> = taken.iter().map(|(m, _)| m.clone()).collect();
+				for undo in rename::reversed(&steps) {
+					let (root, expected) = match (roots.get(&undo.from), files.remove(&undo.from)) {
+						(Some(root), Some(expected)) => (root.clone(), expected),
+						_ => continue,
+					};
+					match move_within_top(&root, &undo, &expected, false).await {
+						Ok((made, file)) => {
+							files.insert(made.to.clone(), file.clone());
+							taken.push((made, file));
+						}
+						Err(err) => failed.push(format!("undoing '{}': {}", undo.from, err)),
+					}
+				}
+				return (taken, failed);
+			}
+		}
+	}
+	(taken, Vec::new())
+}
+
+/// Deletes a file for good, provided it still is the one which was added.
+pub async fn delete_file(
+	root: &FileSystemDirectoryHandle,
+	path: &str,
+	expected: &File,
+) -> Result<(), Error> {
+	let (folders, name) = split(path);
+	let folder = folder(root, &folders, false).await?;
+	unchanged_file(&folder, name, expected).await?;
+	JsFuture::from(folder.remove_entry(name)).await?;
+	Ok(())
+}
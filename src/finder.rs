@@ -0,0 +1,193 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use log::warn;
+use omnivyou_core::{
+	duplicates::{self, MAX_DISTANCE},
+	media::Type,
+};
+use wasm_bindgen_futures::spawn_local;
+use yew::{
+	agent::{Agent, AgentLink, HandlerId, Job},
+	Callback,
+};
+
+use crate::{browser, library::Item, thumbnail};
+
+pub enum Request {
+	/// Looks for copies among the items, replacing any search going on. Video frames are compared
+	/// at the given offset.
+	Find {
+		items: Vec<Item>,
+		offset: Duration,
+	},
+	Cancel,
+}
+
+/// Items which are copies of one another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+	/// Whether the items have the same contents, rather than only looking alike.
+	pub exact: bool,
+	pub items: Vec<Item>,
+}
+
+pub enum Response {
+	/// Number of files looked at so far, out of those to look at.
+	Progress { done: usize, total: usize },
+	/// Groups of copies, exact ones first.
+	Found(Vec<Group>),
+}
+
+pub enum Msg {
+	Progress {
+		search: usize,
+		done: usize,
+		total: usize,
+	},
+	Found {
+		search: usize,
+		groups: Vec<Group>,
+	},
+}
+
+/// Finds exact copies by hashing the contents of files of the same size, and near copies by
+/// comparing perceptual hashes of their thumbnails.
+///
+/// Like indexing, searching runs alongside the page, which it yields to after every file.
+pub struct Finder {
+	handler: Option<HandlerId>,
+	link: AgentLink<Self>,
+	/// Identifier of the latest search, which earlier ones stop at once they see it changed.
+	search: Rc<Cell<usize>>,
+}
+
+/// Runs a search, unless a later one replaced it.
+async fn find(
+	items: Vec<Item>,
+	offset: Duration,
+	search: usize,
+	latest: Rc<Cell<usize>>,
+	progress: Callback<(usize, usize)>,
+) -> Option<Vec<Group>> {
+	// Only files of the same size can have the same contents.
+	let sizes: Vec<Option<u64>> = items
+		.iter()
+		.map(|item| Some(item.file.size() as u64).filter(|&size| size > 0))
+		.collect();
+	let same_size: Vec<usize> = duplicates::groups_by(&sizes)
+		.into_iter()
+		.flatten()
+		.collect();
+	let pictures: Vec<usize> = (0..items.len())
+		.filter(|&i| matches!(items[i].media_type(), Type::Image(_) | Type::Video(_)))
+		.collect();
+	let total = same_size.len() + pictures.len();
+	let mut done = 0;
+	let mut hashes = vec![None; items.len()];
+	for i in same_size {
+		if latest.get() != search {
+			return None;
+		}
+		match browser::content_hash(&items[i].file).await {
+			Ok(hash) => hashes[i] = Some(hash),
+			Err(err) => warn!("Failed to hash '{}': {}", items[i].path, err),
+		}
+		done += 1;
+		progress.emit((done, total));
+	}
+	let mut fingerprints = vec![None; items.len()];
+	for i in pictures {
+		if latest.get() != search {
+			return None;
+		}
+		match thumbnail::fingerprint(&items[i], offset).await {
+			Ok(fingerprint) => fingerprints[i] = fingerprint,
+			Err(err) => warn!("Failed to fingerprint '{}': {}", items[i].path, err),
+		}
+		done += 1;
+		progress.emit((done, total));
+	}
+	let group = |exact: bool, positions: Vec<usize>| Group {
+		exact,
+		items: positions.into_iter().map(|i| items[i].clone()).collect(),
+	};
+	let mut groups: Vec<Group> = duplicates::groups_by(&hashes)
+		.into_iter()
+		.map(|positions| group(true, positions))
+		.collect();
+	for positions in duplicates::similar_groups(&fingerprints, MAX_DISTANCE) {
+		// Near copies which are all exact copies of one another were found already.
+		let first = hashes[positions[0]];
+		if first.is_some() && positions.iter().all(|&i| hashes[i] == first) {
+			continue;
+		}
+		groups.push(group(false, positions));
+	}
+	Some(groups)
+}
+
+impl Agent for Finder {
+	type Reach = Job<Self>;
+	type Message = Msg;
+	type Input = Request;
+	type Output = Response;
+
+	fn create(link: AgentLink<Self>) -> Self {
+		Self {
+			handler: None,
+			link,
+			search: Rc::new(Cell::new(0)),
+		}
+	}
+
+	fn update(&mut self, msg: Self::Message) {
+		let handler = match self.handler {
+			Some(handler) => handler,
+			None => return,
+		};
+		match msg {
+			Msg::Progress {
+				search,
+				done,
+				total,
+			} => {
+				if search == self.search.get() {
+					self
+						.link
+						.respond(handler, Response::Progress { done, total });
+				}
+			}
+			Msg::Found { search, groups } => {
+				if search == self.search.get() {
+					self.handler = None;
+					self.link.respond(handler, Response::Found(groups));
+				}
+			}
+		}
+	}
+
+	fn handle_input(&mut self, msg: Self::Input, handler: HandlerId) {
+		let search = self.search.get() + 1;
+		self.search.set(search);
+		match msg {
+			Request::Find { items, offset } => {
+				self.handler = Some(handler);
+				let latest = Rc::clone(&self.search);
+				let progress = self.link.callback(move |(done, total)| Msg::Progress {
+					search,
+					done,
+					total,
+				});
+				let found = self
+					.link
+					.callback(move |groups| Msg::Found { search, groups });
+				spawn_local(async move {
+					if let Some(groups) = find(items, offset, search, latest, progress).await {
+						found.emit(groups);
+					}
+				});
+			}
+			Request::Cancel => self.handler = None,
+		}
+	}
+}
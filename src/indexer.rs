@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use yew::{
+	agent::{Agent, AgentLink, HandlerId, Job},
+	services::{timeout::TimeoutTask, TimeoutService},
+	web_sys::FileList,
+};
+use yewtil::future::LinkFuture;
+
+use crate::library::{self, Item};
+
+/// Number of files looked at before handing control back to the page.
+const BATCH_SIZE: u32 = 500;
+
+pub enum Request {
+	/// Lists the displayable media among the selected files, replacing any selection being indexed.
+	Index(FileList),
+	Cancel,
+}
+
+pub enum Response {
+	/// Media found in the latest batch, sorted among themselves, along with the number of files
+	/// looked at so far.
+	Batch { items: Vec<Item>, done: usize },
+	/// Every file of the selection was looked at.
+	Finished,
+}
+
+pub enum Msg {
+	NextBatch,
+	/// Headers of the latest batch of the given selection were read.
+	Probed {
+		id: u64,
+		items: Vec<Item>,
+		done: u32,
+	},
+}
+
+struct Selection {
+	files: FileList,
+	handler: HandlerId,
+	/// Tells the selection apart from later ones, so that headers still being read for a replaced
+	/// selection are dropped.
+	id: u64,
+	next: u32,
+}
+
+/// Indexes selections in batches, so that large folders neither freeze the page nor hold up
+/// playback until the last file was looked at. The headers of images which may be animated are
+/// read along the way, so that the viewer knows still ones without probing them.
+///
+/// Files cannot be handed to a web worker through agent messages, which are serialized, so the
+/// agent runs alongside the page and yields to it after every batch instead.
+pub struct Indexer {
+	link: AgentLink<Self>,
+	next_selection: u64,
+	selection: Option<Selection>,
+	timeout: Option<TimeoutTask>,
+}
+
+impl Agent for Indexer {
+	type Reach = Job<Self>;
+	type Message = Msg;
+	type Input = Request;
+	type Output = Response;
+
+	fn create(link: AgentLink<Self>) -> Self {
+		Self {
+			link,
+			next_selection: 0,
+			selection: None,
+			timeout: None,
+		}
+	}
+
+	fn update(&mut self, msg: Self::Message) {
+		match msg {
+			Msg::NextBatch => {
+				let selection = match &mut self.selection {
+					Some(selection) => selection,
+					None => return,
+				};
+				let end = selection.files.length().min(selection.next + BATCH_SIZE);
+				let items = (selection.next..end)
+					.filter_map(|i| selection.files.get(i))
+					.map(Item::from)
+					.collect();
+				selection.next = end;
+				let id = selection.id;
+				self.link.send_future(async move {
+					let mut items = library::retain_media(items);
+					library::probe(&mut items).await;
+					Msg::Probed {
+						id,
+						items,
+						done: end,
+					}
+				});
+			}
+			Msg::Probed { id, items, done } => {
+				let selection = match &self.selection {
+					Some(selection) if selection.id == id => selection,
+					_ => return,
+				};
+				let handler = selection.handler;
+				self.link.respond(
+					handler,
+					Response::Batch {
+						items,
+						done: done as usize,
+					},
+				);
+				if done < selection.files.length() {
+					self.timeout = Some(TimeoutService::spawn(
+						Duration::ZERO,
+						self.link.callback(|_| Msg::NextBatch),
+					));
+				} else {
+					self.selection = None;
+					self.timeout = None;
+					self.link.respond(handler, Response::Finished);
+				}
+			}
+		}
+	}
+
+	fn handle_input(&mut self, msg: Self::Input, handler: HandlerId) {
+		match msg {
+			Request::Index(files) => {
+				self.selection = Some(Selection {
+					files,
+					handler,
+					id: self.next_selection,
+					next: 0,
+				});
+				self.next_selection += 1;
+				self.update(Msg::NextBatch);
+			}
+			Request::Cancel => {
+				self.selection = None;
+				self.timeout = None;
+			}
+		}
+	}
+}
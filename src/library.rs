@@ -0,0 +1,169 @@
+use js_sys::{Array, Date, Promise, Reflect, Uint8Array};
+use omnivyou_core::{
+	animation,
+	exif::{self, DateTime},
+	library::{is_hidden, natural_cmp},
+	media::Type,
+	rename::Metadata,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::web_sys::{
+	DataTransfer, File, FileSystemDirectoryEntry, FileSystemEntry, FileSystemFileEntry,
+};
+
+use crate::{browser, error::Error};
+
+/// A media file along with its path relative to the selected folder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+	pub file: File,
+	pub path: String,
+	/// Identifier of the source the item was added with.
+	pub source: usize,
+	/// Whether the header of the file showed it to be a still image, sparing the viewer from probing
+	/// it for animation.
+	pub still: bool,
+}
+
+/// Folder or set of files added to the library at once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Source {
+	pub id: usize,
+	pub name: String,
+	/// Number of items added with it, leaving out duplicates of items already in the library.
+	pub count: usize,
+}
+
+impl Item {
+	/// Key identifying the file across re-selections of the same folder.
+	pub fn key(&self) -> String {
+		format!(
+			"{}:{}:{}",
+			self.path,
+			self.file.size() as u64,
+			self.file.last_modified() as u64
+		)
+	}
+
+	pub fn media_type(&self) -> Type {
+		Type::from(self.file.type_())
+	}
+
+	/// Capture date and camera found in the EXIF tags of the file, falling back to when the file was
+	/// last changed, in local time.
+	pub async fn metadata(&self) -> Result<Metadata, Error> {
+		let header = self.file.slice_with_i32_and_i32(0, exif::HEADER_BYTES)?;
+		let bytes = Uint8Array::new(&JsFuture::from(header.array_buffer()).await?).to_vec();
+		let exif = exif::parse(&bytes);
+		let date = exif.date.unwrap_or_else(|| {
+			let modified = Date::new(&JsValue::from_f64(self.file.last_modified()));
+			DateTime {
+				year: modified.get_full_year() as i32,
+				// Months count from zero in JavaScript.
+				month: modified.get_month() + 1,
+				day: modified.get_date(),
+				hour: modified.get_hours(),
+				minute: modified.get_minutes(),
+				second: modified.get_seconds(),
+			}
+		});
+		Ok(Metadata {
+			date,
+			camera: exif.camera,
+		})
+	}
+}
+
+impl From<File> for Item {
+	fn from(file: File) -> Self {
+		let path = Reflect::get(&file, &JsValue::from_str("webkitRelativePath"))
+			.ok()
+			.and_then(|p| p.as_string())
+			.filter(|p| !p.is_empty())
+			.unwrap_or_else(|| file.name());
+		Self {
+			file,
+			path,
+			source: 0,
+			still: false,
+		}
+	}
+}
+
+/// Keeps the displayable media among the given items, sorted by path and skipping hidden ones.
+pub fn retain_media(mut items: Vec<Item>) -> Vec<Item> {
+	items
+		.retain(|item| !is_hidden(&item.file.name()) && !matches!(item.media_type(), Type::Invalid(_)));
+	items.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+	items
+}
+
+/// Files and folders of a drop. They have to be taken while the drop event is handled, after which
+/// the browser empties the transfer.
+pub fn entries(transfer: &DataTransfer) -> Vec<FileSystemEntry> {
+	let items = transfer.items();
+	(0..items.length())
+		.filter_map(|i| items.get(i)?.webkit_get_as_entry().ok()?)
+		.collect()
+}
+
+/// Lists the displayable media among dropped files and folders, walking folders recursively.
+pub async fn index_entries(mut entries: Vec<FileSystemEntry>) -> Result<Vec<Item>, Error> {
+	let mut items = Vec::new();
+	while let Some(entry) = entries.pop() {
+		if is_hidden(&entry.name()) {
+			continue;
+		}
+		if entry.is_directory() {
+			let reader = entry
+				.unchecked_ref::<FileSystemDirectoryEntry>()
+				.create_reader();
+			// Folders are read in batches until an empty one comes back.
+			loop {
+				let batch = JsFuture::from(Promise::new(&mut |resolve, reject| {
+					if let Err(err) = reader.read_entries_with_callback_and_callback(&resolve, &reject) {
+						let _ = reject.call1(&JsValue::NULL, &err);
+					}
+				}))
+				.await?
+				.unchecked_into::<Array>();
+				if batch.length() == 0 {
+					break;
+				}
+				entries.extend(batch.iter().map(JsValue::unchecked_into));
+			}
+		} else if entry.is_file() {
+			let file = JsFuture::from(Promise::new(&mut |resolve, reject| {
+				entry
+					.unchecked_ref::<FileSystemFileEntry>()
+					.file_with_callback_and_callback(&resolve, &reject)
+			}))
+			.await?
+			.unchecked_into::<File>();
+			let path = String::from(entry.full_path().trim_start_matches('/'));
+			items.push(Item {
+				file,
+				path,
+				source: 0,
+				still: false,
+			});
+		}
+	}
+	let mut items = retain_media(items);
+	probe(&mut items).await;
+	Ok(items)
+}
+
+/// Reads the header of images which may be animated, marking those which turn out to be still.
+/// Files which cannot be read are left for the viewer to report.
+pub async fn probe(items: &mut [Item]) {
+	for item in items
+		.iter_mut()
+		.filter(|item| animation::animatable(&item.file.type_()))
+	{
+		if let Ok(header) = browser::read_head(&item.file, animation::HEADER_BYTES).await {
+			item.still = !animation::may_be_animated(&header);
+		}
+	}
+}
@@ -1,8 +1,17 @@
 #![recursion_limit = "1024"]
 
 mod app;
+mod browser;
 mod components;
+mod error;
+mod files;
+mod finder;
+mod indexer;
+mod library;
 mod routes;
+mod storage;
+mod thumbnail;
+mod thumbnailer;
 
 use wasm_bindgen::JsValue;
 
@@ -10,6 +19,7 @@ use app::App;
 
 fn main() -> Result<(), JsValue> {
 	wasm_logger::init(wasm_logger::Config::default());
+	error::set_panic_hook();
 	yew::start_app::<App>();
 	Ok(())
 }
@@ -1,198 +1,2380 @@
-use std::time::Duration;
+use std::{
+	collections::{HashMap, HashSet},
+	time::Duration,
+};
 
 use gloo_events::EventListener;
-use log::warn;
+use log::{error, info, warn};
+use omnivyou_core::{
+	cull::{self, Move, Verdict, REJECTS_FOLDER},
+	library::{
+		duplicate_keys, folder_of, merge_sorted, natural_cmp, source_name, without_duplicates, Mark,
+		TagMatch, MAX_RATING,
+	},
+	media::{Kind, Type},
+	playlist::{self, Format},
+	profile::{self, Profile},
+	query,
+	queue::Queue,
+	rename,
+	settings::{Rule, RuleChange, Settings},
+	url,
+};
 use wasm_bindgen::JsCast;
 use yew::{
+	agent::{Bridge, Bridged, Dispatched, Dispatcher},
 	html,
-	web_sys::{window, File, HtmlInputElement, KeyboardEvent},
-	Callback, ChangeData, Component, ComponentLink, Html, NodeRef, ShouldRender,
+	web_sys::{
+		window, DragEvent, File, FileList, FileSystemDirectoryHandle, FileSystemEntry,
+		HtmlInputElement, KeyboardEvent,
+	},
+	Callback, ChangeData, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender,
 };
 use yew_octicons::{Icon, IconKind};
+use yew_router::{
+	agent::{RouteAgentDispatcher, RouteRequest},
+	route::Route,
+};
+use yewtil::future::LinkFuture;
 
 use crate::{
-	app::Settings,
+	browser,
 	components::{
-		media::{Media, Type},
+		about::About,
+		duplicates::Duplicates,
+		filmstrip::Filmstrip,
+		grid::Grid,
+		media::Media,
 		menu::Menu,
-		warning::Warning,
+		notifications::{Notification, Notifications, Severity},
+		playlists::PlaylistManager,
+		rejects::Rejects,
+		renamer::Renamer,
+		scrubber::Scrubber,
+		soundtrack::Soundtrack,
+		tags::TagEditor,
 	},
+	error::Error,
+	files,
+	finder::{self, Finder, Group},
+	indexer::{Indexer, Request, Response},
+	library::{self, Item, Source},
+	routes::AppRoute,
+	storage::{self, MARKS_STORE, PLAYLISTS_STORE, PROFILES_STORE, TAGS_STORE},
+	thumbnailer::{self, Thumbnailer},
 };
 
+/// Number of past notifications kept for the history.
+const MAX_NOTIFICATIONS: usize = 100;
+
+/// Number of items shown on either side of the current one in the filmstrip.
+const FILMSTRIP_RADIUS: usize = 5;
+
 pub enum Msg {
-	DropWarning,
-	IndexDirectory(Vec<File>),
+	AddTag(String),
+	AddToPlaylist,
+	/// Adds dropped items to the library as another source.
+	AddItems(Vec<Item>),
+	CreatePlaylist(String),
+	CancelIndexing,
+	CancelDuplicates,
+	CycleFilterRating,
+	ClearNotifications,
+	/// Leaves the grid, about or settings route for the current item.
+	CloseView,
+	CopySettingsLink,
+	DeletePlaylist(String),
+	/// Deletes files for good.
+	DeleteFiles(Vec<String>),
+	/// Paths of the files deleted, and the failures to delete others.
+	Deleted(Vec<String>, Vec<String>),
+	DismissNotification(usize),
+	/// Whether files are dragged over the page.
+	Dragging(bool),
+	DropEntries(Vec<FileSystemEntry>),
+	Error(Error),
+	Escape,
+	ExportPlaylist(Format),
+	/// Looks for copies among the items of the library.
+	FindDuplicates,
+	FirstFile,
+	/// Rejects of a folder just granted whose files on disk differ from the ones added.
+	FolderChecked(String, Vec<String>),
+	FolderGranted(String, FileSystemDirectoryHandle),
+	Found(finder::Response),
+	/// Asks for permission to change the files of a top folder.
+	GrantFolder(String),
+	/// Leaves files out of the library, without changing them.
+	HideFiles(Vec<String>),
+	ImportPlaylist(File),
+	/// Adds the selected files to the library as another source.
+	Index(FileList),
+	Indexed(Response),
+	/// Marks the current item as kept or rejected while culling, or clears the same verdict.
+	Judge(Verdict),
+	/// Moves to the item at the given position of the queue.
+	JumpTo(usize),
+	KeepRejects(Vec<String>),
+	LastFile,
+	LoadMarks(Vec<(String, Mark)>),
+	LoadPlaylist(Format, String),
+	LoadPlaylists(Vec<(String, Vec<String>)>),
+	LoadTags(Vec<(String, Vec<String>)>),
+	MovePlaylistEntry(String, usize, usize),
+	/// Moves rejects into subfolders of their folders.
+	MoveRejects(Vec<String>),
+	/// Rejects moved along with the files they were moved as, and the failures to move others.
+	Moved(Vec<(Move, File)>, Vec<String>),
+	/// Items moved back, the number of them numbered for their path being taken, and the failures
+	/// to move others.
+	MovedBack(Vec<Item>, usize, Vec<String>),
 	NextFile,
+	/// Moves forward by a page of items, or to the next folder.
+	NextPage,
+	Notify(Severity, String, Option<(String, Callback<()>)>),
+	OpenFolder(String),
+	OpenItem(String),
+	PlayPlaylist(String),
+	PlayTags(Vec<String>, TagMatch),
+	/// Position of the queue previewed by the scrubber.
+	Preview(Option<usize>),
 	PreviousFile,
+	PreviousPage,
 	Quit,
-	ConfigAudioAutoplayDelay(u64),
-	ConfigImageAutoplayDelay(u64),
-	ConfigVideoAutoplayDelay(u64),
-	ToggleAudio,
-	ToggleAudioAutoplay,
-	ToggleImage,
-	ToggleImageAutoplay,
-	ToggleVideo,
-	ToggleVideoAutoplay,
+	RemoveFromPlaylist(String, usize),
+	RemoveSource(usize),
+	RemoveTag(String),
+	/// Renames files, one step after the other.
+	Rename(Vec<Move>),
+	/// Steps taken along with the files renamed, and the failure which stopped the batch, if any.
+	Renamed(Vec<(Move, File)>, Vec<String>),
+	/// Steps taken undoing a batch of renames, which is logged again if the undo failed.
+	RenamedBack((usize, Vec<Move>), Vec<(Move, File)>, Vec<String>),
+	RestorePlaylist(String, Vec<String>),
+	SelectPlaylist(String),
+	SelectSoundtrack(Option<String>),
+	SetRating(u8),
+	ShowAbout,
+	ShowDuplicates(bool),
+	ShowGrid,
+	ShowRejects(bool),
+	ShowRenamer(bool),
+	ShowSettings(bool),
+	SoundtrackDuration(f64),
+	ToggleCulling,
+	ToggleFavorite,
+	ToggleFilmstrip,
+	ToggleFilterFavorites,
+	ToggleHold,
+	TogglePlaylistManager,
+	ToggleTagEditor,
+	/// Moves a batch of rejects back where they were.
+	UndoMove(usize),
+	/// Renames a batch of files back to what they were called.
+	UndoRename(usize),
+	/// Puts hidden items back into the library.
+	UnhideFiles(Vec<Item>),
+	Warn(String),
+	AddRule(Rule),
+	ConfigPageSize(u32),
+	ConfigSoundtrackFade(u64),
+	ConfigSoundtrackVolume(u8),
+	ConfigThumbnailCacheSize(u32),
+	ConfigThumbnailOffset(u64),
+	DeleteProfile(String),
+	ExportProfiles,
+	ImportProfiles(File),
+	LoadProfileFile(String),
+	LoadProfiles(Vec<Profile>),
+	RemoveRule(usize),
+	RestoreProfile(Profile),
+	SaveProfile(String),
+	SelectProfile(String),
+	ToggleKind(Kind),
+	TogglePageByFolder,
+	ToggleSoundtrackSync,
+	UpdateRule(usize, RuleChange),
 	None,
 }
 
+#[derive(Clone, PartialEq, Properties)]
+pub struct Props {
+	/// Query string holding settings to start with, as found in shared links.
+	#[prop_or_default]
+	pub query: String,
+	pub route: AppRoute,
+}
+
+/// Selection being added to the library.
+struct Indexing {
+	/// Whether the selection adds to a library being viewed, rather than starting one.
+	append: bool,
+	done: usize,
+	/// Number of files skipped for being in the library already.
+	duplicates: usize,
+	/// Paths and sizes of the files in the library, including those indexed so far.
+	seen: HashSet<(String, u64)>,
+	source: usize,
+	total: usize,
+}
+
 pub struct Home {
-	files: Option<Vec<File>>,
-	index: isize,
+	/// Saved playlist which new entries are added to.
+	active_playlist: Option<String>,
+	/// Whether items are being kept or rejected with keys.
+	culling: bool,
+	/// Whether files are dragged over the page, which shows where to drop them.
+	dragging: bool,
+	/// Groups of copies found by the last search, if it finished.
+	duplicates: Option<Vec<Group>>,
+	duplicates_visible: bool,
+	/// Whether files are being moved, renamed or deleted.
+	files_busy: bool,
+	filmstrip_visible: bool,
+	finder: Box<dyn Bridge<Finder>>,
+	/// Number of files compared so far and in all, while looking for copies.
+	finding: Option<(usize, usize)>,
+	/// Top folders which changes to their files were allowed to.
+	folder_handles: HashMap<String, FileSystemDirectoryHandle>,
+	/// Whether the slideshow is paused, which lasts across items until resumed.
+	held: bool,
+	indexer: Box<dyn Bridge<Indexer>>,
+	indexing: Option<Indexing>,
 	keydown_listener: Option<EventListener>,
+	/// Every indexed item, of which `queue` is the currently playing subset.
+	library: Vec<Item>,
 	link: ComponentLink<Self>,
+	marks: HashMap<String, Mark>,
+	/// Batches of rejects moved aside, along with the items they were moved as, which can be undone.
+	moves: Vec<(usize, Vec<(Move, Item)>)>,
+	/// Identifier of the next batch of moves or renames.
+	next_batch: usize,
+	next_notification: usize,
+	next_source: usize,
 	node_ref: NodeRef,
+	/// Notification history, oldest first.
+	notifications: Vec<Notification>,
 	on_ended_cb: Callback<()>,
+	playlist_manager_visible: bool,
+	playlists: Vec<(String, Vec<String>)>,
+	/// Position of the queue previewed by the scrubber.
+	preview: Option<usize>,
+	/// Profile the settings were last switched to or saved as.
+	profile: Option<String>,
+	/// Saved settings profiles, sorted by name.
+	profiles: Vec<Profile>,
+	queue: Option<Queue<Item>>,
+	rejects_visible: bool,
+	/// Batches of renames applied, as the steps taken, which can be undone.
+	renames: Vec<(usize, Vec<Move>)>,
+	renamer_visible: bool,
+	route: AppRoute,
+	router: RouteAgentDispatcher,
 	settings: Settings,
-	warning_message: Option<String>,
+	/// Audio file path, or folder path ending in `/`, playing underneath slideshows.
+	soundtrack: Option<String>,
+	soundtrack_duration: Option<f64>,
+	/// Folders and files the library was put together from, in the order they were added.
+	sources: Vec<Source>,
+	tag_editor_visible: bool,
+	tags: HashMap<String, Vec<String>>,
+	/// Keeps thumbnails alive across views, and their cache within budget.
+	thumbnailer: Dispatcher<Thumbnailer>,
+	/// Verdicts passed on items while culling, by path.
+	verdicts: HashMap<String, Verdict>,
+}
+
+impl Home {
+	fn current_item(&self) -> Option<&Item> {
+		self.queue.as_ref().map(Queue::current)
+	}
+
+	/// Navigates to a route, which adds to the browser history.
+	fn navigate(&mut self, route: AppRoute) {
+		self
+			.router
+			.send(RouteRequest::ChangeRoute(Route::from(route)));
+	}
+
+	/// Moves within the queue and shows the item moved to.
+	fn move_in_queue(&mut self, movement: impl FnOnce(&mut Queue<Item>)) -> ShouldRender {
+		match &mut self.queue {
+			Some(queue) => movement(queue),
+			None => return false,
+		}
+		let route = self.viewer_route();
+		self.navigate(route);
+		true
+	}
+
+	/// Moves by a page of items, or to the neighboring folder, as set in the settings.
+	fn move_page(&mut self, forward: bool) -> ShouldRender {
+		let (by_folder, size) = (
+			self.settings.page_by_folder,
+			self.settings.page_size as isize,
+		);
+		self.move_in_queue(|queue| {
+			if by_folder {
+				queue.skip_group(forward, |item| folder_of(&item.path).to_string());
+			} else {
+				queue.skip(if forward { size } else { -size });
+			}
+		})
+	}
+
+	/// Route showing the current item, or the folder picker if there is none.
+	fn viewer_route(&self) -> AppRoute {
+		match self.current_item() {
+			Some(item) => AppRoute::view(&item.path),
+			None => AppRoute::Home,
+		}
+	}
+
+	/// Shows the item the route points at, once the library is indexed.
+	fn apply_route(&mut self) -> ShouldRender {
+		let path = match &self.route {
+			AppRoute::View(path) => url::decode(path),
+			_ => return true,
+		};
+		if self.library.is_empty() || self.current_item().is_some_and(|item| item.path == path) {
+			return true;
+		}
+		if let Some(queue) = &mut self.queue {
+			if let Some(i) = queue.items().iter().position(|item| item.path == path) {
+				queue.jump(i);
+				return true;
+			}
+		}
+		// Items outside of the queue play along with the rest of their folder.
+		let folder = folder_of(&path);
+		let items: Vec<Item> = self
+			.library
+			.iter()
+			.filter(|item| folder_of(&item.path) == folder)
+			.filter(|item| item.path == path || self.passes_filters(item))
+			.cloned()
+			.collect();
+		match items.iter().position(|item| item.path == path) {
+			Some(i) => {
+				self.queue = Queue::new(items);
+				if let Some(queue) = &mut self.queue {
+					queue.jump(i);
+				}
+				true
+			}
+			// The item may be indexed later on.
+			None if self.indexing.is_some() => true,
+			None => self.update(Msg::Warn(format!("'{}' could not be found", path))),
+		}
+	}
+
+	fn mark(&self, item: &Item) -> Mark {
+		self.marks.get(&item.key()).copied().unwrap_or_default()
+	}
+
+	fn item_tags(&self, item: &Item) -> Vec<String> {
+		self.tags.get(&item.key()).cloned().unwrap_or_default()
+	}
+
+	fn known_tags(&self) -> Vec<String> {
+		let mut known: Vec<String> = self.tags.values().flatten().cloned().collect();
+		known.sort();
+		known.dedup();
+		known
+	}
+
+	fn item_rule(&self, item: &Item) -> Option<&Rule> {
+		self.settings.rule(&item.file.type_(), &item.file.name())
+	}
+
+	/// Rule handed to `Media`, with slide durations synced to the soundtrack if requested.
+	fn media_rule(&self, item: &Item) -> Rule {
+		let mut rule = match self.item_rule(item) {
+			Some(rule) => rule.clone(),
+			None => return Rule::new(Kind::Image, None),
+		};
+		if let (Kind::Image, true, Some(_), Some(duration), Some(queue)) = (
+			rule.kind,
+			self.settings.soundtrack_sync,
+			&self.soundtrack,
+			self.soundtrack_duration,
+			&self.queue,
+		) {
+			let images = queue
+				.items()
+				.iter()
+				.filter(|item| matches!(item.media_type(), Type::Image(_)))
+				.count();
+			if images > 0 && duration.is_finite() {
+				rule.delay = Duration::from_secs_f64((duration / images as f64).max(1.0));
+			}
+		}
+		rule
+	}
+
+	fn passes_filters(&self, item: &Item) -> bool {
+		self
+			.settings
+			.passes(&item.file.type_(), &item.file.name(), self.mark(item))
+	}
+
+	/// Rebuilds the queue from the library once the filters changed, staying on the current item if
+	/// it still passes them.
+	fn requeue(&mut self) -> ShouldRender {
+		if self.library.is_empty() {
+			return true;
+		}
+		let files: Vec<Item> = self
+			.library
+			.iter()
+			.filter(|item| self.passes_filters(item))
+			.cloned()
+			.collect();
+		let current = self.current_item().map(|item| item.path.clone());
+		let mut queue = match Queue::new(files) {
+			Some(queue) => queue,
+			None => return self.update(Msg::Warn(String::from("No files pass the filters"))),
+		};
+		if let Some(i) =
+			current.and_then(|path| queue.items().iter().position(|item| item.path == path))
+		{
+			queue.jump(i);
+		}
+		// Nothing of a library being indexed may have passed the filters before.
+		if self.queue.replace(queue).is_none() {
+			self.start_viewing();
+		} else {
+			let route = self.viewer_route();
+			self.navigate(route);
+		}
+		true
+	}
+
+	/// Audio folders followed by audio files which can be picked as soundtrack.
+	fn soundtrack_options(&self) -> Vec<String> {
+		let mut folders = Vec::new();
+		let mut files = Vec::new();
+		for item in self
+			.library
+			.iter()
+			.filter(|item| matches!(item.media_type(), Type::Audio(_)))
+		{
+			if let Some(i) = item.path.rfind('/') {
+				folders.push(format!("{}/", &item.path[..i]));
+			}
+			files.push(item.path.clone());
+		}
+		folders.sort();
+		folders.dedup();
+		folders.extend(files);
+		folders
+	}
+
+	fn soundtrack_tracks(&self) -> Vec<File> {
+		match &self.soundtrack {
+			Some(selection) => self
+				.library
+				.iter()
+				.filter(|item| matches!(item.media_type(), Type::Audio(_)))
+				.filter(|item| {
+					item.path == *selection || (selection.ends_with('/') && item.path.starts_with(selection))
+				})
+				.map(|item| item.file.clone())
+				.collect(),
+			None => Vec::new(),
+		}
+	}
+
+	fn update_mark(&mut self, update: impl FnOnce(&mut Mark)) -> ShouldRender {
+		let key = match self.current_item() {
+			Some(item) => item.key(),
+			None => return false,
+		};
+		let mark = self.marks.entry(key.clone()).or_default();
+		update(mark);
+		let mark = *mark;
+		if mark.is_empty() {
+			self.marks.remove(&key);
+		}
+		self.link.send_future(async move {
+			let result = if mark.is_empty() {
+				storage::delete(MARKS_STORE, &key).await
+			} else {
+				storage::put(MARKS_STORE, &key, &mark).await
+			};
+			if let Err(err) = result {
+				warn!("Failed to save mark for '{}': {:?}", key, err);
+			}
+			Msg::None
+		});
+		true
+	}
+
+	/// Shows a message to the user, optionally offering an action to take about it.
+	fn notify(
+		&mut self,
+		severity: Severity,
+		message: String,
+		action: Option<(String, Callback<()>)>,
+	) -> ShouldRender {
+		match severity {
+			Severity::Info => info!("{}", &message),
+			Severity::Warning => warn!("{}", &message),
+			Severity::Error => error!("{}", &message),
+		}
+		if self.notifications.len() >= MAX_NOTIFICATIONS {
+			self.notifications.remove(0);
+		}
+		self.notifications.push(Notification {
+			action,
+			dismissed: false,
+			id: self.next_notification,
+			message,
+			severity,
+		});
+		self.next_notification += 1;
+		true
+	}
+
+	/// Starts adding a selection of files to the library as a new source.
+	fn begin_indexing(&mut self, total: usize) {
+		if self.indexing.is_some() {
+			self.finish_indexing(true);
+		}
+		let id = self.next_source;
+		self.next_source += 1;
+		self.sources.push(Source {
+			id,
+			name: String::new(),
+			count: 0,
+		});
+		self.indexing = Some(Indexing {
+			append: self.queue.is_some(),
+			done: 0,
+			duplicates: 0,
+			seen: duplicate_keys(&self.library, |item| {
+				(item.path.as_str(), item.file.size() as u64)
+			}),
+			source: id,
+			total,
+		});
+	}
+
+	/// Adds indexed items to the library, starting playback as soon as some of them pass the filters.
+	fn add_indexed(&mut self, items: Vec<Item>, done: usize) -> ShouldRender {
+		let indexing = match &mut self.indexing {
+			Some(indexing) => indexing,
+			None => return false,
+		};
+		let found = items.len();
+		let mut items = without_duplicates(&mut indexing.seen, items, |item| {
+			(item.path.as_str(), item.file.size() as u64)
+		});
+		indexing.done = done;
+		indexing.duplicates += found - items.len();
+		let (id, append) = (indexing.source, indexing.append);
+		for item in &mut items {
+			item.source = id;
+		}
+		let files: Vec<Item> = items
+			.iter()
+			.filter(|item| self.passes_filters(item))
+			.cloned()
+			.collect();
+		// Batches come sorted, so they are merged in rather than sorting the whole library again.
+		merge_sorted(&mut self.library, items, |a, b| {
+			natural_cmp(&a.path, &b.path)
+		});
+		let paths: Vec<&str> = self
+			.library
+			.iter()
+			.filter(|item| item.source == id)
+			.map(|item| item.path.as_str())
+			.collect();
+		let (name, count) = (source_name(&paths), paths.len());
+		if let Some(source) = self.sources.iter_mut().find(|s| s.id == id) {
+			source.name = name;
+			source.count = count;
+		}
+		match &mut self.queue {
+			Some(queue) => {
+				// The queue of a library being put together follows its order.
+				if append {
+					queue.extend(files);
+				} else {
+					queue.merge(files, |a, b| natural_cmp(&a.path, &b.path));
+				}
+				// The item pointed at by the route may have just come in.
+				if let AppRoute::View(_) = self.route {
+					self.apply_route();
+				}
+			}
+			None => {
+				self.queue = Queue::new(files);
+				if self.queue.is_some() {
+					self.start_viewing();
+				}
+			}
+		}
+		true
+	}
+
+	/// Wraps up the selection being indexed, keeping what was indexed so far if it was canceled.
+	fn finish_indexing(&mut self, canceled: bool) -> ShouldRender {
+		let indexing = match self.indexing.take() {
+			Some(indexing) => indexing,
+			None => return false,
+		};
+		if canceled {
+			self.indexer.send(Request::Cancel);
+		}
+		let source = match self.sources.iter().find(|s| s.id == indexing.source) {
+			Some(source) => source.clone(),
+			None => return true,
+		};
+		if source.count == 0 {
+			self.sources.retain(|s| s.id != source.id);
+		}
+		if self.queue.is_none() {
+			self.library.clear();
+			self.sources.clear();
+		} else if let AppRoute::View(_) = self.route {
+			self.apply_route();
+		}
+		if canceled {
+			let message = format!(
+				"Stopped indexing after {} of {} files",
+				indexing.done, indexing.total
+			);
+			self.notify(Severity::Info, message, None)
+		} else if self.queue.is_none() || source.count == 0 {
+			let message = if indexing.duplicates > 0 {
+				String::from("Every file found is in the library already")
+			} else {
+				String::from("No files could be found")
+			};
+			self.update(Msg::Warn(message))
+		} else if indexing.append {
+			let mut message = format!("Added {} files from '{}'", source.count, source.name);
+			if indexing.duplicates > 0 {
+				message.push_str(&format!(", skipping {} duplicates", indexing.duplicates));
+			}
+			self.notify(Severity::Info, message, None)
+		} else {
+			true
+		}
+	}
+
+	/// Leaves the landing page for the queue, which has just been filled.
+	fn start_viewing(&mut self) {
+		match self.route {
+			// The folder was selected again after a refresh, so pick up where the route points.
+			AppRoute::View(_) => {
+				self.apply_route();
+			}
+			AppRoute::Home | AppRoute::PageNotFound(_) => {
+				let route = self.viewer_route();
+				self.navigate(route);
+			}
+			_ => {}
+		}
+		if let Some(w) = window() {
+			let onkeydown = self.link.callback(|e: KeyboardEvent| {
+				let key = e.key();
+				// Leave keystrokes typed into text fields alone.
+				if key != "Escape" && e.target().is_some_and(|t| t.has_type::<HtmlInputElement>()) {
+					return Msg::None;
+				}
+				match key.as_str() {
+					" " => {
+						// Keep the page from scrolling or clicking the focused button.
+						e.prevent_default();
+						Msg::ToggleHold
+					}
+					"ArrowLeft" => Msg::PreviousFile,
+					"ArrowRight" => Msg::NextFile,
+					"End" | "Home" | "PageDown" | "PageUp" => {
+						e.prevent_default();
+						match key.as_str() {
+							"End" => Msg::LastFile,
+							"Home" => Msg::FirstFile,
+							"PageDown" => Msg::NextPage,
+							_ => Msg::PreviousPage,
+						}
+					}
+					"Escape" => Msg::Escape,
+					"a" | "A" => Msg::AddToPlaylist,
+					"c" | "C" => Msg::ToggleCulling,
+					"d" | "D" => Msg::ShowDuplicates(true),
+					"f" | "F" => Msg::ToggleFavorite,
+					"k" | "K" => Msg::Judge(Verdict::Keep),
+					"n" | "N" => Msg::ShowRenamer(true),
+					"r" | "R" => Msg::ShowRejects(true),
+					"x" | "X" => Msg::Judge(Verdict::Reject),
+					"s" | "S" => Msg::ToggleFilmstrip,
+					"g" | "G" => Msg::ShowGrid,
+					"p" | "P" => Msg::TogglePlaylistManager,
+					"t" | "T" => Msg::ToggleTagEditor,
+					key => match key.parse::<u8>() {
+						Ok(rating) if rating <= MAX_RATING => Msg::SetRating(rating),
+						_ => Msg::None,
+					},
+				}
+			});
+			self.keydown_listener = Some(EventListener::new(&w, "keydown", move |e| {
+				if let Some(e) = e.dyn_ref::<KeyboardEvent>() {
+					onkeydown.emit(e.clone());
+				}
+			}));
+		}
+	}
+
+	/// Keeps the items of the library passing a check, and returns whether any are left to view.
+	fn retain_items(&mut self, keep: impl Fn(&Item) -> bool) -> bool {
+		self.library.retain(&keep);
+		self.queue = self.queue.take().and_then(|queue| queue.retain(&keep));
+		self.count_sources();
+		if self.queue.is_none() {
+			return false;
+		}
+		if self
+			.soundtrack
+			.as_ref()
+			.is_some_and(|soundtrack| !self.soundtrack_options().contains(soundtrack))
+		{
+			self.soundtrack = None;
+			self.soundtrack_duration = None;
+		}
+		// The current item may have been removed, so the route follows the queue.
+		if let AppRoute::View(_) = self.route {
+			let route = self.viewer_route();
+			self.navigate(route);
+		}
+		true
+	}
+
+	/// Puts items back into the library, and into the queue if they pass the filters.
+	fn restore_items(&mut self, items: Vec<Item>) {
+		let files: Vec<Item> = items
+			.iter()
+			.filter(|item| self.passes_filters(item))
+			.cloned()
+			.collect();
+		self.library.extend(items);
+		self.library.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+		self.count_sources();
+		if let Some(queue) = &mut self.queue {
+			queue.extend(files);
+			queue.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+		}
+	}
+
+	fn count_sources(&mut self) {
+		for source in &mut self.sources {
+			source.count = self
+				.library
+				.iter()
+				.filter(|item| item.source == source.id)
+				.count();
+		}
+	}
+
+	/// Rejected items of the library.
+	fn rejects(&self) -> Vec<Item> {
+		self
+			.library
+			.iter()
+			.filter(|item| self.verdicts.get(&item.path) == Some(&Verdict::Reject))
+			.cloned()
+			.collect()
+	}
+
+	/// Items of the queue which can be renamed, being in a folder.
+	fn renamable(&self) -> Vec<Item> {
+		self
+			.queue
+			.iter()
+			.flat_map(Queue::items)
+			.filter(|item| cull::split_top(&item.path).is_some())
+			.cloned()
+			.collect()
+	}
+
+	/// Top folders of some items, along with whether changes to them were allowed.
+	fn top_folders(&self, items: &[Item]) -> Vec<(String, bool)> {
+		let mut folders: Vec<(String, bool)> = Vec::new();
+		for item in items {
+			if let Some((top, _)) = cull::split_top(&item.path) {
+				if !folders.iter().any(|(folder, _)| folder == top) {
+					folders.push((top.to_string(), self.folder_handles.contains_key(top)));
+				}
+			}
+		}
+		folders
+	}
+
+	/// Groups of copies found, left with the items still in the library.
+	fn duplicate_groups(&self) -> Option<Vec<Group>> {
+		let paths: HashSet<&String> = self.library.iter().map(|item| &item.path).collect();
+		let groups = self.duplicates.as_ref()?;
+		Some(
+			groups
+				.iter()
+				.map(|group| Group {
+					exact: group.exact,
+					items: group
+						.items
+						.iter()
+						.filter(|item| paths.contains(&item.path))
+						.cloned()
+						.collect(),
+				})
+				.filter(|group| group.items.len() > 1)
+				.collect(),
+		)
+	}
+
+	/// Pairs changes with the folder which changes to their paths were allowed to, leaving out the
+	/// others. Files added on their own cannot be changed, which the user is told about.
+	fn writable<T>(
+		&mut self,
+		changes: Vec<T>,
+		path: impl Fn(&T) -> &str,
+	) -> Vec<(T, FileSystemDirectoryHandle)> {
+		let mut alone = 0;
+		let targets = changes
+			.into_iter()
+			.filter_map(|change| {
+				let top = match cull::split_top(path(&change)) {
+					Some((top, _)) => top,
+					None => {
+						alone += 1;
+						return None;
+					}
+				};
+				let folder = self.folder_handles.get(top)?.clone();
+				Some((change, folder))
+			})
+			.collect();
+		if alone > 0 {
+			let message = format!(
+				"Left {} files added on their own untouched, as only files within a folder can be changed",
+				alone
+			);
+			self.notify(Severity::Info, message, None);
+		}
+		targets
+	}
+
+	/// Describes how many files had to be numbered for their path being taken, if any.
+	fn numbered_note(numbered: usize) -> String {
+		if numbered > 0 {
+			format!(", numbering {} whose names were taken", numbered)
+		} else {
+			String::new()
+		}
+	}
+
+	/// Moves marks and tags over to the key of a file which was renamed.
+	fn rekey(&mut self, old: String, new: String) {
+		if old == new {
+			return;
+		}
+		let mark = self.marks.remove(&old);
+		if let Some(mark) = mark {
+			self.marks.insert(new.clone(), mark);
+		}
+		let tags = self.tags.remove(&old);
+		if let Some(tags) = &tags {
+			self.tags.insert(new.clone(), tags.clone());
+		}
+		if mark.is_none() && tags.is_none() {
+			return;
+		}
+		self.link.send_future(async move {
+			let mut result = Ok(());
+			if let Some(mark) = mark {
+				result = result
+					.and(storage::delete(MARKS_STORE, &old).await)
+					.and(storage::put(MARKS_STORE, &new, &mark).await);
+			}
+			if let Some(tags) = tags {
+				result = result
+					.and(storage::delete(TAGS_STORE, &old).await)
+					.and(storage::put(TAGS_STORE, &new, &tags).await);
+			}
+			if let Err(err) = result {
+				warn!("Failed to move marks and tags of '{}': {:?}", old, err);
+			}
+			Msg::None
+		});
+	}
+
+	/// Points items at the paths and files they were moved to, in the order the moves were made.
+	fn apply_renames(&mut self, renamed: Vec<(Move, File)>) {
+		let current = self.current_item().map(|item| item.path.clone());
+		// Keys the renamed items had before any of the moves, by the paths they ended up at.
+		let mut old_keys: HashMap<String, String> = HashMap::new();
+		for (m, file) in renamed {
+			for item in self.library.iter_mut().filter(|item| item.path == m.from) {
+				let old = old_keys.remove(&m.from).unwrap_or_else(|| item.key());
+				old_keys.insert(m.to.clone(), old);
+				item.path = m.to.clone();
+				item.file = file.clone();
+			}
+			if let Some(queue) = &mut self.queue {
+				for item in queue
+					.items_mut()
+					.iter_mut()
+					.filter(|item| item.path == m.from)
+				{
+					item.path = m.to.clone();
+					item.file = file.clone();
+				}
+			}
+			if let Some(verdict) = self.verdicts.remove(&m.from) {
+				self.verdicts.insert(m.to.clone(), verdict);
+			}
+		}
+		for (path, old) in old_keys {
+			if let Some(new) = self
+				.library
+				.iter()
+				.find(|item| item.path == path)
+				.map(Item::key)
+			{
+				self.rekey(old, new);
+			}
+		}
+		self.library.sort_by(|a, b| natural_cmp(&a.path, &b.path));
+		// The route points at the current item by path.
+		if let AppRoute::View(_) = self.route {
+			if self.current_item().map(|item| &item.path) != current.as_ref() {
+				let route = self.viewer_route();
+				self.navigate(route);
+			}
+		}
+	}
+
+	/// Tells the user about files which could not be changed.
+	fn report_failures(&mut self, action: &str, failed: Vec<String>) -> ShouldRender {
+		let message = match failed.as_slice() {
+			[] => return false,
+			[failure] => format!("Failed to {} {}", action, failure),
+			[failure, ..] => format!(
+				"Failed to {} {} files, including {}",
+				action,
+				failed.len(),
+				failure
+			),
+		};
+		for failure in &failed[1..] {
+			warn!("Failed to {} {}", action, failure);
+		}
+		self.notify(Severity::Error, message, None)
+	}
+
+	/// Tells the user about the failure which stopped a batch of renames, after which the files
+	/// renamed so far were renamed back.
+	fn report_stopped_renames(&mut self, action: &str, failed: Vec<String>) -> ShouldRender {
+		let (failure, undo) = match failed.split_first() {
+			Some(split) => split,
+			None => return false,
+		};
+		let message = if undo.is_empty() {
+			format!("Failed to {} {}, so the batch was undone", action, failure)
+		} else {
+			for failure in undo {
+				warn!("Failed {}", failure);
+			}
+			format!(
+				"Failed to {} {}, and {} files could not be renamed back",
+				action,
+				failure,
+				undo.len()
+			)
+		};
+		self.notify(Severity::Error, message, None)
+	}
+
+	/// Pairs paths with the files of the library items at them, leaving out the others.
+	fn files_by_path(&self, paths: impl IntoIterator<Item = String>) -> Vec<(String, File)> {
+		paths
+			.into_iter()
+			.filter_map(|path| {
+				let file = self
+					.library
+					.iter()
+					.find(|item| item.path == path)?
+					.file
+					.clone();
+				Some((path, file))
+			})
+			.collect()
+	}
+
+	fn save_playlist(&self, name: &str) {
+		if let Some((name, entries)) = self.playlists.iter().find(|(n, _)| n == name).cloned() {
+			self.link.send_future(async move {
+				if let Err(err) = storage::put(PLAYLISTS_STORE, &name, &entries).await {
+					warn!("Failed to save playlist '{}': {:?}", name, err);
+				}
+				Msg::None
+			});
+		}
+	}
+
+	fn save_profiles(&self, profiles: Vec<Profile>) {
+		self.link.send_future(async move {
+			for profile in profiles {
+				if let Err(err) = storage::put(PROFILES_STORE, &profile.name, &profile.settings).await {
+					warn!("Failed to save profile '{}': {:?}", profile.name, err);
+				}
+			}
+			Msg::None
+		});
+	}
+
+	fn update_tags(&mut self, update: impl FnOnce(&mut Vec<String>)) -> ShouldRender {
+		let key = match self.current_item() {
+			Some(item) => item.key(),
+			None => return false,
+		};
+		let tags = self.tags.entry(key.clone()).or_default();
+		update(tags);
+		let tags = tags.clone();
+		if tags.is_empty() {
+			self.tags.remove(&key);
+		}
+		self.link.send_future(async move {
+			let result = if tags.is_empty() {
+				storage::delete(TAGS_STORE, &key).await
+			} else {
+				storage::put(TAGS_STORE, &key, &tags).await
+			};
+			if let Err(err) = result {
+				warn!("Failed to save tags for '{}': {:?}", key, err);
+			}
+			Msg::None
+		});
+		true
+	}
+
+	/// Progress of the selection being indexed, which can be canceled.
+	fn view_indexing(&self) -> Html {
+		let indexing = match &self.indexing {
+			Some(indexing) => indexing,
+			None => return html!(),
+		};
+		let progress = if indexing.total == 0 {
+			0.0
+		} else {
+			indexing.done as f64 / indexing.total as f64
+		};
+		html! {
+			<div class="absolute bottom-0 inset-x-32 md:inset-x-48 lg:inset-x-64 mb-32 lg:mb-28 p-4 rounded-lg bg-gray-800 bg-opacity-90 text-white text-3xl lg:text-xl flex flex-col select-none">
+				<div class="flex place-items-center">
+					<span class="flex-1 truncate">
+						{ format!("Indexing {} of {} files", indexing.done, indexing.total) }
+					</span>
+					<span class="cursor-pointer ml-4" title="Stop indexing" onclick=self.link.callback(|_| Msg::CancelIndexing)>
+						{ Icon::new_sized(IconKind::X, 32) }
+					</span>
+				</div>
+				<div class="h-1 mt-2 bg-white bg-opacity-20">
+					<div class="h-full bg-white" style=format!("width: {}%", progress * 100.0)></div>
+				</div>
+			</div>
+		}
+	}
+
+	/// Thumbnails of the items around the current one, which can be jumped to.
+	#[allow(clippy::unnecessary_operation)]
+	fn view_filmstrip(&self) -> Html {
+		let queue = match &self.queue {
+			Some(queue) if self.filmstrip_visible => queue,
+			_ => return html!(),
+		};
+		let items: Vec<(usize, Item)> = queue
+			.window(FILMSTRIP_RADIUS)
+			.map(|i| (i, queue.items()[i].clone()))
+			.collect();
+		html! {
+			<Filmstrip
+				class="absolute inset-x-0 bottom-0 mb-28 opacity-25 hover:opacity-100 transition duration-500"
+				current=queue.index()
+				items=items
+				onjump=self.link.callback(Msg::JumpTo)
+				thumbnail_offset=self.settings.thumbnail_offset
+			/>
+		}
+	}
+
+	/// Position slider of the queue, previewing the item under the pointer.
+	#[allow(clippy::unnecessary_operation)]
+	fn view_scrubber(&self) -> Html {
+		let queue = match &self.queue {
+			Some(queue) if queue.items().len() > 1 => queue,
+			_ => return html!(),
+		};
+		html! {
+			<Scrubber
+				class="absolute inset-x-0 bottom-0 mb-2 text-white opacity-25 hover:opacity-100 transition duration-500"
+				current=queue.index()
+				len=queue.items().len()
+				onjump=self.link.callback(Msg::JumpTo)
+				onpreview=self.link.callback(Msg::Preview)
+				preview=self.preview.and_then(|i| queue.items().get(i).cloned())
+				thumbnail_offset=self.settings.thumbnail_offset
+			/>
+		}
+	}
+
+	/// Buttons to keep or reject the current item, and to review the rejects, while culling.
+	fn view_culling(&self, item: &Item) -> Html {
+		if !self.culling {
+			return html!();
+		}
+		let verdict = self.verdicts.get(&item.path);
+		let rejects = self
+			.library
+			.iter()
+			.filter(|item| self.verdicts.get(&item.path) == Some(&Verdict::Reject))
+			.count();
+		html! {
+			<>
+				<div class=format!("cursor-pointer ml-4 {}", if verdict == Some(&Verdict::Keep) { "text-green-400" } else { "text-white text-opacity-50" })
+					title="Keep (K)" onclick=self.link.callback(|_| Msg::Judge(Verdict::Keep))>
+					{ Icon::new_sized(IconKind::Check, 32) }
+				</div>
+				<div class=format!("cursor-pointer ml-4 {}", if verdict == Some(&Verdict::Reject) { "text-red-400" } else { "text-white text-opacity-50" })
+					title="Reject (X)" onclick=self.link.callback(|_| Msg::Judge(Verdict::Reject))>
+					{ Icon::new_sized(IconKind::XCircle, 32) }
+				</div>
+				<div class="cursor-pointer ml-4 text-white flex place-items-center" title="Review rejects (R)" onclick=self.link.callback(|_| Msg::ShowRejects(true))>
+					{ Icon::new_sized(IconKind::Trash, 32) }
+					<span class="ml-1">{ rejects }</span>
+				</div>
+			</>
+		}
+	}
+
+	/// Covers the page while files are dragged over it, taking the drop.
+	fn view_drop_zone(&self) -> Html {
+		if !self.dragging {
+			return html!();
+		}
+		html! {
+			<div
+				class="absolute inset-0 m-4 border-4 border-dashed rounded-lg bg-black bg-opacity-70 text-white text-5xl lg:text-3xl flex place-content-center place-items-center select-none"
+				ondragover=self.link.callback(|e: DragEvent| {
+					// Dropping is only allowed where dragging over is canceled.
+					e.prevent_default();
+					Msg::None
+				})
+				ondragleave=self.link.callback(|_| Msg::Dragging(false))
+				ondrop=self.link.callback(|e: DragEvent| {
+					e.prevent_default();
+					Msg::DropEntries(e.data_transfer().map(|t| library::entries(&t)).unwrap_or_default())
+				})
+			>
+				{if self.queue.is_some() { "Drop files or folders to add them" } else { "Drop files or folders to view them" }}
+			</div>
+		}
+	}
 }
 
 impl Component for Home {
 	type Message = Msg;
-	type Properties = ();
+	type Properties = Props;
 
-	fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+	fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
 		let on_ended_cb = link.callback(|_| Msg::NextFile);
+		link.send_future(async {
+			match storage::get_all(MARKS_STORE).await {
+				Ok(marks) => Msg::LoadMarks(marks),
+				Err(err) => {
+					warn!("Failed to load marks: {:?}", err);
+					Msg::None
+				}
+			}
+		});
+		link.send_future(async {
+			match storage::get_all(PLAYLISTS_STORE).await {
+				Ok(playlists) => Msg::LoadPlaylists(playlists),
+				Err(err) => {
+					warn!("Failed to load playlists: {:?}", err);
+					Msg::None
+				}
+			}
+		});
+		link.send_future(async {
+			match storage::get_all::<Settings>(PROFILES_STORE).await {
+				Ok(profiles) => Msg::LoadProfiles(
+					profiles
+						.into_iter()
+						.map(|(name, mut settings)| {
+							settings.normalize();
+							Profile { name, settings }
+						})
+						.collect(),
+				),
+				Err(err) => {
+					warn!("Failed to load profiles: {:?}", err);
+					Msg::None
+				}
+			}
+		});
+		link.send_future(async {
+			match storage::get_all(TAGS_STORE).await {
+				Ok(tags) => Msg::LoadTags(tags),
+				Err(err) => {
+					warn!("Failed to load tags: {:?}", err);
+					Msg::None
+				}
+			}
+		});
+		let (settings, ignored) = query::decode(&props.query);
+		if !ignored.is_empty() {
+			link.send_message(Msg::Warn(format!(
+				"Ignored unknown settings in link: {}",
+				ignored.join(", ")
+			)));
+		}
+		let mut thumbnailer = Thumbnailer::dispatcher();
+		thumbnailer.send(thumbnailer::Request::CacheSize(
+			settings.thumbnail_cache_size,
+		));
 		Self {
-			files: None,
-			index: 0,
+			active_playlist: None,
+			culling: false,
+			dragging: false,
+			duplicates: None,
+			duplicates_visible: false,
+			files_busy: false,
+			filmstrip_visible: false,
+			finder: Finder::bridge(link.callback(Msg::Found)),
+			finding: None,
+			folder_handles: HashMap::new(),
+			held: false,
+			indexer: Indexer::bridge(link.callback(Msg::Indexed)),
+			indexing: None,
 			keydown_listener: None,
+			library: Vec::new(),
 			link,
+			marks: HashMap::new(),
+			moves: Vec::new(),
+			next_batch: 0,
+			next_notification: 0,
+			next_source: 0,
 			node_ref: NodeRef::default(),
+			notifications: Vec::new(),
 			on_ended_cb,
-			settings: Settings::new(),
-			warning_message: None,
+			playlist_manager_visible: false,
+			playlists: Vec::new(),
+			preview: None,
+			profile: None,
+			profiles: Vec::new(),
+			queue: None,
+			rejects_visible: false,
+			renames: Vec::new(),
+			renamer_visible: false,
+			route: props.route,
+			router: RouteAgentDispatcher::new(),
+			settings,
+			soundtrack: None,
+			soundtrack_duration: None,
+			sources: Vec::new(),
+			tag_editor_visible: false,
+			tags: HashMap::new(),
+			thumbnailer,
+			verdicts: HashMap::new(),
 		}
 	}
 
-	fn change(&mut self, _: Self::Properties) -> ShouldRender {
-		false
+	fn change(&mut self, props: Self::Properties) -> ShouldRender {
+		if self.route == props.route {
+			return false;
+		}
+		self.route = props.route;
+		self.apply_route()
 	}
 
 	fn update(&mut self, msg: Self::Message) -> ShouldRender {
 		match msg {
-			Msg::DropWarning => {
-				self.warning_message = None;
+			Msg::AddTag(tag) => self.update_tags(|tags| {
+				if !tags.contains(&tag) {
+					tags.push(tag);
+				}
+			}),
+			Msg::AddToPlaylist => {
+				let path = match self.current_item() {
+					Some(item) => item.path.clone(),
+					None => return false,
+				};
+				let name = match &self.active_playlist {
+					Some(name) => name.clone(),
+					None => {
+						self.playlist_manager_visible = true;
+						return self.update(Msg::Warn(String::from(
+							"Create or select a playlist to add items to",
+						)));
+					}
+				};
+				if let Some((_, entries)) = self.playlists.iter_mut().find(|(n, _)| *n == name) {
+					entries.push(path);
+				}
+				self.save_playlist(&name);
 				true
 			}
-			Msg::IndexDirectory(files) => {
-				if files.len() > 0 {
-					self.files = Some(files);
-					self.index = 0;
-					if let Some(w) = window() {
-						let onkeydown = self
-							.link
-							.callback(|e: KeyboardEvent| match e.key().as_str() {
-								"ArrowLeft" => Msg::PreviousFile,
-								"ArrowRight" => Msg::NextFile,
-								"Escape" => Msg::Quit,
-								_ => Msg::None,
-							});
-						self.keydown_listener = Some(EventListener::new(&w, "keydown", move |e| {
-							onkeydown.emit(e.dyn_ref::<KeyboardEvent>().unwrap().clone())
-						}));
+			Msg::AddItems(items) => {
+				self.begin_indexing(items.len());
+				let total = items.len();
+				self.add_indexed(items, total);
+				self.finish_indexing(false)
+			}
+			Msg::CreatePlaylist(name) => {
+				if !self.playlists.iter().any(|(n, _)| *n == name) {
+					self.playlists.push((name.clone(), Vec::new()));
+					self.playlists.sort_by(|(a, _), (b, _)| a.cmp(b));
+					self.save_playlist(&name);
+				}
+				self.active_playlist = Some(name);
+				true
+			}
+			Msg::CancelIndexing => self.finish_indexing(true),
+			Msg::CancelDuplicates => {
+				self.finder.send(finder::Request::Cancel);
+				self.finding = None;
+				true
+			}
+			Msg::CycleFilterRating => {
+				self.settings.filter_rating = (self.settings.filter_rating + 1) % (MAX_RATING + 1);
+				self.requeue()
+			}
+			Msg::CloseView => {
+				let route = self.viewer_route();
+				self.navigate(route);
+				false
+			}
+			Msg::CopySettingsLink => {
+				let mut link = match browser::base_url() {
+					Ok(url) => url,
+					Err(err) => return self.update(Msg::Error(err)),
+				};
+				let query = query::encode(&self.settings);
+				if !query.is_empty() {
+					link = format!("{}?{}", link, query);
+				}
+				self.link.send_future(async move {
+					match browser::copy_text(&link).await {
+						Ok(()) => Msg::Notify(
+							Severity::Info,
+							String::from("Copied a link with these settings"),
+							None,
+						),
+						Err(err) => Msg::Error(err),
+					}
+				});
+				false
+			}
+			Msg::ClearNotifications => {
+				self.notifications.clear();
+				true
+			}
+			Msg::DeletePlaylist(name) => {
+				let entries = match self.playlists.iter().position(|(n, _)| *n == name) {
+					Some(i) => self.playlists.remove(i).1,
+					None => return false,
+				};
+				if self.active_playlist.as_ref() == Some(&name) {
+					self.active_playlist = None;
+				}
+				let deleted = name.clone();
+				self.link.send_future(async move {
+					if let Err(err) = storage::delete(PLAYLISTS_STORE, &deleted).await {
+						warn!("Failed to delete playlist '{}': {:?}", deleted, err);
 					}
+					Msg::None
+				});
+				let message = format!("Deleted playlist '{}'", name);
+				let undo = self
+					.link
+					.callback(move |_| Msg::RestorePlaylist(name.clone(), entries.clone()));
+				self.notify(Severity::Info, message, Some((String::from("Undo"), undo)))
+			}
+			Msg::DeleteFiles(paths) => {
+				let files = self.files_by_path(paths);
+				let targets = self.writable(files, |(path, _)| path);
+				self.files_busy = true;
+				self.link.send_future(async move {
+					let mut deleted = Vec::new();
+					let mut failed = Vec::new();
+					for ((path, file), folder) in targets {
+						let result = match cull::split_top(&path) {
+							Some((_, rest)) => files::delete_file(&folder, rest, &file).await,
+							None => continue,
+						};
+						match result {
+							Ok(()) => deleted.push(path),
+							Err(err) => failed.push(format!("'{}': {}", path, err)),
+						}
+					}
+					Msg::Deleted(deleted, failed)
+				});
+				true
+			}
+			Msg::Deleted(deleted, failed) => {
+				self.files_busy = false;
+				self.report_failures("delete", failed);
+				if deleted.is_empty() {
 					return true;
 				}
-				self.warning_message = Some(String::from("No files could be found"));
-				warn!("{}", &self.warning_message.as_ref().unwrap());
+				for path in &deleted {
+					self.verdicts.remove(path);
+				}
+				let message = format!("Deleted {} files", deleted.len());
+				self.notify(Severity::Info, message, None);
+				let deleted: HashSet<String> = deleted.into_iter().collect();
+				if !self.retain_items(|item| !deleted.contains(&item.path)) {
+					return self.update(Msg::Quit);
+				}
 				true
 			}
-			Msg::NextFile => {
-				if let Some(files) = &self.files {
-					self.index = (self.index + 1).rem_euclid(files.len() as isize);
+			Msg::DismissNotification(id) => {
+				if let Some(notification) = self.notifications.iter_mut().find(|n| n.id == id) {
+					notification.dismissed = true;
 					return true;
 				}
 				false
 			}
-			Msg::PreviousFile => {
-				if let Some(files) = &self.files {
-					self.index = (self.index - 1).rem_euclid(files.len() as isize);
+			Msg::Dragging(dragging) => {
+				let changed = self.dragging != dragging;
+				self.dragging = dragging;
+				changed
+			}
+			Msg::DropEntries(entries) => {
+				self.dragging = false;
+				self.link.send_future(async move {
+					match library::index_entries(entries).await {
+						Ok(items) => Msg::AddItems(items),
+						Err(err) => Msg::Error(err),
+					}
+				});
+				true
+			}
+			Msg::Error(err) => self.notify(Severity::Error, err.to_string(), None),
+			Msg::Escape => {
+				if matches!(
+					self.route,
+					AppRoute::About | AppRoute::Grid(_) | AppRoute::Settings
+				) {
+					return self.update(Msg::CloseView);
+				}
+				if self.duplicates_visible || self.rejects_visible || self.renamer_visible {
+					self.duplicates_visible = false;
+					self.rejects_visible = false;
+					self.renamer_visible = false;
+					return true;
+				}
+				if self.tag_editor_visible || self.playlist_manager_visible {
+					self.playlist_manager_visible = false;
+					self.tag_editor_visible = false;
+					return true;
+				}
+				self.update(Msg::Quit)
+			}
+			Msg::ExportPlaylist(format) => {
+				if let Some(queue) = &self.queue {
+					let paths: Vec<&str> = queue
+						.items()
+						.iter()
+						.map(|item| item.path.as_str())
+						.collect();
+					let name = paths
+						.first()
+						.and_then(|p| p.split('/').next())
+						.filter(|folder| !folder.is_empty())
+						.unwrap_or("playlist");
+					let name = format!("{}.{}", name, format.extension());
+					if let Err(err) = browser::download(&name, format.mime(), &format.write(&paths)) {
+						return self.notify(
+							Severity::Error,
+							format!("Failed to export playlist: {}", err),
+							None,
+						);
+					}
+				}
+				false
+			}
+			Msg::FindDuplicates => {
+				self.finder.send(finder::Request::Find {
+					items: self.library.clone(),
+					offset: self.settings.thumbnail_offset,
+				});
+				self.duplicates = None;
+				self.finding = Some((0, 0));
+				true
+			}
+			Msg::FirstFile => self.move_in_queue(|queue| {
+				queue.jump(0);
+			}),
+			Msg::FolderGranted(top, folder) => {
+				if folder.name() != top {
+					return self.update(Msg::Warn(format!(
+						"Select the folder '{}' to allow changes to its files",
+						top
+					)));
+				}
+				// A folder of the same name elsewhere, or files changed since, are told about up front.
+				let rejects: Vec<(String, File)> = self
+					.rejects()
+					.into_iter()
+					.filter(|item| cull::split_top(&item.path).is_some_and(|(t, _)| t == top))
+					.map(|item| (item.path, item.file))
+					.collect();
+				self.folder_handles.insert(top.clone(), folder.clone());
+				self.link.send_future(async move {
+					let mut differing = Vec::new();
+					for (path, file) in rejects {
+						if let Some((_, rest)) = cull::split_top(&path) {
+							if files::check_file(&folder, rest, &file).await.is_err() {
+								differing.push(path);
+							}
+						}
+					}
+					Msg::FolderChecked(top, differing)
+				});
+				true
+			}
+			Msg::FolderChecked(top, differing) => {
+				let message = match differing.as_slice() {
+					[] => return false,
+					[path] => format!(
+						"'{}' differs from the file on disk, and will be left untouched",
+						path
+					),
+					_ => format!(
+						"{} rejects in '{}' differ from the files on disk, and will be left untouched",
+						differing.len(),
+						top
+					),
+				};
+				self.update(Msg::Warn(message))
+			}
+			Msg::Found(finder::Response::Progress { done, total }) => {
+				self.finding = Some((done, total));
+				true
+			}
+			Msg::Found(finder::Response::Found(groups)) => {
+				self.finding = None;
+				let count = groups.len();
+				self.duplicates = Some(groups);
+				if self.duplicates_visible {
 					return true;
 				}
+				let message = format!("Found {} groups of copies", count);
+				let show = self.link.callback(|_| Msg::ShowDuplicates(true));
+				self.notify(Severity::Info, message, Some((String::from("Show"), show)))
+			}
+			Msg::GrantFolder(top) => {
+				self.link.send_future(async move {
+					match files::pick_folder().await {
+						Ok(folder) => Msg::FolderGranted(top, folder),
+						Err(err) => Msg::Error(err),
+					}
+				});
+				false
+			}
+			Msg::HideFiles(paths) => {
+				let paths: HashSet<String> = paths.into_iter().collect();
+				let hidden: Vec<Item> = self
+					.library
+					.iter()
+					.filter(|item| paths.contains(&item.path))
+					.cloned()
+					.collect();
+				if hidden.is_empty() {
+					return false;
+				}
+				if !self.retain_items(|item| !paths.contains(&item.path)) {
+					return self.update(Msg::Quit);
+				}
+				let message = format!("Hid {} files", hidden.len());
+				let undo = self
+					.link
+					.callback(move |_| Msg::UnhideFiles(hidden.clone()));
+				self.notify(Severity::Info, message, Some((String::from("Undo"), undo)))
+			}
+			Msg::ImportPlaylist(file) => {
+				let name = file.name();
+				match Format::from_name(&name) {
+					Some(format) => {
+						let retry_file = file.clone();
+						let retry = self
+							.link
+							.callback(move |_| Msg::ImportPlaylist(retry_file.clone()));
+						self.link.send_future(async move {
+							match browser::read_text(&file).await {
+								Ok(contents) => Msg::LoadPlaylist(format, contents),
+								Err(err) => Msg::Notify(
+									Severity::Error,
+									format!("Failed to read playlist '{}': {}", name, err),
+									Some((String::from("Retry"), retry)),
+								),
+							}
+						})
+					}
+					None => return self.update(Msg::Warn(format!("Unsupported playlist format '{}'", name))),
+				}
 				false
 			}
+			Msg::Index(files) => {
+				self.begin_indexing(files.length() as usize);
+				self.indexer.send(Request::Index(files));
+				true
+			}
+			Msg::Indexed(Response::Batch { items, done }) => self.add_indexed(items, done),
+			Msg::Indexed(Response::Finished) => self.finish_indexing(false),
+			Msg::Judge(verdict) => {
+				let path = match self.current_item() {
+					Some(item) if self.culling => item.path.clone(),
+					_ => return false,
+				};
+				if self.verdicts.get(&path) == Some(&verdict) {
+					self.verdicts.remove(&path);
+					return true;
+				}
+				self.verdicts.insert(path, verdict);
+				self.move_in_queue(Queue::next)
+			}
+			Msg::JumpTo(index) => self.move_in_queue(|queue| {
+				queue.jump(index);
+			}),
+			Msg::KeepRejects(paths) => {
+				for path in paths {
+					self.verdicts.insert(path, Verdict::Keep);
+				}
+				true
+			}
+			Msg::LastFile => self.move_in_queue(|queue| {
+				queue.jump(queue.items().len() - 1);
+			}),
+			Msg::LoadMarks(marks) => {
+				self.marks = marks.into_iter().collect();
+				true
+			}
+			Msg::LoadPlaylist(format, contents) => {
+				let mut files = Vec::new();
+				let mut unresolved = Vec::new();
+				for location in format.parse(&contents) {
+					match playlist::resolve(
+						&location,
+						self.library.iter().map(|item| item.path.as_str()),
+					) {
+						Some(i) => files.push(self.library[i].clone()),
+						None => unresolved.push(location),
+					}
+				}
+				for location in &unresolved {
+					warn!("Playlist entry '{}' could not be found", location);
+				}
+				self.queue = match Queue::new(files) {
+					Some(queue) => Some(queue),
+					None => {
+						return self.update(Msg::Warn(String::from(
+							"No playlist entries could be found",
+						)))
+					}
+				};
+				let route = self.viewer_route();
+				self.navigate(route);
+				if !unresolved.is_empty() {
+					return self.update(Msg::Warn(format!(
+						"{} playlist entries could not be found",
+						unresolved.len()
+					)));
+				}
+				true
+			}
+			Msg::LoadPlaylists(playlists) => {
+				self.playlists = playlists;
+				true
+			}
+			Msg::LoadTags(tags) => {
+				self.tags = tags.into_iter().collect();
+				true
+			}
+			Msg::MovePlaylistEntry(name, from, to) => {
+				if let Some((_, entries)) = self.playlists.iter_mut().find(|(n, _)| *n == name) {
+					if from < entries.len() && to < entries.len() {
+						let entry = entries.remove(from);
+						entries.insert(to, entry);
+					}
+				}
+				self.save_playlist(&name);
+				true
+			}
+			Msg::MoveRejects(paths) => {
+				let moves: Vec<(Move, File)> = self
+					.files_by_path(paths)
+					.into_iter()
+					.map(|(path, file)| {
+						let m = Move {
+							to: cull::rejected_path(&path),
+							from: path,
+						};
+						(m, file)
+					})
+					.collect();
+				let targets = self
+					.writable(moves, |(m, _)| &m.from)
+					.into_iter()
+					.map(|((m, file), folder)| (m, file, folder))
+					.collect();
+				self.files_busy = true;
+				self.link.send_future(async move {
+					let (moved, failed) = files::move_files(targets).await;
+					Msg::Moved(moved, failed)
+				});
+				true
+			}
+			Msg::Moved(moved, failed) => {
+				self.files_busy = false;
+				self.report_failures("move", failed);
+				let batch: Vec<(Move, Item)> = moved
+					.into_iter()
+					.filter_map(|(m, file)| {
+						let item = self.library.iter().find(|item| item.path == m.from)?;
+						let moved = Item {
+							file,
+							path: m.to.clone(),
+							source: item.source,
+							still: item.still,
+						};
+						Some((m, moved))
+					})
+					.collect();
+				if batch.is_empty() {
+					return true;
+				}
+				let id = self.next_batch;
+				self.next_batch += 1;
+				let numbered = batch
+					.iter()
+					.filter(|(m, _)| {
+						cull::split_top(&m.from)
+							.is_some_and(|(top, rest)| m.to != format!("{}/{}", top, cull::rejected_path(rest)))
+					})
+					.count();
+				let message = format!(
+					"Moved {} rejects into '{}' folders{}",
+					batch.len(),
+					REJECTS_FOLDER,
+					Self::numbered_note(numbered)
+				);
+				let moved: HashSet<String> = batch.iter().map(|(m, _)| m.from.clone()).collect();
+				for path in &moved {
+					self.verdicts.remove(path);
+				}
+				self.moves.push((id, batch));
+				let undo = self.link.callback(move |_| Msg::UndoMove(id));
+				self.notify(Severity::Info, message, Some((String::from("Undo"), undo)));
+				if !self.retain_items(|item| !moved.contains(&item.path)) {
+					return self.update(Msg::Quit);
+				}
+				true
+			}
+			Msg::MovedBack(items, numbered, failed) => {
+				self.files_busy = false;
+				self.report_failures("move back", failed);
+				if items.is_empty() {
+					return true;
+				}
+				// Items moved back are still rejects, to be reviewed again.
+				for item in &items {
+					self.verdicts.insert(item.path.clone(), Verdict::Reject);
+				}
+				let message = format!(
+					"Moved {} rejects back{}",
+					items.len(),
+					Self::numbered_note(numbered)
+				);
+				self.restore_items(items);
+				self.notify(Severity::Info, message, None)
+			}
+			Msg::NextFile => self.move_in_queue(Queue::next),
+			Msg::NextPage => self.move_page(true),
+			Msg::Notify(severity, message, action) => self.notify(severity, message, action),
+			Msg::OpenFolder(folder) => {
+				self.navigate(AppRoute::grid(&folder));
+				false
+			}
+			Msg::OpenItem(path) => {
+				self.navigate(AppRoute::view(&path));
+				false
+			}
+			Msg::PlayPlaylist(name) => {
+				let entries = match self.playlists.iter().find(|(n, _)| *n == name) {
+					Some((_, entries)) => entries.clone(),
+					None => return false,
+				};
+				let mut files = Vec::new();
+				let mut missing = 0;
+				for path in &entries {
+					match self.library.iter().find(|item| item.path == *path) {
+						Some(item) => files.push(item.clone()),
+						None => {
+							warn!("Playlist entry '{}' could not be found", path);
+							missing += 1;
+						}
+					}
+				}
+				self.queue = match Queue::new(files) {
+					Some(queue) => Some(queue),
+					None => {
+						return self.update(Msg::Warn(format!(
+							"No entries of playlist '{}' could be found",
+							name
+						)))
+					}
+				};
+				let route = self.viewer_route();
+				self.navigate(route);
+				self.active_playlist = Some(name);
+				if missing > 0 {
+					return self.update(Msg::Warn(format!(
+						"{} playlist entries could not be found",
+						missing
+					)));
+				}
+				true
+			}
+			Msg::PlayTags(selected, tag_match) => {
+				if self.queue.is_none() {
+					return false;
+				}
+				let files: Vec<Item> = self
+					.library
+					.iter()
+					.filter(|item| self.passes_filters(item))
+					.filter(|item| selected.is_empty() || tag_match.matches(&selected, &self.item_tags(item)))
+					.cloned()
+					.collect();
+				self.queue = match Queue::new(files) {
+					Some(queue) => Some(queue),
+					None => return self.update(Msg::Warn(String::from("No files match the selected tags"))),
+				};
+				let route = self.viewer_route();
+				self.navigate(route);
+				true
+			}
+			Msg::Preview(index) => {
+				self.preview = index;
+				true
+			}
+			Msg::PreviousFile => self.move_in_queue(Queue::previous),
+			Msg::PreviousPage => self.move_page(false),
 			Msg::Quit => {
-				self.files = None;
-				self.index = 0;
+				if self.indexing.take().is_some() {
+					self.indexer.send(Request::Cancel);
+				}
+				if self.finding.take().is_some() {
+					self.finder.send(finder::Request::Cancel);
+				}
+				self.held = false;
+				self.navigate(AppRoute::Home);
 				self.keydown_listener = None;
+				self.library.clear();
+				self.playlist_manager_visible = false;
+				self.queue = None;
+				self.soundtrack = None;
+				self.soundtrack_duration = None;
+				self.sources.clear();
+				self.tag_editor_visible = false;
+				self.culling = false;
+				self.duplicates = None;
+				self.duplicates_visible = false;
+				self.moves.clear();
+				self.rejects_visible = false;
+				self.renames.clear();
+				self.renamer_visible = false;
+				self.verdicts.clear();
+				true
+			}
+			Msg::RemoveFromPlaylist(name, index) => {
+				if let Some((_, entries)) = self.playlists.iter_mut().find(|(n, _)| *n == name) {
+					if index < entries.len() {
+						entries.remove(index);
+					}
+				}
+				self.save_playlist(&name);
+				true
+			}
+			Msg::RemoveSource(id) => {
+				if self.indexing.as_ref().is_some_and(|i| i.source == id) {
+					self.indexing = None;
+					self.indexer.send(Request::Cancel);
+				}
+				let source = match self.sources.iter().position(|s| s.id == id) {
+					Some(i) => self.sources.remove(i),
+					None => return false,
+				};
+				if !self.retain_items(|item| item.source != id) {
+					return self.update(Msg::Quit);
+				}
+				self.notify(Severity::Info, format!("Removed '{}'", source.name), None)
+			}
+			Msg::RemoveTag(tag) => self.update_tags(|tags| tags.retain(|t| *t != tag)),
+			Msg::Rename(renames) => {
+				let steps = rename::steps(&renames);
+				let count = steps.len();
+				let targets = self.writable(steps, |m| &m.from);
+				// Batches are applied whole, so none of it is if some folder cannot be changed.
+				if targets.len() < count {
+					return self.update(Msg::Warn(String::from(
+						"Allow changes to the folders of every file to rename them",
+					)));
+				}
+				let files = self
+					.files_by_path(renames.into_iter().map(|m| m.from))
+					.into_iter()
+					.collect();
+				self.files_busy = true;
+				self.link.send_future(async move {
+					let (renamed, failed) = files::rename_files(targets, files).await;
+					Msg::Renamed(renamed, failed)
+				});
+				true
+			}
+			Msg::Renamed(renamed, failed) => {
+				self.files_busy = false;
+				if !failed.is_empty() {
+					// Files renamed back are new files all the same.
+					self.apply_renames(renamed);
+					return self.report_stopped_renames("rename", failed);
+				}
+				if renamed.is_empty() {
+					return true;
+				}
+				let steps: Vec<Move> = renamed.iter().map(|(m, _)| m.clone()).collect();
+				let count = steps
+					.iter()
+					.filter(|m| !rename::is_temporary(&m.to))
+					.count();
+				self.apply_renames(renamed);
+				let id = self.next_batch;
+				self.next_batch += 1;
+				self.renames.push((id, steps));
+				let undo = self.link.callback(move |_| Msg::UndoRename(id));
+				let message = format!("Renamed {} files", count);
+				self.notify(Severity::Info, message, Some((String::from("Undo"), undo)))
+			}
+			Msg::RenamedBack(batch, renamed, failed) => {
+				self.files_busy = false;
+				if !failed.is_empty() {
+					self.apply_renames(renamed);
+					self.renames.push(batch);
+					self.renames.sort_by_key(|(id, _)| *id);
+					return self.report_stopped_renames("rename back", failed);
+				}
+				if renamed.is_empty() {
+					return true;
+				}
+				let count = renamed
+					.iter()
+					.filter(|(m, _)| !rename::is_temporary(&m.to))
+					.count();
+				self.apply_renames(renamed);
+				self.notify(
+					Severity::Info,
+					format!("Renamed {} files back", count),
+					None,
+				)
+			}
+			Msg::RestorePlaylist(name, entries) => {
+				if !self.playlists.iter().any(|(n, _)| *n == name) {
+					self.playlists.push((name.clone(), entries));
+					self.playlists.sort_by(|(a, _), (b, _)| a.cmp(b));
+					self.save_playlist(&name);
+				}
+				true
+			}
+			Msg::SelectPlaylist(name) => {
+				self.active_playlist = Some(name);
+				true
+			}
+			Msg::SelectSoundtrack(soundtrack) => {
+				self.soundtrack = soundtrack;
+				self.soundtrack_duration = None;
+				true
+			}
+			Msg::SetRating(rating) => self.update_mark(|mark| mark.rating = rating),
+			Msg::ShowAbout => {
+				self.navigate(AppRoute::About);
+				false
+			}
+			Msg::ShowDuplicates(visible) => {
+				if visible && self.duplicates.is_none() && self.finding.is_none() {
+					self.update(Msg::FindDuplicates);
+				}
+				self.duplicates_visible = visible;
+				true
+			}
+			Msg::ShowGrid => {
+				let folder = self
+					.current_item()
+					.map_or("", |item| folder_of(&item.path))
+					.to_string();
+				self.update(Msg::OpenFolder(folder))
+			}
+			Msg::ShowRejects(visible) => {
+				self.rejects_visible = visible;
+				true
+			}
+			Msg::ShowRenamer(visible) => {
+				self.renamer_visible = visible;
+				true
+			}
+			Msg::ShowSettings(visible) => {
+				let route = if visible {
+					AppRoute::Settings
+				} else {
+					self.viewer_route()
+				};
+				self.navigate(route);
+				false
+			}
+			Msg::SoundtrackDuration(duration) => {
+				self.soundtrack_duration = Some(duration);
+				self.settings.soundtrack_sync
+			}
+			Msg::ToggleCulling => {
+				self.culling = !self.culling;
+				// Items are judged one at a time, rather than shown as a slideshow.
+				if self.culling {
+					self.held = true;
+				}
+				true
+			}
+			Msg::ToggleFavorite => self.update_mark(|mark| mark.favorite = !mark.favorite),
+			Msg::ToggleFilmstrip => {
+				self.filmstrip_visible = !self.filmstrip_visible;
+				true
+			}
+			Msg::ToggleFilterFavorites => {
+				self.settings.filter_favorites = !self.settings.filter_favorites;
+				self.requeue()
+			}
+			Msg::ToggleHold => {
+				self.held = !self.held;
+				true
+			}
+			Msg::TogglePlaylistManager => {
+				self.playlist_manager_visible = !self.playlist_manager_visible;
+				true
+			}
+			Msg::ToggleTagEditor => {
+				self.tag_editor_visible = !self.tag_editor_visible;
+				true
+			}
+			Msg::UndoMove(id) => {
+				let batch = match self.moves.iter().position(|(i, _)| *i == id) {
+					Some(i) if !self.files_busy => self.moves.remove(i).1,
+					_ => return false,
+				};
+				// Items moved back take the place of the ones moved, by the path they were moved to.
+				let mut moved: HashMap<String, (String, Item)> = HashMap::new();
+				let mut moves = Vec::new();
+				for (m, item) in batch {
+					moves.push((
+						Move {
+							from: m.to.clone(),
+							to: m.from.clone(),
+						},
+						item.file.clone(),
+					));
+					moved.insert(m.to, (m.from, item));
+				}
+				let targets = self
+					.writable(moves, |(m, _)| &m.from)
+					.into_iter()
+					.map(|((m, file), folder)| (m, file, folder))
+					.collect();
+				self.files_busy = true;
+				self.link.send_future(async move {
+					let (back, failed) = files::move_files(targets).await;
+					let mut items = Vec::new();
+					let mut numbered = 0;
+					for (m, file) in back {
+						if let Some((path, item)) = moved.remove(&m.from) {
+							if m.to != path {
+								numbered += 1;
+							}
+							items.push(Item {
+								file,
+								path: m.to,
+								..item
+							});
+						}
+					}
+					Msg::MovedBack(items, numbered, failed)
+				});
+				true
+			}
+			Msg::UndoRename(id) => {
+				let steps = match self.renames.iter().position(|(i, _)| *i == id) {
+					Some(i) if !self.files_busy => self.renames.remove(i).1,
+					_ => return false,
+				};
+				let undo = rename::reversed(&steps);
+				let count = undo.len();
+				let targets = self.writable(undo, |m| &m.from);
+				if targets.len() < count {
+					self.renames.push((id, steps));
+					self.renames.sort_by_key(|(id, _)| *id);
+					return self.update(Msg::Warn(String::from(
+						"Allow changes to the folders of every file to rename them back",
+					)));
+				}
+				// The steps end at the paths of library items, other than temporary names.
+				let files = self
+					.files_by_path(
+						steps
+							.iter()
+							.filter(|m| !rename::is_temporary(&m.to))
+							.map(|m| m.to.clone()),
+					)
+					.into_iter()
+					.collect();
+				self.files_busy = true;
+				self.link.send_future(async move {
+					let (renamed, failed) = files::rename_files(targets, files).await;
+					Msg::RenamedBack((id, steps), renamed, failed)
+				});
+				true
+			}
+			Msg::AddRule(rule) => {
+				self.settings.rules.push(rule);
+				true
+			}
+			Msg::ConfigSoundtrackFade(fade) => {
+				self.settings.soundtrack_fade = Duration::from_secs(fade);
 				true
 			}
-			Msg::ConfigAudioAutoplayDelay(delay) => {
-				self.settings.config_audio_autoplay_delay = Duration::from_secs(delay);
+			Msg::ConfigSoundtrackVolume(volume) => {
+				self.settings.soundtrack_volume = volume.min(100);
 				true
 			}
-			Msg::ConfigImageAutoplayDelay(delay) => {
-				self.settings.config_image_autoplay_delay = Duration::from_secs(delay);
+			Msg::ConfigThumbnailCacheSize(megabytes) => {
+				self.settings.thumbnail_cache_size = megabytes;
+				self
+					.thumbnailer
+					.send(thumbnailer::Request::CacheSize(megabytes));
 				true
 			}
-			Msg::ConfigVideoAutoplayDelay(delay) => {
-				self.settings.config_video_autoplay_delay = Duration::from_secs(delay);
+			Msg::ConfigPageSize(size) => {
+				self.settings.page_size = size.max(1);
 				true
 			}
-			Msg::ToggleAudio => {
-				self.settings.toggle_audio = !self.settings.toggle_audio;
+			Msg::ConfigThumbnailOffset(offset) => {
+				self.settings.thumbnail_offset = Duration::from_secs(offset);
 				true
 			}
-			Msg::ToggleAudioAutoplay => {
-				self.settings.toggle_audio_autoplay = !self.settings.toggle_audio_autoplay;
+			Msg::DeleteProfile(name) => {
+				let profile = match self.profiles.iter().position(|p| p.name == name) {
+					Some(i) => self.profiles.remove(i),
+					None => return false,
+				};
+				if self.profile.as_ref() == Some(&name) {
+					self.profile = None;
+				}
+				let deleted = name.clone();
+				self.link.send_future(async move {
+					if let Err(err) = storage::delete(PROFILES_STORE, &deleted).await {
+						warn!("Failed to delete profile '{}': {:?}", deleted, err);
+					}
+					Msg::None
+				});
+				let message = format!("Deleted profile '{}'", name);
+				let undo = self
+					.link
+					.callback(move |_| Msg::RestoreProfile(profile.clone()));
+				self.notify(Severity::Info, message, Some((String::from("Undo"), undo)))
+			}
+			Msg::ExportProfiles => {
+				if let Err(err) = browser::download(
+					profile::FILE_NAME,
+					profile::MIME,
+					&profile::export(&self.profiles),
+				) {
+					return self.notify(
+						Severity::Error,
+						format!("Failed to export profiles: {}", err),
+						None,
+					);
+				}
+				false
+			}
+			Msg::ImportProfiles(file) => {
+				let name = file.name();
+				self.link.send_future(async move {
+					match browser::read_text(&file).await {
+						Ok(contents) => Msg::LoadProfileFile(contents),
+						Err(err) => Msg::Notify(
+							Severity::Error,
+							format!("Failed to read profiles '{}': {}", name, err),
+							None,
+						),
+					}
+				});
+				false
+			}
+			Msg::LoadProfileFile(contents) => match profile::import(&contents) {
+				Ok(profiles) => {
+					let message = format!("Imported {} profiles", profiles.len());
+					self.save_profiles(profiles.clone());
+					profile::merge(&mut self.profiles, profiles);
+					self.notify(Severity::Info, message, None)
+				}
+				Err(err) => self.notify(
+					Severity::Error,
+					format!("Failed to import profiles: {}", err),
+					None,
+				),
+			},
+			Msg::LoadProfiles(profiles) => {
+				profile::merge(&mut self.profiles, profiles);
 				true
 			}
-			Msg::ToggleImage => {
-				self.settings.toggle_image = !self.settings.toggle_image;
+			Msg::RemoveRule(index) => {
+				// Catch-all rules can only be disabled, never removed.
+				if self
+					.settings
+					.rules
+					.get(index)
+					.is_some_and(|rule| rule.matcher.is_some())
+				{
+					self.settings.rules.remove(index);
+					return true;
+				}
+				false
+			}
+			Msg::RestoreProfile(profile) => {
+				self.save_profiles(vec![profile.clone()]);
+				profile::merge(&mut self.profiles, vec![profile]);
 				true
 			}
-			Msg::ToggleImageAutoplay => {
-				self.settings.toggle_image_autoplay = !self.settings.toggle_image_autoplay;
+			Msg::SaveProfile(name) => {
+				let profile = Profile {
+					name: name.clone(),
+					settings: self.settings.clone(),
+				};
+				self.save_profiles(vec![profile.clone()]);
+				profile::merge(&mut self.profiles, vec![profile]);
+				self.profile = Some(name);
 				true
 			}
-			Msg::ToggleVideo => {
-				self.settings.toggle_video = !self.settings.toggle_video;
+			Msg::SelectProfile(name) => {
+				if let Some(profile) = self.profiles.iter().find(|p| p.name == name) {
+					self.settings = profile.settings.clone();
+					self.profile = Some(name);
+					self.thumbnailer.send(thumbnailer::Request::CacheSize(
+						self.settings.thumbnail_cache_size,
+					));
+					return self.requeue();
+				}
+				false
+			}
+			Msg::ToggleKind(kind) => {
+				match self.settings.kind_rule_index(kind) {
+					Some(index) => self.update(Msg::UpdateRule(index, RuleChange::ToggleEnabled)),
+					// Without a catch-all rule the kind was off, so it is turned on with a new one.
+					None => {
+						self.settings.rules.push(Rule::new(kind, None));
+						true
+					}
+				}
+			}
+			Msg::TogglePageByFolder => {
+				self.settings.page_by_folder = !self.settings.page_by_folder;
 				true
 			}
-			Msg::ToggleVideoAutoplay => {
-				self.settings.toggle_video_autoplay = !self.settings.toggle_video_autoplay;
+			Msg::ToggleSoundtrackSync => {
+				self.settings.soundtrack_sync = !self.settings.soundtrack_sync;
 				true
 			}
+			Msg::UpdateRule(index, change) => {
+				if let Some(rule) = self.settings.rules.get_mut(index) {
+					rule.apply(change);
+					return true;
+				}
+				false
+			}
+			Msg::UnhideFiles(items) => {
+				// Items of sources removed since stay out.
+				let paths: HashSet<&String> = self.library.iter().map(|item| &item.path).collect();
+				let items: Vec<Item> = items
+					.into_iter()
+					.filter(|item| !paths.contains(&item.path))
+					.filter(|item| self.sources.iter().any(|s| s.id == item.source))
+					.collect();
+				self.restore_items(items);
+				true
+			}
+			Msg::Warn(message) => self.notify(Severity::Warning, message, None),
 			Msg::None => false,
 		}
 	}
 
+	#[allow(clippy::unnecessary_operation)]
 	fn view(&self) -> Html {
 		let circle_buttons_class = "text-center text-white bg-gray-500 text-opacity-25 bg-opacity-25 hover:text-opacity-80 hover:bg-opacity-90 transition duration-500 absolute top-0 rounded-full text-4xl mx-6 my-4 p-2 h-32 w-32 lg:h-16 lg:w-16 flex place-content-center place-items-center cursor-pointer select-none rotate-0 hover:rotate-180";
-		let warning_class = "absolute inset-x-32 md:inset-x-48 lg:inset-x-64 top-0 mt-4";
-		let config_audio_autoplay_delay_callback = self.link.callback(|value| {
-			if let ChangeData::Value(delay) = value {
-				if let Ok(d) = delay.parse::<u64>() {
-					return Msg::ConfigAudioAutoplayDelay(d);
-				}
-			}
-			Msg::None
-		});
-		let config_image_autoplay_delay_callback = self.link.callback(|value| {
-			if let ChangeData::Value(delay) = value {
-				if let Ok(d) = delay.parse::<u64>() {
-					return Msg::ConfigImageAutoplayDelay(d);
+		let notification_button_class = "text-center text-white bg-gray-500 text-opacity-25 bg-opacity-25 hover:text-opacity-80 hover:bg-opacity-90 transition duration-500 absolute right-0 rounded-full mx-6 p-2 h-32 w-32 lg:h-16 lg:w-16 flex place-content-center place-items-center cursor-pointer select-none";
+		let notifications_class = "absolute inset-x-32 md:inset-x-48 lg:inset-x-64 top-0 mt-4";
+		match &self.route {
+			AppRoute::About => {
+				return html! {
+					<About class="absolute inset-0" onclose=self.link.callback(|_| Msg::CloseView) />
 				}
 			}
-			Msg::None
-		});
-		let config_video_autoplay_delay_callback = self.link.callback(|value| {
-			if let ChangeData::Value(delay) = value {
-				if let Ok(d) = delay.parse::<u64>() {
-					return Msg::ConfigVideoAutoplayDelay(d);
+			AppRoute::Grid(folder) if !self.library.is_empty() => {
+				return html! {
+					<Grid
+						class="absolute inset-0"
+						folder=url::decode(folder)
+						items=self.library.clone()
+						onclose=self.link.callback(|_| Msg::CloseView)
+						onfolder=self.link.callback(Msg::OpenFolder)
+						onopen=self.link.callback(Msg::OpenItem)
+						thumbnail_offset=self.settings.thumbnail_offset
+					/>
 				}
 			}
-			Msg::None
-		});
-		match &self.files {
-			Some(files) => {
-				let file = &files[(self.index as usize).rem_euclid(files.len())];
+			_ => {}
+		}
+		match self.current_item() {
+			Some(item) => {
+				let mark = self.mark(item);
+				let last_move = self.moves.last().map(|(id, _)| *id);
+				let last_rename = self.renames.last().map(|(id, _)| *id);
 				let nav_buttons_class = "text-white bg-gray-700 text-opacity-0 bg-opacity-0 hover:text-opacity-100 hover:bg-opacity-70 transition duration-500 absolute inset-y-0 w-1/6 lg:w-1/12 text-9xl flex place-content-center place-items-center cursor-pointer select-none";
 				html! {
-					<div class="bg-black text-white absolute inset-0 flex place-content-center place-items-center">
-						<Media onended=self.on_ended_cb.clone() class="max-h-screen max-w-screen" file=file.clone() settings=self.settings />
+					<div class="bg-black text-white absolute inset-0 flex place-content-center place-items-center" ondragenter=self.link.callback(|_| Msg::Dragging(true))>
+						<Media onended=self.on_ended_cb.clone() class="max-h-screen max-w-screen" file=item.file.clone() held=self.held onerror=self.link.callback(Msg::Error) rule=self.media_rule(item) still=item.still />
+						{if self.soundtrack.is_some() {
+							html! {
+								<Soundtrack
+									active=!self.held && matches!(item.media_type(), Type::Image(_))
+									fade=self.settings.soundtrack_fade
+									ondurationchange=self.link.callback(Msg::SoundtrackDuration)
+									tracks=self.soundtrack_tracks()
+									volume=self.settings.soundtrack_volume
+								/>
+							}
+						} else {
+							html!()
+						}}
 						<div class=format!("{} {}", nav_buttons_class, "left-0") onclick=self.link.callback(|_| Msg::PreviousFile)>
 							{ Icon::new_sized(IconKind::ArrowLeft, 128) }
 						</div>
@@ -202,24 +2384,246 @@ impl Component for Home {
 						<div class=format!("{} {}", circle_buttons_class, "right-0") onclick=self.link.callback(|_| Msg::Quit)>
 							{ Icon::new_sized(IconKind::X, 64) }
 						</div>
+						<div class="absolute bottom-0 mb-12 px-4 py-2 rounded-full bg-gray-700 bg-opacity-25 hover:bg-opacity-70 transition duration-500 flex place-items-center select-none">
+							<div class="cursor-pointer mr-4 text-white" title="Hold slideshow (space)" onclick=self.link.callback(|_| Msg::ToggleHold)>
+								{ Icon::new_sized(if self.held { IconKind::Play } else { IconKind::SquareFill }, 32) }
+							</div>
+							<div class=format!("cursor-pointer mr-4 {}", if mark.favorite { "text-red-500" } else { "text-white text-opacity-50" })
+								onclick=self.link.callback(|_| Msg::ToggleFavorite)>
+								{ Icon::new_sized(if mark.favorite { IconKind::HeartFill } else { IconKind::Heart }, 32) }
+							</div>
+							{for (1..=MAX_RATING).map(|rating| {
+								let filled = rating <= mark.rating;
+								// Clicking the current rating again clears it.
+								let next = if rating == mark.rating { 0 } else { rating };
+								html! {
+									<div class=format!("cursor-pointer {}", if filled { "text-yellow-300" } else { "text-white text-opacity-50" })
+										onclick=self.link.callback(move |_| Msg::SetRating(next))>
+										{ Icon::new_sized(if filled { IconKind::StarFill } else { IconKind::Star }, 32) }
+									</div>
+								}
+							})}
+							<div class=format!("cursor-pointer ml-4 {}", if self.playlist_manager_visible { "text-white" } else { "text-white text-opacity-50" })
+								onclick=self.link.callback(|_| Msg::TogglePlaylistManager)>
+								{ Icon::new_sized(IconKind::ListOrdered, 32) }
+							</div>
+							<div class=format!("cursor-pointer ml-4 {}", if self.tag_editor_visible { "text-white" } else { "text-white text-opacity-50" })
+								onclick=self.link.callback(|_| Msg::ToggleTagEditor)>
+								{ Icon::new_sized(IconKind::Tag, 32) }
+							</div>
+							<div class=format!("cursor-pointer ml-4 {}", if self.filmstrip_visible { "text-white" } else { "text-white text-opacity-50" })
+								title="Filmstrip (S)" onclick=self.link.callback(|_| Msg::ToggleFilmstrip)>
+								{ Icon::new_sized(IconKind::Versions, 32) }
+							</div>
+							<div class="cursor-pointer ml-4 text-white text-opacity-50" title="Browse folders (G)" onclick=self.link.callback(|_| Msg::ShowGrid)>
+								{ Icon::new_sized(IconKind::Project, 32) }
+							</div>
+							<div class=format!("cursor-pointer ml-4 {}", if self.culling { "text-white" } else { "text-white text-opacity-50" })
+								title="Culling (C)" onclick=self.link.callback(|_| Msg::ToggleCulling)>
+								{ Icon::new_sized(IconKind::Checklist, 32) }
+							</div>
+							{ self.view_culling(item) }
+							<div class="cursor-pointer ml-4 text-white text-opacity-50" title="Rename files (N)" onclick=self.link.callback(|_| Msg::ShowRenamer(true))>
+								{ Icon::new_sized(IconKind::Pencil, 32) }
+							</div>
+							<div class="cursor-pointer ml-4 text-white text-opacity-50" title="Find duplicates (D)" onclick=self.link.callback(|_| Msg::ShowDuplicates(true))>
+								{ Icon::new_sized(IconKind::Copy, 32) }
+							</div>
+						</div>
+						{ self.view_filmstrip() }
+						{ self.view_scrubber() }
+						{if self.playlist_manager_visible {
+							html! {
+								<PlaylistManager
+									active=self.active_playlist.clone()
+									class="absolute left-0 bottom-0 ml-4 mb-32 w-4/5 md:w-3/5 lg:w-1/3"
+									onclose=self.link.callback(|_| Msg::TogglePlaylistManager)
+									oncreate=self.link.callback(Msg::CreatePlaylist)
+									ondelete=self.link.callback(Msg::DeletePlaylist)
+									onmove=self.link.callback(|(name, from, to)| Msg::MovePlaylistEntry(name, from, to))
+									onplay=self.link.callback(Msg::PlayPlaylist)
+									onremove=self.link.callback(|(name, index)| Msg::RemoveFromPlaylist(name, index))
+									onselect=self.link.callback(Msg::SelectPlaylist)
+									playlists=self.playlists.clone()
+								/>
+							}
+						} else {
+							html!()
+						}}
+						{if self.tag_editor_visible {
+							html! {
+								<TagEditor
+									class="absolute right-0 bottom-0 mr-4 mb-32 w-4/5 md:w-3/5 lg:w-1/3"
+									known=self.known_tags()
+									onadd=self.link.callback(Msg::AddTag)
+									onclose=self.link.callback(|_| Msg::ToggleTagEditor)
+									onplay=self.link.callback(|(selected, tag_match)| Msg::PlayTags(selected, tag_match))
+									onremove=self.link.callback(Msg::RemoveTag)
+									tags=self.item_tags(item)
+								/>
+							}
+						} else {
+							html!()
+						}}
 						<Menu
 							button_class=format!("{} {}", circle_buttons_class, "left-0")
-							settings=self.settings
-							toggle_audio_callback=self.link.callback(|_| Msg::ToggleAudio)
-							toggle_audio_autoplay_callback=self.link.callback(|_| Msg::ToggleAudioAutoplay)
-							toggle_image_callback=self.link.callback(|_| Msg::ToggleImage)
-							toggle_image_autoplay_callback=self.link.callback(|_| Msg::ToggleImageAutoplay)
-							toggle_video_callback=self.link.callback(|_| Msg::ToggleVideo)
-							toggle_video_autoplay_callback=self.link.callback(|_| Msg::ToggleVideoAutoplay)
-							config_audio_autoplay_delay_callback=config_audio_autoplay_delay_callback
-							config_image_autoplay_delay_callback=config_image_autoplay_delay_callback
-							config_video_autoplay_delay_callback=config_video_autoplay_delay_callback
+							export_playlist_callback=Some(self.link.callback(Msg::ExportPlaylist))
+							add_source_callback=Some(self.link.callback(|value| match value {
+								ChangeData::Files(files) => Msg::Index(files),
+								_ => Msg::None,
+							}))
+							remove_source_callback=self.link.callback(Msg::RemoveSource)
+							sources=self.sources.clone()
+							import_playlist_callback=Some(self.link.callback(|value| {
+								if let ChangeData::Files(files) = value {
+									if let Some(file) = files.get(0) {
+										return Msg::ImportPlaylist(file);
+									}
+								}
+								Msg::None
+							}))
+							settings=self.settings.clone()
+							soundtrack=self.soundtrack.clone()
+							soundtrack_options=self.soundtrack_options()
+							select_soundtrack_callback=self.link.callback(|value| match value {
+								ChangeData::Select(select) if !select.value().is_empty() => {
+									Msg::SelectSoundtrack(Some(select.value()))
+								}
+								_ => Msg::SelectSoundtrack(None),
+							})
+							config_soundtrack_fade_callback=self.link.callback(|value| {
+								if let ChangeData::Value(fade) = value {
+									if let Ok(f) = fade.parse::<u64>() {
+										return Msg::ConfigSoundtrackFade(f);
+									}
+								}
+								Msg::None
+							})
+							config_soundtrack_volume_callback=self.link.callback(|value| {
+								if let ChangeData::Value(volume) = value {
+									if let Ok(v) = volume.parse::<u8>() {
+										return Msg::ConfigSoundtrackVolume(v);
+									}
+								}
+								Msg::None
+							})
+							toggle_soundtrack_sync_callback=self.link.callback(|_| Msg::ToggleSoundtrackSync)
+							config_page_size_callback=self.link.callback(|value| {
+								if let ChangeData::Value(size) = value {
+									if let Ok(s) = size.parse::<u32>() {
+										return Msg::ConfigPageSize(s);
+									}
+								}
+								Msg::None
+							})
+							toggle_page_by_folder_callback=self.link.callback(|_| Msg::TogglePageByFolder)
+							config_thumbnail_cache_size_callback=self.link.callback(|value| {
+								if let ChangeData::Value(megabytes) = value {
+									if let Ok(m) = megabytes.parse::<u32>() {
+										return Msg::ConfigThumbnailCacheSize(m);
+									}
+								}
+								Msg::None
+							})
+							config_thumbnail_offset_callback=self.link.callback(|value| {
+								if let ChangeData::Value(offset) = value {
+									if let Ok(o) = offset.parse::<u64>() {
+										return Msg::ConfigThumbnailOffset(o);
+									}
+								}
+								Msg::None
+							})
+							copy_link_callback=Some(self.link.callback(|_| Msg::CopySettingsLink))
+							rule_callback=self.link.callback(|(index, change)| Msg::UpdateRule(index, change))
+							add_rule_callback=self.link.callback(Msg::AddRule)
+							remove_rule_callback=self.link.callback(Msg::RemoveRule)
+							profile=self.profile.clone()
+							profiles=self.profiles.iter().map(|p| p.name.clone()).collect::<Vec<_>>()
+							delete_profile_callback=self.link.callback(Msg::DeleteProfile)
+							export_profiles_callback=self.link.callback(|_| Msg::ExportProfiles)
+							import_profiles_callback=self.link.callback(|value| {
+								if let ChangeData::Files(files) = value {
+									if let Some(file) = files.get(0) {
+										return Msg::ImportProfiles(file);
+									}
+								}
+								Msg::None
+							})
+							save_profile_callback=self.link.callback(Msg::SaveProfile)
+							select_profile_callback=self.link.callback(Msg::SelectProfile)
+							visible=self.route == AppRoute::Settings
+							visible_callback=self.link.callback(Msg::ShowSettings)
 						/>
-						{if let Some(warning) = &self.warning_message {
-							html! {<Warning class=warning_class message=warning.clone() ondead=self.link.callback(|_| Msg::DropWarning) />}
+						{if self.rejects_visible {
+							html! {
+								<Rejects
+									busy=self.files_busy
+									class="absolute inset-0"
+									folders=self.top_folders(&self.rejects())
+									log=self.moves.iter().map(|(_, batch)| batch.len()).collect::<Vec<_>>()
+									onclose=self.link.callback(|_| Msg::ShowRejects(false))
+									ondelete=self.link.callback(Msg::DeleteFiles)
+									ongrant=self.link.callback(Msg::GrantFolder)
+									onkeep=self.link.callback(Msg::KeepRejects)
+									onmove=self.link.callback(Msg::MoveRejects)
+									onundo=self.link.callback(move |_| last_move.map_or(Msg::None, Msg::UndoMove))
+									rejects=self.rejects()
+									thumbnail_offset=self.settings.thumbnail_offset
+								/>
+							}
+						} else {
+							html!()
+						}}
+						{if self.renamer_visible {
+							let items = self.renamable();
+							html! {
+								<Renamer
+									busy=self.files_busy
+									class="absolute inset-0"
+									folders=self.top_folders(&items)
+									items=items
+									log=self.renames.iter().map(|(_, steps)| steps.iter().filter(|m| !rename::is_temporary(&m.to)).count()).collect::<Vec<_>>()
+									onapply=self.link.callback(Msg::Rename)
+									onclose=self.link.callback(|_| Msg::ShowRenamer(false))
+									ongrant=self.link.callback(Msg::GrantFolder)
+									onundo=self.link.callback(move |_| last_rename.map_or(Msg::None, Msg::UndoRename))
+									paths=self.library.iter().map(|item| item.path.clone()).collect::<Vec<_>>()
+								/>
+							}
 						} else {
 							html!()
 						}}
+						{if self.duplicates_visible {
+							let groups = self.duplicate_groups();
+							let items: Vec<Item> = groups.iter().flatten().flat_map(|group| group.items.clone()).collect();
+							html! {
+								<Duplicates
+									busy=self.files_busy
+									class="absolute inset-0"
+									folders=self.top_folders(&items)
+									groups=groups
+									oncancel=self.link.callback(|_| Msg::CancelDuplicates)
+									onclose=self.link.callback(|_| Msg::ShowDuplicates(false))
+									ondelete=self.link.callback(Msg::DeleteFiles)
+									ongrant=self.link.callback(Msg::GrantFolder)
+									onhide=self.link.callback(Msg::HideFiles)
+									onsearch=self.link.callback(|_| Msg::FindDuplicates)
+									progress=self.finding
+									thumbnail_offset=self.settings.thumbnail_offset
+								/>
+							}
+						} else {
+							html!()
+						}}
+						<Notifications
+							button_class=format!("{} {}", notification_button_class, "top-40 lg:top-24")
+							class=notifications_class
+							notifications=self.notifications.clone()
+							onclear=self.link.callback(|_| Msg::ClearNotifications)
+							ondismiss=self.link.callback(Msg::DismissNotification)
+						/>
+						{ self.view_indexing() }
+						{ self.view_drop_zone() }
 					</div>
 				}
 			}
@@ -227,72 +2631,103 @@ impl Component for Home {
 				let checkbox_class = "w-16 h-16 lg:w-12 lg:h-12 p-2 m-4 border-2 rounded-full flex place-content-center place-items-center cursor-pointer bg-white transition duration-200";
 				let unchecked = "text-white bg-opacity-0";
 				let checked = "text-black bg-opacity-100";
-				let settings = self.settings;
 				html! {
-					<div class="bg-gray-700 text-white absolute inset-0 flex flex-col place-content-center place-items-center select-none">
+					<div class="bg-gray-700 text-white absolute inset-0 flex flex-col place-content-center place-items-center select-none" ondragenter=self.link.callback(|_| Msg::Dragging(true))>
 						<h1 class="animate-bounce text-9xl m-2">{ "OmnivYou" }</h1>
+						{if let AppRoute::View(path) = &self.route {
+							html! {
+								<p class="text-3xl lg:text-xl m-2">
+									{ format!("Select the folder again to continue with '{}'", url::decode(path)) }
+								</p>
+							}
+						} else {
+							html!()
+						}}
 						<label for="directory" class="cursor-pointer border-2 rounded-lg text-7xl px-2 py-1 bg-white bg-opacity-0 hover:bg-opacity-100 hover:text-black transition duration-500 flex place-content-center place-items-center select-none">
 							<span class="pr-1 text-yellow-300">
 								{ Icon::new_sized(IconKind::FileDirectoryFill, 64) }
 							</span>
 							{ "Select a folder" }
 						</label>
-						<input ref=self.node_ref.clone() id="directory" type="file" value="" webkitdirectory="" class="hidden" onchange=self.link.callback(move |value| {
-							let mut result = Vec::new();
-							if let ChangeData::Files(files) = value {
-									let files = js_sys::try_iter(&files)
-											.unwrap()
-											.unwrap()
-											.map(|v| File::from(v.unwrap()))
-											.filter(|f| !f.name().starts_with('.'))
-											.filter(|f| !matches!(Type::from(f.type_()), Type::Invalid(_)))
-											.filter(|f| {
-												(settings.toggle_audio && matches!(Type::from(f.type_()), Type::Audio(_)))
-												|| (settings.toggle_image && matches!(Type::from(f.type_()), Type::Image(_)))
-												|| (settings.toggle_video && matches!(Type::from(f.type_()), Type::Video(_)))
-											});
-									result.extend(files);
-							}
-							Msg::IndexDirectory(result)
+						<p class="text-3xl lg:text-xl text-white text-opacity-50 m-2">{ "or drop files and folders anywhere" }</p>
+						<input ref=self.node_ref.clone() id="directory" type="file" value="" webkitdirectory="" class="hidden" onchange=self.link.callback(|value| match value {
+							ChangeData::Files(files) => Msg::Index(files),
+							_ => Msg::None,
 						}) />
 						<div class="p-2 flex place-content-center place-items-center">
 							<div
-								class=format!("{} {}", checkbox_class, if self.settings.toggle_video { checked } else { unchecked })
-								onclick=self.link.callback(|_| Msg::ToggleVideo)
+								class=format!("{} {}", checkbox_class, if self.settings.kind_rule(Kind::Video).is_some_and(|rule| rule.enabled) { checked } else { unchecked })
+								onclick=self.link.callback(|_| Msg::ToggleKind(Kind::Video))
 							>
 								{ Icon::new_sized(IconKind::DeviceCameraVideo, 32) }
 							</div>
 							<div
-								class=format!("{} {}", checkbox_class, if self.settings.toggle_audio { checked } else { unchecked })
-								onclick=self.link.callback(|_| Msg::ToggleAudio)
+								class=format!("{} {}", checkbox_class, if self.settings.kind_rule(Kind::Audio).is_some_and(|rule| rule.enabled) { checked } else { unchecked })
+								onclick=self.link.callback(|_| Msg::ToggleKind(Kind::Audio))
 							>
 								{ Icon::new_sized(IconKind::Unmute, 32) }
 							</div>
 							<div
-								class=format!("{} {}", checkbox_class, if self.settings.toggle_image { checked } else { unchecked })
-								onclick=self.link.callback(|_| Msg::ToggleImage)
+								class=format!("{} {}", checkbox_class, if self.settings.kind_rule(Kind::Image).is_some_and(|rule| rule.enabled) { checked } else { unchecked })
+								onclick=self.link.callback(|_| Msg::ToggleKind(Kind::Image))
 							>
 								{ Icon::new_sized(IconKind::Image, 32) }
 							</div>
+							<div
+								class=format!("{} {}", checkbox_class, if self.settings.filter_favorites { checked } else { unchecked })
+								onclick=self.link.callback(|_| Msg::ToggleFilterFavorites)
+							>
+								{ Icon::new_sized(IconKind::HeartFill, 32) }
+							</div>
+							<div
+								class=format!("{} {}", checkbox_class, if self.settings.filter_rating > 0 { checked } else { unchecked })
+								onclick=self.link.callback(|_| Msg::CycleFilterRating)
+							>
+								{ Icon::new_sized(IconKind::StarFill, 24) }
+								{if self.settings.filter_rating > 0 {
+									html! {<span class="text-xl">{ format!("≥{}", self.settings.filter_rating) }</span>}
+								} else {
+									html!()
+								}}
+							</div>
+						</div>
+						<div class="absolute bottom-0 mb-4 cursor-pointer text-white text-opacity-50 hover:text-opacity-100 transition flex place-items-center text-3xl lg:text-xl" onclick=self.link.callback(|_| Msg::ShowAbout)>
+							<span class="mr-2">{ Icon::new_sized(IconKind::Info, 32) }</span>
+							{ "About" }
 						</div>
 						<Menu
 							button_class=format!("{} {}", circle_buttons_class, "left-0")
-							settings=self.settings
-							toggle_audio_callback=self.link.callback(|_| Msg::ToggleAudio)
-							toggle_audio_autoplay_callback=self.link.callback(|_| Msg::ToggleAudioAutoplay)
-							toggle_image_callback=self.link.callback(|_| Msg::ToggleImage)
-							toggle_image_autoplay_callback=self.link.callback(|_| Msg::ToggleImageAutoplay)
-							toggle_video_callback=self.link.callback(|_| Msg::ToggleVideo)
-							toggle_video_autoplay_callback=self.link.callback(|_| Msg::ToggleVideoAutoplay)
-							config_audio_autoplay_delay_callback=config_audio_autoplay_delay_callback
-							config_image_autoplay_delay_callback=config_image_autoplay_delay_callback
-							config_video_autoplay_delay_callback=config_video_autoplay_delay_callback
+							settings=self.settings.clone()
+							copy_link_callback=Some(self.link.callback(|_| Msg::CopySettingsLink))
+							rule_callback=self.link.callback(|(index, change)| Msg::UpdateRule(index, change))
+							add_rule_callback=self.link.callback(Msg::AddRule)
+							remove_rule_callback=self.link.callback(Msg::RemoveRule)
+							profile=self.profile.clone()
+							profiles=self.profiles.iter().map(|p| p.name.clone()).collect::<Vec<_>>()
+							delete_profile_callback=self.link.callback(Msg::DeleteProfile)
+							export_profiles_callback=self.link.callback(|_| Msg::ExportProfiles)
+							import_profiles_callback=self.link.callback(|value| {
+								if let ChangeData::Files(files) = value {
+									if let Some(file) = files.get(0) {
+										return Msg::ImportProfiles(file);
+									}
+								}
+								Msg::None
+							})
+							save_profile_callback=self.link.callback(Msg::SaveProfile)
+							select_profile_callback=self.link.callback(Msg::SelectProfile)
+							visible=self.route == AppRoute::Settings
+							visible_callback=self.link.callback(Msg::ShowSettings)
 						/>
-						{if let Some(warning) = &self.warning_message {
-							html! {<Warning class=warning_class message=warning.clone() ondead=self.link.callback(|_| Msg::DropWarning) />}
-						} else {
-							html!()
-						}}
+						<Notifications
+							button_class=format!("{} {}", notification_button_class, "top-0 my-4")
+							class=notifications_class
+							notifications=self.notifications.clone()
+							onclear=self.link.callback(|_| Msg::ClearNotifications)
+							ondismiss=self.link.callback(Msg::DismissNotification)
+						/>
+						{ self.view_indexing() }
+						{ self.view_drop_zone() }
 					</div>
 				}
 			}
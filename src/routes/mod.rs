@@ -1,12 +1,35 @@
+use omnivyou_core::url;
 use yew_router::{switch::Permissive, Switch};
 
 pub mod home;
 
 /// App routes
-#[derive(Clone, Debug, Switch)]
+///
+/// Paths and folders are kept percent-encoded, as they appear in the address bar.
+#[derive(Clone, Debug, PartialEq, Switch)]
 pub enum AppRoute {
-	#[to = "/!"]
-	Home,
+	#[to = "/view/{*:path}"]
+	View(String),
+	#[to = "/grid/{*:folder}"]
+	Grid(String),
+	#[to = "/settings!"]
+	Settings,
+	#[to = "/about!"]
+	About,
 	#[to = "/page-not-found!"]
 	PageNotFound(Permissive<String>),
+	#[to = "/!"]
+	Home,
+}
+
+impl AppRoute {
+	/// Route showing the item at the given path.
+	pub fn view(path: &str) -> Self {
+		AppRoute::View(url::encode(path))
+	}
+
+	/// Route browsing the given folder, the root folder being the empty string.
+	pub fn grid(folder: &str) -> Self {
+		AppRoute::Grid(url::encode(folder))
+	}
 }
@@ -0,0 +1,139 @@
+use js_sys::{Array, Promise};
+use serde::{de::DeserializeOwned, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::web_sys::{window, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode};
+
+const DATABASE_NAME: &str = "omnivyou";
+const DATABASE_VERSION: u32 = 5;
+
+/// Favorites and star ratings keyed by `Item::key`.
+pub const MARKS_STORE: &str = "marks";
+
+/// Saved playlists keyed by name, holding relative paths of their entries.
+pub const PLAYLISTS_STORE: &str = "playlists";
+
+/// Settings profiles keyed by name.
+pub const PROFILES_STORE: &str = "profiles";
+
+/// Free-form tags keyed by `Item::key`.
+pub const TAGS_STORE: &str = "tags";
+
+/// Thumbnail blobs keyed by `Item::key`, stored as they are rather than as JSON.
+pub const THUMBNAILS_STORE: &str = "thumbnails";
+
+const STORES: &[&str] = &[
+	MARKS_STORE,
+	PLAYLISTS_STORE,
+	PROFILES_STORE,
+	TAGS_STORE,
+	THUMBNAILS_STORE,
+];
+
+/// Wraps an `IdbRequest` in a future resolving with the request result.
+fn settle(request: &IdbRequest) -> JsFuture {
+	let promise = Promise::new(&mut |resolve, reject| {
+		let success_request = request.clone();
+		let onsuccess = Closure::once_into_js(move || {
+			let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+			let _ = resolve.call1(&JsValue::NULL, &result);
+		});
+		let error_request = request.clone();
+		let onerror = Closure::once_into_js(move || {
+			let error = match error_request.error() {
+				Ok(Some(err)) => err.into(),
+				_ => JsValue::from_str("IndexedDB request failed"),
+			};
+			let _ = reject.call1(&JsValue::NULL, &error);
+		});
+		request.set_onsuccess(Some(onsuccess.unchecked_ref()));
+		request.set_onerror(Some(onerror.unchecked_ref()));
+	});
+	JsFuture::from(promise)
+}
+
+async fn open() -> Result<IdbDatabase, JsValue> {
+	let factory = window()
+		.ok_or_else(|| JsValue::from_str("No window available"))?
+		.indexed_db()?
+		.ok_or_else(|| JsValue::from_str("IndexedDB is not supported"))?;
+	let request = factory.open_with_u32(DATABASE_NAME, DATABASE_VERSION)?;
+	let upgrade_request = request.clone();
+	let onupgradeneeded = Closure::once_into_js(move || {
+		if let Ok(result) = upgrade_request.result() {
+			let database: IdbDatabase = result.unchecked_into();
+			let existing = database.object_store_names();
+			for store in STORES {
+				if !existing.contains(store) {
+					let _ = database.create_object_store(store);
+				}
+			}
+		}
+	});
+	request.set_onupgradeneeded(Some(onupgradeneeded.unchecked_ref()));
+	Ok(settle(&request).await?.unchecked_into())
+}
+
+async fn object_store(store: &str, mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+	open()
+		.await?
+		.transaction_with_str_and_mode(store, mode)?
+		.object_store(store)
+}
+
+fn decode<T: DeserializeOwned>(value: &JsValue) -> Option<T> {
+	value
+		.as_string()
+		.and_then(|s| serde_json::from_str(&s).ok())
+}
+
+pub async fn get_all<T: DeserializeOwned>(store: &str) -> Result<Vec<(String, T)>, JsValue> {
+	Ok(
+		get_all_values(store)
+			.await?
+			.into_iter()
+			.filter_map(|(k, v)| Some((k, decode(&v)?)))
+			.collect(),
+	)
+}
+
+pub async fn put<T: Serialize>(store: &str, key: &str, value: &T) -> Result<(), JsValue> {
+	let value = serde_json::to_string(value).map_err(|e| JsValue::from_str(&e.to_string()))?;
+	put_value(store, key, &JsValue::from_str(&value)).await
+}
+
+/// Value stored under a key as it is, `undefined` if there is none.
+pub async fn get_value(store: &str, key: &str) -> Result<JsValue, JsValue> {
+	let store = object_store(store, IdbTransactionMode::Readonly).await?;
+	settle(&store.get(&JsValue::from_str(key))?).await
+}
+
+/// Every value of a store as it is, along with its key.
+pub async fn get_all_values(store: &str) -> Result<Vec<(String, JsValue)>, JsValue> {
+	let store = object_store(store, IdbTransactionMode::Readonly).await?;
+	// Issue both requests before awaiting so they share the transaction.
+	let keys = settle(&store.get_all_keys()?);
+	let values = settle(&store.get_all()?);
+	let keys: Array = keys.await?.unchecked_into();
+	let values: Array = values.await?.unchecked_into();
+	Ok(
+		keys
+			.iter()
+			.zip(values.iter())
+			.filter_map(|(k, v)| Some((k.as_string()?, v)))
+			.collect(),
+	)
+}
+
+/// Stores a value as it is, which lets blobs be stored.
+pub async fn put_value(store: &str, key: &str, value: &JsValue) -> Result<(), JsValue> {
+	let store = object_store(store, IdbTransactionMode::Readwrite).await?;
+	settle(&store.put_with_key(value, &JsValue::from_str(key))?).await?;
+	Ok(())
+}
+
+pub async fn delete(store: &str, key: &str) -> Result<(), JsValue> {
+	let store = object_store(store, IdbTransactionMode::Readwrite).await?;
+	settle(&store.delete(&JsValue::from_str(key))?).await?;
+	Ok(())
+}
@@ -0,0 +1,251 @@
+use std::time::Duration;
+
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use js_sys::{Array, Date, Object, Promise, Reflect, Uint8Array};
+use omnivyou_core::{
+	duplicates::{self, FINGERPRINT_HEIGHT, FINGERPRINT_WIDTH},
+	media::Type,
+	thumbnail::{self, Entry, MAX_EDGE},
+};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use yew::web_sys::{
+	window, Blob, BlobPropertyBag, File, HtmlVideoElement, ImageBitmap, ImageEncodeOptions,
+	OffscreenCanvas, OffscreenCanvasRenderingContext2d, Url,
+};
+
+use crate::{
+	error::Error,
+	library::Item,
+	storage::{self, THUMBNAILS_STORE},
+};
+
+/// Bytes read from the start of audio files looking for cover art.
+const COVER_ART_BYTES: i32 = 8 * 1024 * 1024;
+
+/// Format thumbnails are encoded in. Browsers which cannot encode it fall back to PNG.
+const MIME: &str = "image/webp";
+
+const QUALITY: f64 = 0.8;
+
+/// Time videos get for every step of making a thumbnail, after which they count as failed rather
+/// than taking up one of the thumbnailer's slots for good.
+const VIDEO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the last use of a cached thumbnail is left as is, sparing a write on every showing.
+/// Eviction does not need it any finer.
+const USED_RESOLUTION: Duration = Duration::from_secs(60 * 60);
+
+/// Cache key of the thumbnail of an item. Video thumbnails also depend on the position of their
+/// frame, so changing it makes new ones rather than showing stale ones.
+pub fn key(item: &Item, offset: Duration) -> String {
+	match item.media_type() {
+		Type::Video(_) => format!("{}@{}", item.key(), offset.as_secs_f64()),
+		_ => item.key(),
+	}
+}
+
+fn context(canvas: &OffscreenCanvas) -> Result<OffscreenCanvasRenderingContext2d, Error> {
+	Ok(
+		canvas
+			.get_context("2d")?
+			.ok_or_else(|| JsValue::from_str("No 2d context available"))?
+			.unchecked_into(),
+	)
+}
+
+async fn bitmap(blob: &Blob) -> Result<ImageBitmap, Error> {
+	let bitmap = JsFuture::from(
+		window()
+			.ok_or_else(|| JsValue::from_str("No window available"))?
+			.create_image_bitmap_with_blob(blob)?,
+	)
+	.await?;
+	Ok(bitmap.unchecked_into())
+}
+
+/// Draws a picture of the given size onto a canvas fitting thumbnails, and encodes it.
+async fn scale(
+	width: u32,
+	height: u32,
+	draw: impl FnOnce(&OffscreenCanvasRenderingContext2d, f64, f64) -> Result<(), JsValue>,
+) -> Result<Blob, Error> {
+	let (width, height) = thumbnail::fit(width, height, MAX_EDGE);
+	let canvas = OffscreenCanvas::new(width, height)?;
+	let context = context(&canvas)?;
+	draw(&context, width as f64, height as f64)?;
+	let options = ImageEncodeOptions::new();
+	options.set_type(MIME);
+	options.set_quality(QUALITY);
+	let blob = JsFuture::from(canvas.convert_to_blob_with_options(&options)?).await?;
+	Ok(blob.unchecked_into())
+}
+
+async fn image(blob: &Blob) -> Result<Blob, Error> {
+	let bitmap = bitmap(blob).await?;
+	let thumbnail = scale(bitmap.width(), bitmap.height(), |context, w, h| {
+		context.draw_image_with_image_bitmap_and_dw_and_dh(&bitmap, 0.0, 0.0, w, h)
+	})
+	.await;
+	bitmap.close();
+	thumbnail
+}
+
+/// Waits for an event of a video, failing if the video cannot be loaded or takes too long instead.
+async fn video_event(video: &HtmlVideoElement, event: &'static str) -> Result<(), Error> {
+	let mut listeners = Vec::new();
+	let mut timeout = None;
+	let promise = Promise::new(&mut |resolve, reject| {
+		listeners.push(EventListener::once(video, event, move |_| {
+			let _ = resolve.call0(&JsValue::NULL);
+		}));
+		let on_timeout = reject.clone();
+		listeners.push(EventListener::once(video, "error", move |_| {
+			let _ = reject.call1(&JsValue::NULL, &JsValue::from_str("Failed to load video"));
+		}));
+		timeout = Some(Timeout::new(VIDEO_TIMEOUT.as_millis() as u32, move || {
+			let _ = on_timeout.call1(
+				&JsValue::NULL,
+				&JsValue::from_str("Timed out loading video"),
+			);
+		}));
+	});
+	JsFuture::from(promise).await?;
+	Ok(())
+}
+
+async fn video(blob: &Blob, offset: Duration) -> Result<Blob, Error> {
+	let video: HtmlVideoElement = window()
+		.and_then(|w| w.document())
+		.ok_or_else(|| JsValue::from_str("No document available"))?
+		.create_element("video")?
+		.unchecked_into();
+	video.set_muted(true);
+	video.set_preload("auto");
+	let src = Url::create_object_url_with_blob(blob)?;
+	video.set_src(&src);
+	let thumbnail = async {
+		video_event(&video, "loadeddata").await?;
+		// Videos shorter than the offset show their middle frame instead.
+		let duration = video.duration();
+		let position = match offset.as_secs_f64() {
+			offset if duration.is_finite() => offset.min(duration / 2.0),
+			offset => offset,
+		};
+		video.set_current_time(position);
+		video_event(&video, "seeked").await?;
+		scale(
+			video.video_width(),
+			video.video_height(),
+			|context, w, h| {
+				context.draw_image_with_html_video_element_and_dw_and_dh(&video, 0.0, 0.0, w, h)
+			},
+		)
+		.await
+	}
+	.await;
+	// Lets the browser release the decoder right away.
+	video.remove_attribute("src")?;
+	Url::revoke_object_url(&src)?;
+	thumbnail
+}
+
+async fn cover_art(file: &File) -> Result<Option<Blob>, Error> {
+	let start = file.slice_with_i32_and_i32(0, COVER_ART_BYTES)?;
+	let bytes = Uint8Array::new(&JsFuture::from(start.array_buffer()).await?).to_vec();
+	let art = match thumbnail::cover_art(&bytes) {
+		Some(art) => art,
+		None => return Ok(None),
+	};
+	let options = BlobPropertyBag::new();
+	options.set_type(&art.mime);
+	let parts = Array::of1(&Uint8Array::from(art.data));
+	let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)?;
+	image(&blob).await.map(Some)
+}
+
+async fn generate(item: &Item, offset: Duration) -> Result<Option<Blob>, Error> {
+	match item.media_type() {
+		Type::Audio(_) => cover_art(&item.file).await,
+		Type::Image(_) => image(&item.file).await.map(Some),
+		Type::Video(_) => video(&item.file, offset).await.map(Some),
+		Type::Invalid(_) => Ok(None),
+	}
+}
+
+/// Cache record of a thumbnail, noting when it was last shown. Items without any picture to show
+/// are recorded too, so that audio files are not searched for cover art again.
+fn record(blob: Option<&Blob>) -> Result<JsValue, JsValue> {
+	let record = Object::new();
+	Reflect::set(&record, &"blob".into(), &blob.into())?;
+	Reflect::set(
+		&record,
+		&"bytes".into(),
+		&blob.map_or(0.0, Blob::size).into(),
+	)?;
+	Reflect::set(&record, &"used".into(), &Date::now().into())?;
+	Ok(record.into())
+}
+
+fn number(record: &JsValue, field: &str) -> f64 {
+	Reflect::get(record, &field.into())
+		.ok()
+		.and_then(|value| value.as_f64())
+		.unwrap_or(0.0)
+}
+
+/// Thumbnail of an item, made once and taken from the cache afterwards. Items without any picture
+/// to show have none.
+pub async fn load(item: &Item, offset: Duration) -> Result<Option<Blob>, Error> {
+	let key = key(item, offset);
+	let cached = storage::get_value(THUMBNAILS_STORE, &key).await?;
+	let blob = if cached.is_undefined() {
+		generate(item, offset).await?
+	} else {
+		let blob = Reflect::get(&cached, &"blob".into())?.dyn_into().ok();
+		if Date::now() - number(&cached, "used") < USED_RESOLUTION.as_millis() as f64 {
+			return Ok(blob);
+		}
+		blob
+	};
+	storage::put_value(THUMBNAILS_STORE, &key, &record(blob.as_ref())?).await?;
+	Ok(blob)
+}
+
+/// Perceptual hash of the thumbnail of an item, which pictures looking alike share most bits of.
+/// Items without any picture to show have none.
+pub async fn fingerprint(item: &Item, offset: Duration) -> Result<Option<u64>, Error> {
+	let blob = match load(item, offset).await? {
+		Some(blob) => blob,
+		None => return Ok(None),
+	};
+	let bitmap = bitmap(&blob).await?;
+	let (width, height) = (FINGERPRINT_WIDTH as f64, FINGERPRINT_HEIGHT as f64);
+	let canvas = OffscreenCanvas::new(FINGERPRINT_WIDTH, FINGERPRINT_HEIGHT)?;
+	let context = context(&canvas)?;
+	let drawn = context.draw_image_with_image_bitmap_and_dw_and_dh(&bitmap, 0.0, 0.0, width, height);
+	bitmap.close();
+	drawn?;
+	let pixels = context.get_image_data(0.0, 0.0, width, height)?.data();
+	Ok(Some(duplicates::dhash(&pixels)))
+}
+
+/// Drops the least recently shown thumbnails until the cache fits in the given number of
+/// megabytes, returning how many were dropped.
+pub async fn evict(megabytes: u32) -> Result<usize, Error> {
+	let entries = storage::get_all_values(THUMBNAILS_STORE)
+		.await?
+		.into_iter()
+		.map(|(key, record)| Entry {
+			key,
+			bytes: number(&record, "bytes") as u64,
+			used: number(&record, "used"),
+		})
+		.collect();
+	let keys = thumbnail::evict(entries, megabytes as u64 * 1024 * 1024);
+	for key in &keys {
+		storage::delete(THUMBNAILS_STORE, key).await?;
+	}
+	Ok(keys.len())
+}
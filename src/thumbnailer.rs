@@ -0,0 +1,154 @@
+use std::{
+	collections::{HashMap, VecDeque},
+	time::Duration,
+};
+
+use log::{debug, warn};
+use wasm_bindgen_futures::spawn_local;
+use yew::{
+	agent::{Agent, AgentLink, Context, HandlerId},
+	web_sys::Blob,
+};
+
+use crate::{library::Item, thumbnail};
+
+/// Number of thumbnails made at once, each of which may decode a full size picture or video.
+const CONCURRENCY: usize = 3;
+
+/// Number of thumbnails loaded between checks of the cache size.
+const EVICTION_INTERVAL: usize = 200;
+
+pub enum Request {
+	/// Thumbnail of an item, taking video frames at the given offset.
+	Thumbnail { item: Item, offset: Duration },
+	/// Megabytes the thumbnail cache may take up. The cache is trimmed down to it right away.
+	CacheSize(u32),
+}
+
+pub enum Response {
+	/// Thumbnail with the given key, none if the item has no picture to show.
+	Ready { key: String, blob: Option<Blob> },
+}
+
+pub enum Msg {
+	Loaded { key: String, blob: Option<Blob> },
+}
+
+struct Pending {
+	key: String,
+	item: Item,
+	offset: Duration,
+}
+
+/// Makes and caches thumbnails in order of request, a few at a time so that grids of large files
+/// do not decode all of them at once. Requests of components which went away are dropped.
+pub struct Thumbnailer {
+	cache_size: Option<u32>,
+	link: AgentLink<Self>,
+	loaded: usize,
+	pending: VecDeque<Pending>,
+	running: usize,
+	/// Handlers waiting for each thumbnail which is pending or being made.
+	waiting: HashMap<String, Vec<HandlerId>>,
+}
+
+impl Thumbnailer {
+	fn evict(&mut self) {
+		self.loaded = 0;
+		if let Some(megabytes) = self.cache_size {
+			spawn_local(async move {
+				match thumbnail::evict(megabytes).await {
+					Ok(0) => {}
+					Ok(count) => debug!("Dropped {} thumbnails from the cache", count),
+					Err(err) => warn!("Failed to trim the thumbnail cache: {}", err),
+				}
+			});
+		}
+	}
+
+	fn run(&mut self) {
+		while self.running < CONCURRENCY {
+			let Pending { key, item, offset } = match self.pending.pop_front() {
+				Some(pending) => pending,
+				None => return,
+			};
+			if !self.waiting.contains_key(&key) {
+				continue;
+			}
+			self.running += 1;
+			let callback = self.link.callback(|(key, blob)| Msg::Loaded { key, blob });
+			spawn_local(async move {
+				let blob = thumbnail::load(&item, offset).await.unwrap_or_else(|err| {
+					debug!("No thumbnail for '{}': {}", item.path, err);
+					None
+				});
+				callback.emit((key, blob));
+			});
+		}
+	}
+}
+
+impl Agent for Thumbnailer {
+	type Reach = Context<Self>;
+	type Message = Msg;
+	type Input = Request;
+	type Output = Response;
+
+	fn create(link: AgentLink<Self>) -> Self {
+		Self {
+			cache_size: None,
+			link,
+			loaded: 0,
+			pending: VecDeque::new(),
+			running: 0,
+			waiting: HashMap::new(),
+		}
+	}
+
+	fn update(&mut self, msg: Self::Message) {
+		match msg {
+			Msg::Loaded { key, blob } => {
+				self.running -= 1;
+				for handler in self.waiting.remove(&key).unwrap_or_default() {
+					self.link.respond(
+						handler,
+						Response::Ready {
+							key: key.clone(),
+							blob: blob.clone(),
+						},
+					);
+				}
+				self.loaded += 1;
+				if self.loaded >= EVICTION_INTERVAL {
+					self.evict();
+				}
+				self.run();
+			}
+		}
+	}
+
+	fn handle_input(&mut self, msg: Self::Input, handler: HandlerId) {
+		match msg {
+			Request::Thumbnail { item, offset } => {
+				let key = thumbnail::key(&item, offset);
+				let handlers = self.waiting.entry(key.clone()).or_default();
+				if handlers.is_empty() {
+					self.pending.push_back(Pending { key, item, offset });
+				}
+				handlers.push(handler);
+				self.run();
+			}
+			Request::CacheSize(megabytes) => {
+				self.cache_size = Some(megabytes);
+				self.evict();
+			}
+		}
+	}
+
+	fn disconnected(&mut self, id: HandlerId) {
+		self.waiting.retain(|_, handlers| {
+			handlers.retain(|&handler| handler != id);
+			!handlers.is_empty()
+		});
+	}
+}